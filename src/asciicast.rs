@@ -0,0 +1,193 @@
+//! Session recording in asciicast v2 format
+//!
+//! Every byte a `Terminal` writes is appended as a timestamped output event,
+//! so recordings work for any subcommand and play back with `asciinema play`.
+//! See <https://docs.asciinema.org/manual/asciicast/v2/>.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Process-wide recorder; terminals are created and dropped per subcommand
+/// (and per tree in infinite bonsai), but a session is one recording.
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+/// Why recording stopped early, kept until the terminal is restored and the
+/// message can be seen
+static FAILURE: Mutex<Option<io::Error>> = Mutex::new(None);
+
+struct Recorder {
+    out: Box<dyn Write + Send>,
+    started: Instant,
+    timestamp: u64,
+    header_written: bool,
+}
+
+impl Recorder {
+    fn new(out: Box<dyn Write + Send>) -> Self {
+        Self {
+            out,
+            started: Instant::now(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            header_written: false,
+        }
+    }
+
+    /// The header needs the terminal size, so it is written with the first event
+    fn ensure_header(&mut self, width: u16, height: u16) -> io::Result<()> {
+        if self.header_written {
+            return Ok(());
+        }
+        let header = serde_json::json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+            "timestamp": self.timestamp,
+            "env": {
+                "TERM": std::env::var("TERM").unwrap_or_default(),
+                "SHELL": std::env::var("SHELL").unwrap_or_default(),
+            },
+        });
+        writeln!(self.out, "{header}")?;
+        self.header_written = true;
+        Ok(())
+    }
+
+    fn event(&mut self, width: u16, height: u16, kind: &str, data: &str) -> io::Result<()> {
+        self.ensure_header(width, height)?;
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let line = serde_json::to_string(&(round_micros(elapsed), kind, data))?;
+        writeln!(self.out, "{line}")?;
+        // Flush per event so a killed session still leaves a playable file
+        self.out.flush()
+    }
+}
+
+fn round_micros(seconds: f64) -> f64 {
+    (seconds * 1_000_000.0).round() / 1_000_000.0
+}
+
+/// Start recording all terminal output to `path`
+pub fn start(path: &Path) -> io::Result<()> {
+    let file = File::create(path)?;
+    install(Box::new(BufWriter::new(file)));
+    Ok(())
+}
+
+fn install(out: Box<dyn Write + Send>) {
+    if let Ok(mut recorder) = RECORDER.lock() {
+        *recorder = Some(Recorder::new(out));
+    }
+}
+
+fn with_recorder(f: impl FnOnce(&mut Recorder) -> io::Result<()>) {
+    if let Ok(mut guard) = RECORDER.lock() {
+        if let Some(recorder) = guard.as_mut() {
+            if let Err(e) = f(recorder) {
+                // Stop rather than fail the session or repeat the error every frame
+                if let Ok(mut failure) = FAILURE.lock() {
+                    failure.get_or_insert(e);
+                }
+                *guard = None;
+            }
+        }
+    }
+}
+
+/// Record bytes written to a terminal of the given size
+pub fn output(width: u16, height: u16, data: &[u8]) {
+    if data.is_empty() {
+        return;
+    }
+    with_recorder(|recorder| recorder.event(width, height, "o", &String::from_utf8_lossy(data)));
+}
+
+/// Record a terminal resize
+pub fn resize(width: u16, height: u16) {
+    with_recorder(|recorder| recorder.event(width, height, "r", &format!("{width}x{height}")));
+}
+
+/// Report a recording that stopped early; call once the terminal is restored
+pub fn report() {
+    if let Some(e) = take_failure() {
+        eprintln!("Warning: Recording stopped: {e}");
+    }
+}
+
+fn take_failure() -> Option<io::Error> {
+    FAILURE.lock().ok()?.take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{install, output, resize, take_failure, RECORDER};
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().expect("buffer lock").extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct FullDisk;
+
+    impl Write for FullDisk {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::StorageFull.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn events_follow_a_single_header_line() {
+        let buffer = SharedBuffer::default();
+        install(Box::new(buffer.clone()));
+
+        output(80, 24, b"\x1b[1;1Hhi \"there\"");
+        output(80, 24, b"");
+        resize(100, 30);
+        *RECORDER.lock().expect("recorder lock") = None;
+
+        let text = String::from_utf8(buffer.0.lock().expect("buffer lock").clone()).unwrap();
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("json line"))
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 80);
+        assert_eq!(lines[0]["height"], 24);
+        assert_eq!(lines[1][1], "o");
+        assert_eq!(lines[1][2], "\u{1b}[1;1Hhi \"there\"");
+        assert_eq!(lines[2][1], "r");
+        assert_eq!(lines[2][2], "100x30");
+        assert!(lines[2][0].as_f64().unwrap() >= lines[1][0].as_f64().unwrap());
+        assert!(take_failure().is_none());
+
+        // A failed write stops the recording and keeps the error for later
+        install(Box::new(FullDisk));
+        output(80, 24, b"lost");
+        assert!(RECORDER.lock().expect("recorder lock").is_none());
+        assert_eq!(
+            take_failure().map(|e| e.kind()),
+            Some(io::ErrorKind::StorageFull)
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use crate::config::{BonsaiConfig, BranchType, Counters};
 use crate::help::{show_help_spec_modal, HelpSpec};
 use crate::terminal::{colors, Terminal};
+use crate::unicode;
 use crossterm::event::KeyCode;
 use crossterm::style::Color;
 use rand::prelude::*;
@@ -232,7 +233,7 @@ fn draw_base(term: &mut Terminal, base_type: u8) {
 fn draw_message(term: &mut Terminal, message: &str) {
     let (width, height) = term.size();
     let max_box_width = (width as f32 * 0.25) as usize;
-    let box_width = max_box_width.max(20).min(message.len() + 4);
+    let box_width = max_box_width.max(20).min(unicode::str_width(message) + 4);
 
     // Word wrap the message
     let wrapped = word_wrap(message, box_width - 4);
@@ -287,7 +288,7 @@ fn word_wrap(text: &str, width: usize) -> Vec<String> {
     for word in text.split_whitespace() {
         if current_line.is_empty() {
             current_line = word.to_string();
-        } else if current_line.len() + 1 + word.len() <= width {
+        } else if unicode::str_width(&current_line) + 1 + unicode::str_width(word) <= width {
             current_line.push(' ');
             current_line.push_str(word);
         } else {
@@ -576,3 +577,44 @@ fn choose_char_and_color(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::config::BonsaiConfig;
+    use crate::terminal::{assert_snapshot, run_headless};
+
+    fn seeded_config(seed: u64) -> BonsaiConfig {
+        BonsaiConfig {
+            live: false,
+            infinite: false,
+            print: false,
+            time_step: 0.03,
+            time_wait: 4.0,
+            life_start: 32,
+            multiplier: 5,
+            seed: Some(seed),
+            base_type: 1,
+            leaves: vec!["&".to_string()],
+            message: Some("golden frame".to_string()),
+        }
+    }
+
+    #[test]
+    fn seeded_tree_matches_golden_frame() {
+        let (result, frame) = run_headless(60, 24, [], || super::run(seeded_config(42)));
+        result.expect("headless bonsai");
+        assert_snapshot("bonsai_seed_42", &frame.text());
+    }
+
+    #[test]
+    fn print_mode_renders_offscreen() {
+        let config = BonsaiConfig {
+            print: true,
+            ..seeded_config(42)
+        };
+        let (result, frame) = run_headless(60, 24, [], || super::run(config));
+        result.expect("headless bonsai print");
+        assert_eq!(frame.presented, 1);
+        assert!(frame.text().contains('&'));
+    }
+}
@@ -0,0 +1,334 @@
+//! Sub-cell pixel canvas
+//!
+//! Plots pixels at braille (2x4), quadrant (2x2) or half-block (1x2)
+//! resolution per terminal cell and flushes them to a `Terminal` as the
+//! matching Unicode characters. Each pixel holds a small value (0 = off) that
+//! the flush maps to a color, so callers can layer intensities.
+
+use crate::terminal::Terminal;
+use crossterm::style::Color;
+
+/// Base code point of the braille patterns block (U+2800)
+const BRAILLE_BASE: u32 = 0x2800;
+
+/// Braille dot bits indexed `[row][col]`
+const BRAILLE_BITS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Quadrant characters indexed by upper-left=1, upper-right=2, lower-left=4,
+/// lower-right=8
+const QUADRANTS: [char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+];
+
+/// How a cell is divided into pixels
+#[cfg_attr(not(test), allow(dead_code))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CanvasMode {
+    /// 2x4 braille dots
+    Braille,
+    /// 2x2 quadrant blocks
+    Quadrant,
+    /// 1x2 half blocks
+    HalfBlock,
+}
+
+impl CanvasMode {
+    /// Pixels per cell as (columns, rows)
+    pub const fn dots(self) -> (usize, usize) {
+        match self {
+            CanvasMode::Braille => (2, 4),
+            CanvasMode::Quadrant => (2, 2),
+            CanvasMode::HalfBlock => (1, 2),
+        }
+    }
+
+    /// Character for a cell whose pixels are `lit(col, row)`
+    fn glyph(self, lit: impl Fn(usize, usize) -> bool) -> char {
+        match self {
+            CanvasMode::Braille => {
+                let mut code = 0u32;
+                for (row, bits) in BRAILLE_BITS.iter().enumerate() {
+                    for (col, bit) in bits.iter().enumerate() {
+                        if lit(col, row) {
+                            code |= *bit as u32;
+                        }
+                    }
+                }
+                char::from_u32(BRAILLE_BASE + code).unwrap_or(' ')
+            }
+            CanvasMode::Quadrant => {
+                let index = lit(0, 0) as usize
+                    | (lit(1, 0) as usize) << 1
+                    | (lit(0, 1) as usize) << 2
+                    | (lit(1, 1) as usize) << 3;
+                QUADRANTS[index]
+            }
+            CanvasMode::HalfBlock => match (lit(0, 0), lit(0, 1)) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            },
+        }
+    }
+}
+
+/// Pixel buffer covering a block of terminal cells
+pub struct Canvas {
+    mode: CanvasMode,
+    cols: usize,
+    rows: usize,
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+    /// Per-cell color overriding the value-based color at flush
+    colors: Vec<Option<(Color, bool)>>,
+}
+
+impl Canvas {
+    /// Create a canvas covering `cols` x `rows` terminal cells
+    pub fn new(mode: CanvasMode, cols: u16, rows: u16) -> Self {
+        let mut canvas = Self {
+            mode,
+            cols: 0,
+            rows: 0,
+            width: 0,
+            height: 0,
+            pixels: Vec::new(),
+            colors: Vec::new(),
+        };
+        canvas.resize(cols, rows);
+        canvas
+    }
+
+    /// Resize to `cols` x `rows` cells, clearing the canvas if the size changed
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        let (cols, rows) = (cols as usize, rows as usize);
+        if cols == self.cols && rows == self.rows {
+            return;
+        }
+        let (dx, dy) = self.mode.dots();
+        self.cols = cols;
+        self.rows = rows;
+        self.width = cols * dx;
+        self.height = rows * dy;
+        self.pixels = vec![0; self.width * self.height];
+        self.colors = vec![None; cols * rows];
+    }
+
+    /// Size in pixels as (width, height)
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Turn every pixel off and drop per-cell colors
+    pub fn clear(&mut self) {
+        self.pixels.fill(0);
+        self.colors.fill(None);
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        Some(y as usize * self.width + x as usize)
+    }
+
+    /// Value of a pixel; 0 when off or out of bounds
+    pub fn get(&self, x: i32, y: i32) -> u8 {
+        self.index(x, y).map_or(0, |i| self.pixels[i])
+    }
+
+    /// Set a pixel to `value`. Out-of-bounds pixels are ignored.
+    pub fn set(&mut self, x: i32, y: i32, value: u8) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = value;
+        }
+    }
+
+    /// Turn a pixel off
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn unset(&mut self, x: i32, y: i32) {
+        self.set(x, y, 0);
+    }
+
+    /// Bresenham line from (x0, y0) to (x1, y1), endpoints included
+    pub fn line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, value: u8) {
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        let (mut x, mut y) = (x0, y0);
+
+        loop {
+            self.set(x, y, value);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Midpoint circle outline of `radius` pixels around (cx, cy)
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn circle(&mut self, cx: i32, cy: i32, radius: i32, value: u8) {
+        if radius <= 0 {
+            self.set(cx, cy, value);
+            return;
+        }
+        let (mut x, mut y) = (radius, 0);
+        let mut err = 1 - radius;
+        while x >= y {
+            for (px, py) in [
+                (x, y),
+                (y, x),
+                (-y, x),
+                (-x, y),
+                (-x, -y),
+                (-y, -x),
+                (y, -x),
+                (x, -y),
+            ] {
+                self.set(cx + px, cy + py, value);
+            }
+            y += 1;
+            if err < 0 {
+                err += 2 * y + 1;
+            } else {
+                x -= 1;
+                err += 2 * (y - x) + 1;
+            }
+        }
+    }
+
+    /// Closed polygon outline through `points`
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn polygon(&mut self, points: &[(i32, i32)], value: u8) {
+        for (i, &(x0, y0)) in points.iter().enumerate() {
+            let (x1, y1) = points[(i + 1) % points.len()];
+            self.line(x0, y0, x1, y1, value);
+        }
+    }
+
+    /// Color a whole cell regardless of its pixel values
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn set_cell_color(&mut self, col: usize, row: usize, color: Color, bold: bool) {
+        if col < self.cols && row < self.rows {
+            self.colors[row * self.cols + col] = Some((color, bold));
+        }
+    }
+
+    /// Draw every cell with at least one pixel on, at the terminal origin.
+    /// `color` maps the cell's highest pixel value to a color and bold flag.
+    pub fn flush(&self, term: &mut Terminal, color: impl Fn(u8) -> (Color, bool)) {
+        self.flush_at(term, 0, 0, color);
+    }
+
+    /// Like `flush`, with the canvas' top-left cell at (x, y)
+    pub fn flush_at(
+        &self,
+        term: &mut Terminal,
+        x: i32,
+        y: i32,
+        color: impl Fn(u8) -> (Color, bool),
+    ) {
+        let (dx, dy) = self.mode.dots();
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let pixel =
+                    |x: usize, y: usize| self.pixels[(row * dy + y) * self.width + col * dx + x];
+                let max = (0..dy)
+                    .flat_map(|y| (0..dx).map(move |x| (x, y)))
+                    .map(|(x, y)| pixel(x, y))
+                    .max()
+                    .unwrap_or(0);
+                if max == 0 {
+                    continue;
+                }
+
+                let ch = self.mode.glyph(|x, y| pixel(x, y) > 0);
+                let (fg, bold) = self.colors[row * self.cols + col].unwrap_or_else(|| color(max));
+                term.set(x + col as i32, y + row as i32, ch, Some(fg), bold);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Canvas, CanvasMode};
+    use crate::terminal::{Attrs, Terminal};
+    use crossterm::style::Color;
+
+    fn flushed(canvas: &Canvas, cols: u16, rows: u16) -> String {
+        let mut term = Terminal::offscreen(cols, rows);
+        canvas.flush(&mut term, |_| (Color::White, false));
+        term.back_buffer()
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.ch).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn braille_lines_and_polygons_encode_dots() {
+        let mut canvas = Canvas::new(CanvasMode::Braille, 2, 1);
+        assert_eq!(canvas.size(), (4, 4));
+        canvas.line(0, 0, 3, 0, 1);
+        assert_eq!(flushed(&canvas, 2, 1), "⠉⠉");
+
+        canvas.clear();
+        canvas.polygon(&[(0, 0), (1, 0), (1, 3), (0, 3)], 1);
+        assert_eq!(flushed(&canvas, 2, 1), "⣿ ");
+        canvas.unset(0, 3);
+        assert_eq!(flushed(&canvas, 2, 1), "⢿ ");
+
+        // Out of bounds is clipped rather than panicking
+        canvas.line(-5, -5, 10, 10, 1);
+        assert_eq!(canvas.get(10, 10), 0);
+    }
+
+    #[test]
+    fn half_block_and_quadrant_modes_pick_block_characters() {
+        let mut half = Canvas::new(CanvasMode::HalfBlock, 3, 1);
+        half.set(0, 0, 1);
+        half.set(1, 1, 1);
+        half.line(2, 0, 2, 1, 1);
+        assert_eq!(flushed(&half, 3, 1), "▀▄█");
+
+        let mut quad = Canvas::new(CanvasMode::Quadrant, 2, 1);
+        quad.set(0, 0, 1);
+        quad.set(3, 1, 1);
+        quad.set(2, 0, 1);
+        assert_eq!(flushed(&quad, 2, 1), "▘▚");
+    }
+
+    #[test]
+    fn circles_are_symmetric_and_cells_take_value_or_override_colors() {
+        let mut canvas = Canvas::new(CanvasMode::Braille, 6, 3);
+        canvas.circle(5, 5, 4, 2);
+        for (x, y) in [(9, 5), (1, 5), (5, 9), (5, 1)] {
+            assert_eq!(canvas.get(x, y), 2);
+        }
+        assert_eq!(canvas.get(5, 5), 0);
+
+        canvas.set(11, 11, 7);
+        canvas.set_cell_color(2, 0, Color::Red, true);
+        let mut term = Terminal::offscreen(6, 3);
+        canvas.flush(&mut term, |value| (Color::AnsiValue(value), false));
+        let cells = term.back_buffer();
+        assert_eq!(cells[0][2].fg, Some(Color::Red));
+        assert!(cells[0][2].attrs.contains(Attrs::BOLD));
+        assert_eq!(cells[1][4].fg, Some(Color::AnsiValue(2)));
+        assert_eq!(cells[2][5].fg, Some(Color::AnsiValue(7)));
+    }
+}
@@ -1,5 +1,126 @@
+use crate::settings::{SchemeSettings, Settings};
 use crossterm::event::{KeyCode, KeyModifiers};
 use crossterm::style::Color;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::LazyLock;
+
+/// Number of built-in schemes selectable with Shift+0-9
+pub const BUILTIN_SCHEMES: u8 = 10;
+
+/// Default intensity at which custom scheme text turns bold (matches built-ins)
+const DEFAULT_BOLD_THRESHOLD: f32 = 1.0;
+
+/// User-defined schemes from `[[colors.scheme]]` followed by imported themes,
+/// numbered after the built-ins
+static CUSTOM_SCHEMES: LazyLock<Vec<CustomScheme>> = LazyLock::new(|| {
+    let mut entries = Settings::load().colors.schemes;
+    entries.extend(Settings::imported_schemes());
+    custom_schemes_from_settings(&entries)
+});
+
+/// Scheme most recently selected through a `ColorState`; the terminal fills
+/// its background on every present
+static ACTIVE_SCHEME: AtomicU8 = AtomicU8::new(0);
+
+/// A user-defined color scheme loaded from config.toml
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomScheme {
+    pub name: String,
+    pub stops: Vec<(u8, u8, u8)>,
+    pub bold_threshold: f32,
+    pub background: Option<(u8, u8, u8)>,
+}
+
+impl CustomScheme {
+    /// Validate a config entry, reporting the first invalid field
+    pub fn from_settings(settings: &SchemeSettings) -> Result<Self, String> {
+        if settings.name.trim().is_empty() {
+            return Err("scheme name must not be empty".to_string());
+        }
+        if settings.stops.is_empty() {
+            return Err(format!(
+                "scheme '{}' needs at least one stop",
+                settings.name
+            ));
+        }
+        let stops = settings
+            .stops
+            .iter()
+            .map(|stop| {
+                parse_hex_color(stop)
+                    .ok_or_else(|| format!("scheme '{}': invalid color '{stop}'", settings.name))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let background = match &settings.background {
+            Some(value) => Some(parse_hex_color(value).ok_or_else(|| {
+                format!("scheme '{}': invalid background '{value}'", settings.name)
+            })?),
+            None => None,
+        };
+        let bold_threshold = settings.bold_threshold.unwrap_or(DEFAULT_BOLD_THRESHOLD);
+        if !bold_threshold.is_finite() {
+            return Err(format!(
+                "scheme '{}': invalid bold_threshold",
+                settings.name
+            ));
+        }
+
+        Ok(Self {
+            name: settings.name.trim().to_string(),
+            stops,
+            bold_threshold,
+            background,
+        })
+    }
+}
+
+/// Parse `#rrggbb` (leading `#` optional) into RGB components
+pub fn parse_hex_color(value: &str) -> Option<(u8, u8, u8)> {
+    let hex = value.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn custom_schemes_from_settings(entries: &[SchemeSettings]) -> Vec<CustomScheme> {
+    let capacity = (u8::MAX - BUILTIN_SCHEMES) as usize + 1;
+    entries
+        .iter()
+        .filter_map(|entry| match CustomScheme::from_settings(entry) {
+            Ok(scheme) => Some(scheme),
+            Err(e) => {
+                eprintln!("Warning: Ignoring color scheme in config: {e}");
+                None
+            }
+        })
+        .take(capacity)
+        .collect()
+}
+
+fn custom_scheme(scheme: u8) -> Option<&'static CustomScheme> {
+    scheme
+        .checked_sub(BUILTIN_SCHEMES)
+        .and_then(|i| CUSTOM_SCHEMES.get(i as usize))
+}
+
+/// Total number of selectable schemes, built-in and user-defined
+pub fn scheme_count() -> u16 {
+    BUILTIN_SCHEMES as u16 + CUSTOM_SCHEMES.len() as u16
+}
+
+/// Background fill for a scheme, if it defines one
+pub fn scheme_background(scheme: u8) -> Option<Color> {
+    custom_scheme(scheme)
+        .and_then(|custom| custom.background)
+        .map(|(r, g, b)| Color::Rgb { r, g, b })
+}
+
+/// Background fill of the active scheme, if it defines one
+pub fn active_background() -> Option<Color> {
+    scheme_background(ACTIVE_SCHEME.load(Ordering::Relaxed))
+}
 
 /// Shared color scheme state
 #[derive(Clone, Copy)]
@@ -9,6 +130,7 @@ pub struct ColorState {
 
 impl ColorState {
     pub fn new(default_scheme: u8) -> Self {
+        ACTIVE_SCHEME.store(default_scheme, Ordering::Relaxed);
         Self {
             scheme: default_scheme,
         }
@@ -30,9 +152,11 @@ impl ColorState {
             KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) && c.is_ascii_digit() => {
                 c as u8 - b'0'
             }
+            KeyCode::Char('~') => next_scheme(self.scheme, scheme_count()), // Shift+`: cycle all
             _ => return false,
         };
         self.scheme = scheme;
+        ACTIVE_SCHEME.store(scheme, Ordering::Relaxed);
         true
     }
 
@@ -42,6 +166,9 @@ impl ColorState {
     }
 
     pub fn name(&self) -> &'static str {
+        if let Some(custom) = custom_scheme(self.scheme) {
+            return &custom.name;
+        }
         match self.scheme {
             0 => "Matrix",
             1 => "Fire",
@@ -58,86 +185,132 @@ impl ColorState {
     }
 }
 
+fn next_scheme(current: u8, count: u16) -> u8 {
+    ((current as u16 + 1) % count.max(1)) as u8
+}
+
+/// RGB gradient stops for each built-in scheme, from dimmest to brightest.
+/// The terminal down-samples these when truecolor is unavailable.
+const SCHEME_STOPS: [&[(u8, u8, u8)]; 10] = [
+    // 0: Matrix
+    &[(0, 70, 10), (0, 150, 30), (20, 210, 60), (130, 255, 140)],
+    // 1: Fire
+    &[(110, 0, 0), (210, 30, 0), (250, 130, 0), (255, 235, 90)],
+    // 2: Ice
+    &[(0, 20, 130), (40, 90, 230), (60, 200, 240), (190, 250, 255)],
+    // 3: Pink
+    &[
+        (110, 0, 100),
+        (200, 30, 170),
+        (240, 80, 210),
+        (255, 160, 240),
+    ],
+    // 4: Gold
+    &[(130, 90, 0), (210, 160, 0), (250, 210, 40), (255, 245, 150)],
+    // 5: Electric
+    &[
+        (0, 110, 120),
+        (0, 190, 210),
+        (40, 235, 255),
+        (180, 255, 255),
+    ],
+    // 6: Lava
+    &[(110, 0, 10), (210, 20, 20), (225, 40, 150), (255, 100, 90)],
+    // 7: Mono
+    &[
+        (100, 100, 100),
+        (190, 190, 190),
+        (235, 235, 235),
+        (255, 255, 255),
+    ],
+    // 8: Rainbow
+    &[(240, 40, 40), (250, 220, 0), (40, 220, 60), (0, 220, 240)],
+    // 9: Neon
+    &[(20, 0, 140), (70, 70, 255), (210, 40, 255), (255, 110, 255)],
+];
+
+/// Sample a gradient at position `t` (0.0-1.0), interpolating between
+/// evenly spaced RGB stops
+pub fn gradient_color(stops: &[(u8, u8, u8)], t: f32) -> Color {
+    let Some(&last) = stops.last() else {
+        return Color::Reset;
+    };
+    let t = if t.is_finite() {
+        t.clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let pos = t * (stops.len() - 1) as f32;
+    let idx = pos.floor() as usize;
+    if idx + 1 >= stops.len() {
+        return Color::Rgb {
+            r: last.0,
+            g: last.1,
+            b: last.2,
+        };
+    }
+
+    let frac = pos - idx as f32;
+    let (a, b) = (stops[idx], stops[idx + 1]);
+    let lerp = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * frac).round() as u8;
+    Color::Rgb {
+        r: lerp(a.0, b.0),
+        g: lerp(a.1, b.1),
+        b: lerp(a.2, b.2),
+    }
+}
+
+fn scheme_stops(scheme: u8) -> &'static [(u8, u8, u8)] {
+    if let Some(custom) = custom_scheme(scheme) {
+        return &custom.stops;
+    }
+    SCHEME_STOPS
+        .get(scheme as usize)
+        .copied()
+        .unwrap_or(SCHEME_STOPS[0])
+}
+
+/// Sample a scheme at a continuous intensity (0.0 = dimmest, 1.0 = brightest)
+pub fn scheme_ramp(scheme: u8, t: f32) -> Color {
+    gradient_color(scheme_stops(scheme), t)
+}
+
+/// Sample a scheme at `steps` evenly spaced intensities, dimmest first
+pub fn scheme_steps(scheme: u8, steps: usize) -> Vec<Color> {
+    let last = steps.saturating_sub(1).max(1) as f32;
+    (0..steps)
+        .map(|i| scheme_ramp(scheme, i as f32 / last))
+        .collect()
+}
+
 /// Get color from scheme based on intensity (0-3)
 pub fn scheme_color(scheme: u8, intensity: u8, bold: bool) -> (Color, bool) {
-    match scheme {
-        1 => match intensity {
-            // Red/Yellow (fire)
-            0 => (Color::DarkRed, false),
-            1 => (Color::Red, false),
-            2 => (Color::DarkYellow, bold),
-            _ => (Color::Yellow, true),
-        },
-        2 => match intensity {
-            // Blue/Cyan (ice)
-            0 => (Color::DarkBlue, false),
-            1 => (Color::Blue, false),
-            2 => (Color::Cyan, bold),
-            _ => (Color::Cyan, true),
-        },
-        3 => match intensity {
-            // Magenta/Pink (pink)
-            0 => (Color::DarkMagenta, false),
-            1 => (Color::Magenta, false),
-            2 => (Color::Magenta, bold),
-            _ => (Color::AnsiValue(13), true), // Bright magenta
-        },
-        4 => match intensity {
-            // Yellow/Gold (gold)
-            0 => (Color::DarkYellow, false),
-            1 => (Color::Yellow, false),
-            2 => (Color::Yellow, bold),
-            _ => (Color::AnsiValue(11), true), // Bright yellow
-        },
-        5 => match intensity {
-            // Cyan/Electric (electric)
-            0 => (Color::DarkCyan, false),
-            1 => (Color::Cyan, false),
-            2 => (Color::Cyan, bold),
-            _ => (Color::AnsiValue(14), true), // Bright cyan
-        },
-        6 => match intensity {
-            // Red/Magenta (lava)
-            0 => (Color::DarkRed, false),
-            1 => (Color::Red, false),
-            2 => (Color::Magenta, bold),
-            _ => (Color::AnsiValue(9), true), // Bright red
-        },
-        7 => match intensity {
-            // White/Grey (mono)
-            0 => (Color::DarkGrey, false),
-            1 => (Color::Grey, false),
-            2 => (Color::White, bold),
-            _ => (Color::White, true),
-        },
-        8 => match intensity {
-            // Rainbow cycling
-            0 => (Color::Red, false),
-            1 => (Color::Yellow, false),
-            2 => (Color::Green, bold),
-            _ => (Color::Cyan, true),
-        },
-        9 => match intensity {
-            // Blue/Magenta (neon)
-            0 => (Color::DarkBlue, false),
-            1 => (Color::Blue, false),
-            2 => (Color::Magenta, bold),
-            _ => (Color::AnsiValue(13), true), // Bright magenta
-        },
-        _ => match intensity {
-            // Default: Green (matrix)
-            0 => (Color::DarkGreen, false),
-            1 => (Color::Green, false),
-            2 => (Color::Green, true),
-            _ => (Color::AnsiValue(10), true), // Bright green
-        },
+    let intensity = intensity.min(3);
+    let t = intensity as f32 / 3.0;
+    let color = scheme_ramp(scheme, t);
+    if let Some(custom) = custom_scheme(scheme) {
+        return (
+            color,
+            t >= custom.bold_threshold || (intensity == 2 && bold),
+        );
     }
+    let bold = match intensity {
+        0 | 1 => false,
+        2 => bold || scheme == 0,
+        _ => true,
+    };
+    (color, bold)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ColorState;
+    use super::{
+        custom_schemes_from_settings, gradient_color, next_scheme, parse_hex_color, scheme_color,
+        scheme_ramp, scheme_steps, ColorState, CustomScheme,
+    };
+    use crate::settings::SchemeSettings;
     use crossterm::event::{KeyCode, KeyModifiers};
+    use crossterm::style::Color;
 
     #[test]
     fn color_shortcuts_accept_both_shifted_key_representations() {
@@ -172,4 +345,92 @@ mod tests {
             assert_eq!(ColorState::new(scheme as u8).name(), name);
         }
     }
+
+    #[test]
+    fn schemes_are_rgb_gradients_from_dim_to_bright() {
+        for scheme in 0..10 {
+            let luma = |intensity| match scheme_color(scheme, intensity, false).0 {
+                Color::Rgb { r, g, b } => r as u32 + g as u32 + b as u32,
+                other => panic!("scheme {scheme} returned non-RGB color {other:?}"),
+            };
+            if scheme != 8 {
+                assert!(luma(0) < luma(3), "scheme {scheme} should brighten");
+            }
+        }
+        assert!(scheme_color(1, 3, false).1);
+        assert!(!scheme_color(1, 0, true).1);
+    }
+
+    #[test]
+    fn gradient_sampling_interpolates_between_stops() {
+        let stops = [(0, 0, 0), (200, 100, 50)];
+        assert_eq!(
+            gradient_color(&stops, 0.5),
+            Color::Rgb {
+                r: 100,
+                g: 50,
+                b: 25
+            }
+        );
+        assert_eq!(
+            gradient_color(&stops, 2.0),
+            Color::Rgb {
+                r: 200,
+                g: 100,
+                b: 50
+            }
+        );
+        assert_eq!(
+            gradient_color(&stops, f32::NAN),
+            Color::Rgb { r: 0, g: 0, b: 0 }
+        );
+    }
+
+    #[test]
+    fn ramps_sample_continuously_between_scheme_stops() {
+        assert_eq!(scheme_ramp(1, 0.0), scheme_color(1, 0, false).0);
+        assert_eq!(scheme_ramp(1, 1.0), scheme_color(1, 3, false).0);
+        assert_ne!(scheme_ramp(1, 0.1), scheme_ramp(1, 0.2));
+
+        let steps = scheme_steps(2, 16);
+        assert_eq!(steps.len(), 16);
+        assert_eq!(steps[0], scheme_ramp(2, 0.0));
+        assert_eq!(steps[15], scheme_ramp(2, 1.0));
+        assert_eq!(scheme_steps(2, 1), vec![scheme_ramp(2, 0.0)]);
+    }
+
+    #[test]
+    fn custom_schemes_parse_hex_stops_and_reject_bad_entries() {
+        let entry = |name: &str, stops: &[&str], background: Option<&str>| SchemeSettings {
+            name: name.to_string(),
+            stops: stops.iter().map(|s| s.to_string()).collect(),
+            bold_threshold: Some(0.5),
+            background: background.map(str::to_string),
+        };
+        let scheme =
+            CustomScheme::from_settings(&entry("House", &["#102030", "ffeedd"], Some("#000000")))
+                .expect("valid scheme");
+        assert_eq!(scheme.stops, vec![(0x10, 0x20, 0x30), (0xff, 0xee, 0xdd)]);
+        assert_eq!(scheme.background, Some((0, 0, 0)));
+        assert_eq!(scheme.bold_threshold, 0.5);
+
+        let loaded = custom_schemes_from_settings(&[
+            entry("Good", &["#000000"], None),
+            entry("Bad", &["#zzzzzz"], None),
+            entry("Empty", &[], None),
+            entry("Bad bg", &["#000000"], Some("red")),
+        ]);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].name, "Good");
+
+        assert_eq!(parse_hex_color("#0a0B0c"), Some((10, 11, 12)));
+        assert_eq!(parse_hex_color("#abc"), None);
+    }
+
+    #[test]
+    fn scheme_cycling_wraps_past_custom_schemes() {
+        assert_eq!(next_scheme(9, 10), 0);
+        assert_eq!(next_scheme(9, 12), 10);
+        assert_eq!(next_scheme(11, 12), 0);
+    }
 }
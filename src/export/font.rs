@@ -0,0 +1,379 @@
+//! Bitmap font for rasterizing terminal cells
+//!
+//! Printable ASCII comes from a 5x7 glyph table drawn at double height.
+//! Block elements, braille and box drawing are generated geometrically so
+//! the half-block and braille renderers come out pixel exact.
+
+/// Width of one cell in pixels
+pub const CELL_WIDTH: u32 = 8;
+/// Height of one cell in pixels
+pub const CELL_HEIGHT: u32 = 16;
+
+const ROWS: usize = CELL_HEIGHT as usize;
+
+/// Row drawn for underlined text, just below the ASCII glyphs
+pub const UNDERLINE_ROW: usize = ROWS - 1;
+
+/// One cell of pixels, a row per byte with the most significant bit leftmost
+pub type Glyph = [u8; ROWS];
+
+/// 5x7 glyphs for U+0020..=U+007E, a row per byte in the low five bits
+const ASCII: [[u8; 7]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04], // '!'
+    [0x0a, 0x0a, 0x0a, 0x00, 0x00, 0x00, 0x00], // '"'
+    [0x0a, 0x0a, 0x1f, 0x0a, 0x1f, 0x0a, 0x0a], // '#'
+    [0x04, 0x0f, 0x14, 0x0e, 0x05, 0x1e, 0x04], // '$'
+    [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03], // '%'
+    [0x0c, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0d], // '&'
+    [0x04, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00], // "'"
+    [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02], // '('
+    [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08], // ')'
+    [0x00, 0x04, 0x15, 0x0e, 0x15, 0x04, 0x00], // '*'
+    [0x00, 0x04, 0x04, 0x1f, 0x04, 0x04, 0x00], // '+'
+    [0x00, 0x00, 0x00, 0x00, 0x0c, 0x04, 0x08], // ','
+    [0x00, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00], // '-'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x0c], // '.'
+    [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00], // '/'
+    [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e], // '0'
+    [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e], // '1'
+    [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f], // '2'
+    [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e], // '3'
+    [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02], // '4'
+    [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e], // '5'
+    [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e], // '6'
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08], // '7'
+    [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e], // '8'
+    [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c], // '9'
+    [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x0c, 0x00], // ':'
+    [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x04, 0x08], // ';'
+    [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02], // '<'
+    [0x00, 0x00, 0x1f, 0x00, 0x1f, 0x00, 0x00], // '='
+    [0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08], // '>'
+    [0x0e, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04], // '?'
+    [0x0e, 0x11, 0x01, 0x0d, 0x15, 0x15, 0x0e], // '@'
+    [0x0e, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11], // 'A'
+    [0x1e, 0x11, 0x11, 0x1e, 0x11, 0x11, 0x1e], // 'B'
+    [0x0e, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0e], // 'C'
+    [0x1c, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1c], // 'D'
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x1f], // 'E'
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x10], // 'F'
+    [0x0e, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0f], // 'G'
+    [0x11, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11], // 'H'
+    [0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e], // 'I'
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0c], // 'J'
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11], // 'K'
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f], // 'L'
+    [0x11, 0x1b, 0x15, 0x15, 0x11, 0x11, 0x11], // 'M'
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11], // 'N'
+    [0x0e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e], // 'O'
+    [0x1e, 0x11, 0x11, 0x1e, 0x10, 0x10, 0x10], // 'P'
+    [0x0e, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0d], // 'Q'
+    [0x1e, 0x11, 0x11, 0x1e, 0x14, 0x12, 0x11], // 'R'
+    [0x0f, 0x10, 0x10, 0x0e, 0x01, 0x01, 0x1e], // 'S'
+    [0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // 'T'
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e], // 'U'
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0a, 0x04], // 'V'
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0a], // 'W'
+    [0x11, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x11], // 'X'
+    [0x11, 0x11, 0x11, 0x0a, 0x04, 0x04, 0x04], // 'Y'
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1f], // 'Z'
+    [0x0e, 0x08, 0x08, 0x08, 0x08, 0x08, 0x0e], // '['
+    [0x00, 0x10, 0x08, 0x04, 0x02, 0x01, 0x00], // '\\'
+    [0x0e, 0x02, 0x02, 0x02, 0x02, 0x02, 0x0e], // ']'
+    [0x04, 0x0a, 0x11, 0x00, 0x00, 0x00, 0x00], // '^'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1f], // '_'
+    [0x08, 0x04, 0x02, 0x00, 0x00, 0x00, 0x00], // '`'
+    [0x00, 0x00, 0x0e, 0x01, 0x0f, 0x11, 0x0f], // 'a'
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x1e], // 'b'
+    [0x00, 0x00, 0x0e, 0x10, 0x10, 0x11, 0x0e], // 'c'
+    [0x01, 0x01, 0x0d, 0x13, 0x11, 0x11, 0x0f], // 'd'
+    [0x00, 0x00, 0x0e, 0x11, 0x1f, 0x10, 0x0e], // 'e'
+    [0x06, 0x09, 0x08, 0x1c, 0x08, 0x08, 0x08], // 'f'
+    [0x00, 0x0f, 0x11, 0x11, 0x0f, 0x01, 0x0e], // 'g'
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x11], // 'h'
+    [0x04, 0x00, 0x0c, 0x04, 0x04, 0x04, 0x0e], // 'i'
+    [0x02, 0x00, 0x06, 0x02, 0x02, 0x12, 0x0c], // 'j'
+    [0x10, 0x10, 0x12, 0x14, 0x18, 0x14, 0x12], // 'k'
+    [0x0c, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e], // 'l'
+    [0x00, 0x00, 0x1a, 0x15, 0x15, 0x11, 0x11], // 'm'
+    [0x00, 0x00, 0x16, 0x19, 0x11, 0x11, 0x11], // 'n'
+    [0x00, 0x00, 0x0e, 0x11, 0x11, 0x11, 0x0e], // 'o'
+    [0x00, 0x00, 0x1e, 0x11, 0x1e, 0x10, 0x10], // 'p'
+    [0x00, 0x00, 0x0d, 0x13, 0x0f, 0x01, 0x01], // 'q'
+    [0x00, 0x00, 0x16, 0x19, 0x10, 0x10, 0x10], // 'r'
+    [0x00, 0x00, 0x0e, 0x10, 0x0e, 0x01, 0x1e], // 's'
+    [0x08, 0x08, 0x1c, 0x08, 0x08, 0x09, 0x06], // 't'
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0d], // 'u'
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x0a, 0x04], // 'v'
+    [0x00, 0x00, 0x11, 0x11, 0x15, 0x15, 0x0a], // 'w'
+    [0x00, 0x00, 0x11, 0x0a, 0x04, 0x0a, 0x11], // 'x'
+    [0x00, 0x00, 0x11, 0x11, 0x0f, 0x01, 0x0e], // 'y'
+    [0x00, 0x00, 0x1f, 0x02, 0x04, 0x08, 0x1f], // 'z'
+    [0x02, 0x04, 0x04, 0x08, 0x04, 0x04, 0x02], // '{'
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // '|'
+    [0x08, 0x04, 0x04, 0x02, 0x04, 0x04, 0x08], // '}'
+    [0x00, 0x00, 0x08, 0x15, 0x02, 0x00, 0x00], // '~'
+];
+
+/// Pixels of `ch`, emboldened by smearing one pixel to the right
+pub fn glyph(ch: char, bold: bool) -> Glyph {
+    let mut glyph = base_glyph(ch);
+    if bold {
+        for row in &mut glyph {
+            *row |= *row >> 1;
+        }
+    }
+    glyph
+}
+
+fn base_glyph(ch: char) -> Glyph {
+    match ch {
+        ' ' | '\u{2800}' => [0; ROWS],
+        ' '..='~' => ascii(ch),
+        '\u{2500}'..='\u{257f}' => box_drawing(ch).unwrap_or_else(missing),
+        '\u{2580}'..='\u{259f}' => block(ch),
+        '\u{2801}'..='\u{28ff}' => braille(ch as u32 - 0x2800),
+        '■' | '▪' | '◼' => rect(1, 4, 7, 12),
+        '●' | '•' | '◉' | '⬤' => disc(false),
+        '○' | '◯' | '◦' => disc(true),
+        '·' | '∙' => rect(3, 7, 5, 9),
+        '▲' | '▴' => triangle(true),
+        '▼' | '▾' => triangle(false),
+        _ => missing(),
+    }
+}
+
+fn ascii(ch: char) -> Glyph {
+    let mut glyph = [0; ROWS];
+    let rows = ASCII[ch as usize - 0x20];
+    for (i, bits) in rows.iter().enumerate() {
+        // Five columns shifted to start one pixel in; each row drawn twice
+        let row = bits << 2;
+        glyph[1 + i * 2] = row;
+        glyph[2 + i * 2] = row;
+    }
+    glyph
+}
+
+/// Filled rectangle covering columns `x0..x1` and rows `y0..y1`
+fn rect(x0: u32, y0: u32, x1: u32, y1: u32) -> Glyph {
+    let mut glyph = [0; ROWS];
+    let row = (0xffu32 >> x0 & !(0xffu32 >> x1)) as u8;
+    for line in &mut glyph[y0 as usize..y1 as usize] {
+        *line = row;
+    }
+    glyph
+}
+
+fn union(a: Glyph, b: Glyph) -> Glyph {
+    std::array::from_fn(|i| a[i] | b[i])
+}
+
+/// Unicode block elements U+2580..=U+259F
+fn block(ch: char) -> Glyph {
+    let (w, h) = (CELL_WIDTH, CELL_HEIGHT);
+    let upper_left = rect(0, 0, w / 2, h / 2);
+    let upper_right = rect(w / 2, 0, w, h / 2);
+    let lower_left = rect(0, h / 2, w / 2, h);
+    let lower_right = rect(w / 2, h / 2, w, h);
+    let shade = |pattern: [u8; 2]| -> Glyph { std::array::from_fn(|i| pattern[i % 2]) };
+
+    match ch {
+        '▀' => rect(0, 0, w, h / 2),
+        // Lower eighths ▁..█
+        '\u{2581}'..='\u{2588}' => {
+            let eighths = ch as u32 - 0x2580;
+            rect(0, h - h * eighths / 8, w, h)
+        }
+        // Left eighths ▉..▏
+        '\u{2589}'..='\u{258f}' => {
+            let eighths = 8 - (ch as u32 - 0x2588);
+            rect(0, 0, w * eighths / 8, h)
+        }
+        '▐' => rect(w / 2, 0, w, h),
+        '░' => shade([0x88, 0x22]),
+        '▒' => shade([0xaa, 0x55]),
+        '▓' => shade([0x77, 0xdd]),
+        '▔' => rect(0, 0, w, h / 8),
+        '▕' => rect(w - w / 8, 0, w, h),
+        '▖' => lower_left,
+        '▗' => lower_right,
+        '▘' => upper_left,
+        '▙' => union(union(upper_left, lower_left), lower_right),
+        '▚' => union(upper_left, lower_right),
+        '▛' => union(union(upper_left, upper_right), lower_left),
+        '▜' => union(union(upper_left, upper_right), lower_right),
+        '▝' => upper_right,
+        '▞' => union(upper_right, lower_left),
+        _ => union(union(upper_right, lower_left), lower_right),
+    }
+}
+
+/// Braille patterns: a 2x4 grid of dots, bits numbered down the left
+/// column then the right, with dots 7 and 8 on the bottom row
+fn braille(dots: u32) -> Glyph {
+    const POSITIONS: [(u32, u32); 8] = [
+        (0, 0),
+        (0, 1),
+        (0, 2),
+        (1, 0),
+        (1, 1),
+        (1, 2),
+        (0, 3),
+        (1, 3),
+    ];
+    let mut glyph = [0; ROWS];
+    for (bit, (col, row)) in POSITIONS.iter().enumerate() {
+        if dots & (1 << bit) != 0 {
+            let x = col * 4 + 1;
+            let y = row * 4 + 1;
+            glyph = union(glyph, rect(x, y, x + 2, y + 2));
+        }
+    }
+    glyph
+}
+
+/// Line weight of one arm of a box-drawing character
+#[derive(Clone, Copy, PartialEq)]
+enum Arm {
+    None,
+    Light,
+    Heavy,
+}
+
+/// Box drawing U+2500..=U+257F as (up, down, left, right) arms. Double lines
+/// are drawn heavy; dashed lines solid.
+fn box_drawing(ch: char) -> Option<Glyph> {
+    use Arm::{Heavy as H, Light as L, None as N};
+    let (up, down, left, right) = match ch {
+        '─' | '┄' | '┈' | '╌' => (N, N, L, L),
+        '━' | '┅' | '┉' | '╍' | '═' => (N, N, H, H),
+        '│' | '┆' | '┊' | '╎' => (L, L, N, N),
+        '┃' | '┇' | '┋' | '╏' | '║' => (H, H, N, N),
+        '┌' | '╭' => (N, L, N, L),
+        '┏' | '╔' => (N, H, N, H),
+        '┐' | '╮' => (N, L, L, N),
+        '┓' | '╗' => (N, H, H, N),
+        '└' | '╰' => (L, N, N, L),
+        '┗' | '╚' => (H, N, N, H),
+        '┘' | '╯' => (L, N, L, N),
+        '┛' | '╝' => (H, N, H, N),
+        '├' => (L, L, N, L),
+        '┣' | '╠' => (H, H, N, H),
+        '┤' => (L, L, L, N),
+        '┫' | '╣' => (H, H, H, N),
+        '┬' => (N, L, L, L),
+        '┳' | '╦' => (N, H, H, H),
+        '┴' => (L, N, L, L),
+        '┻' | '╩' => (H, N, H, H),
+        '┼' => (L, L, L, L),
+        '╋' | '╬' => (H, H, H, H),
+        '╴' => (N, N, L, N),
+        '╵' => (L, N, N, N),
+        '╶' => (N, N, N, L),
+        '╷' => (N, L, N, N),
+        _ => return None,
+    };
+
+    let (w, h) = (CELL_WIDTH, CELL_HEIGHT);
+    let (cx, cy) = (w / 2, h / 2);
+    let thickness = |arm: Arm| if arm == Arm::Heavy { 2 } else { 1 };
+    let mut glyph = [0; ROWS];
+    if up != Arm::None {
+        let t = thickness(up);
+        glyph = union(glyph, rect(cx - t / 2, 0, cx - t / 2 + t, cy + 1));
+    }
+    if down != Arm::None {
+        let t = thickness(down);
+        glyph = union(glyph, rect(cx - t / 2, cy, cx - t / 2 + t, h));
+    }
+    if left != Arm::None {
+        let t = thickness(left);
+        glyph = union(glyph, rect(0, cy - t / 2, cx + 1, cy - t / 2 + t));
+    }
+    if right != Arm::None {
+        let t = thickness(right);
+        glyph = union(glyph, rect(cx, cy - t / 2, w, cy - t / 2 + t));
+    }
+    Some(glyph)
+}
+
+fn disc(hollow: bool) -> Glyph {
+    let (cx, cy, r) = (3.5f32, 7.5f32, 3.2f32);
+    let mut glyph = [0; ROWS];
+    for (y, row) in glyph.iter_mut().enumerate() {
+        for x in 0..CELL_WIDTH {
+            // Pixels are twice as tall as wide on screen
+            let dx = x as f32 - cx;
+            let dy = (y as f32 - cy) / 2.0;
+            let d = (dx * dx + dy * dy).sqrt();
+            if d <= r && !(hollow && d < r - 1.2) {
+                *row |= 0x80 >> x;
+            }
+        }
+    }
+    glyph
+}
+
+fn triangle(up: bool) -> Glyph {
+    let mut glyph = [0; ROWS];
+    for i in 0..7u32 {
+        let half = i.div_ceil(2);
+        let row = (0xffu32 >> (3 - half.min(3)) & !(0xffu32 >> (5 + half.min(3)))) as u8;
+        let y = if up { 4 + i } else { 11 - i };
+        glyph[y as usize] = row;
+    }
+    glyph
+}
+
+/// Outlined box drawn for characters the font does not cover
+fn missing() -> Glyph {
+    let mut glyph = rect(1, 3, 7, 13);
+    for row in &mut glyph[4..12] {
+        *row &= 0x42;
+    }
+    glyph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glyph, CELL_HEIGHT};
+
+    fn lit(ch: char) -> Vec<(u32, u32)> {
+        let glyph = glyph(ch, false);
+        (0..CELL_HEIGHT)
+            .flat_map(|y| (0..8).map(move |x| (x, y)))
+            .filter(|&(x, y)| glyph[y as usize] & (0x80 >> x) != 0)
+            .collect()
+    }
+
+    #[test]
+    fn blocks_and_braille_cover_exact_fractions_of_the_cell() {
+        assert_eq!(lit('█').len(), 128);
+        assert_eq!(
+            lit('▀'),
+            lit('▄')
+                .iter()
+                .map(|&(x, y)| (x, y - 8))
+                .collect::<Vec<_>>()
+        );
+        assert!(lit('▌').iter().all(|&(x, _)| x < 4));
+        assert_eq!(lit('▗').len(), 32);
+        // Dot 1 top-left, dot 8 bottom-right
+        assert_eq!(lit('\u{2801}'), [(1, 1), (2, 1), (1, 2), (2, 2)]);
+        assert_eq!(lit('\u{2880}'), [(5, 13), (6, 13), (5, 14), (6, 14)]);
+        assert_eq!(lit('\u{28ff}').len(), 32);
+    }
+
+    #[test]
+    fn ascii_is_drawn_at_double_height_and_bold_widens() {
+        let bar = lit('|');
+        assert_eq!(bar.len(), 14);
+        assert!(bar.iter().all(|&(x, _)| x == 3));
+        let bold = glyph('|', true);
+        assert_eq!(bold[1], 0b0001_1000);
+        assert_eq!(glyph(' ', true), [0; 16]);
+        assert_ne!(glyph('é', false), [0; 16]);
+    }
+}
@@ -0,0 +1,278 @@
+//! Frame export to PNG and animated GIF
+//!
+//! The subcommand runs offscreen and every presented back buffer is
+//! rasterized with the bundled bitmap font, so images come out identical on
+//! any machine with no screenshot tooling.
+
+mod font;
+
+use crate::terminal::{self, color_to_rgb, Attrs, Frame};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, ImageFormat, Rgba, RgbaImage};
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+use std::time::Duration;
+
+/// Foreground for cells without a color (xterm's default grey)
+const DEFAULT_FG: (u8, u8, u8) = (229, 229, 229);
+/// Background for cells without a color
+const DEFAULT_BG: (u8, u8, u8) = (0, 0, 0);
+/// Size used when stdout is not a terminal
+const FALLBACK_SIZE: (u16, u16) = (80, 24);
+/// Frame delay when the program never slept between frames
+const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+/// Shortest delay browsers honor; anything faster is slowed to 100ms
+const MIN_DELAY: Duration = Duration::from_millis(20);
+
+/// Output image format, chosen from the file extension
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Png,
+    Gif,
+}
+
+impl Format {
+    fn from_path(path: &Path) -> io::Result<Self> {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("png") => Ok(Format::Png),
+            Some("gif") => Ok(Format::Gif),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "export file must end in .png or .gif",
+            )),
+        }
+    }
+}
+
+/// Run `f` offscreen at the current terminal size and export the first
+/// `frames` frames it draws: all of them to a GIF, or the last one to a PNG
+pub fn run(path: &Path, frames: usize, f: impl FnOnce() -> io::Result<()>) -> io::Result<()> {
+    let format = Format::from_path(path)?;
+    let (width, height) = crossterm::terminal::size()
+        .ok()
+        .filter(|&(w, h)| w > 0 && h > 0)
+        .unwrap_or(FALLBACK_SIZE);
+
+    let (result, captured) = terminal::capture_headless(width, height, frames.max(1), f);
+    result?;
+    if captured.is_empty() {
+        return Err(io::Error::other("nothing was drawn to export"));
+    }
+
+    match format {
+        Format::Png => {
+            let last = &captured[captured.len() - 1];
+            rasterize(last)
+                .save_with_format(path, ImageFormat::Png)
+                .map_err(io::Error::other)
+        }
+        Format::Gif => write_gif(path, &captured),
+    }
+}
+
+fn write_gif(path: &Path, frames: &[Frame]) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = GifEncoder::new_with_speed(file, 10);
+    encoder
+        .set_repeat(Repeat::Infinite)
+        .map_err(io::Error::other)?;
+
+    let delays = frame_delays(frames);
+    for (frame, delay) in frames.iter().zip(delays) {
+        let delay = Delay::from_saturating_duration(delay);
+        encoder
+            .encode_frame(image::Frame::from_parts(rasterize(frame), 0, 0, delay))
+            .map_err(io::Error::other)?;
+    }
+    Ok(())
+}
+
+/// How long each frame stays up: the time slept until the next one. The
+/// last frame repeats the previous delay.
+fn frame_delays(frames: &[Frame]) -> Vec<Duration> {
+    let mut delays: Vec<Duration> = frames
+        .windows(2)
+        .map(|pair| pair[1].elapsed.saturating_sub(pair[0].elapsed))
+        .map(|delay| {
+            if delay.is_zero() {
+                DEFAULT_DELAY
+            } else {
+                delay.max(MIN_DELAY)
+            }
+        })
+        .collect();
+    delays.push(delays.last().copied().unwrap_or(DEFAULT_DELAY));
+    delays
+}
+
+/// Draw a frame's cells as pixels
+fn rasterize(frame: &Frame) -> RgbaImage {
+    let rows = frame.cells.len() as u32;
+    let cols = frame.cells.first().map_or(0, |row| row.len()) as u32;
+    let default_bg = frame
+        .default_bg
+        .and_then(color_to_rgb)
+        .unwrap_or(DEFAULT_BG);
+    let mut image = RgbaImage::new(cols * font::CELL_WIDTH, rows * font::CELL_HEIGHT);
+
+    for (y, row) in frame.cells.iter().enumerate() {
+        for (x, cell) in row.iter().enumerate().take(cols as usize) {
+            let mut fg = cell.fg.and_then(color_to_rgb).unwrap_or(DEFAULT_FG);
+            let mut bg = cell.bg.and_then(color_to_rgb).unwrap_or(default_bg);
+            if cell.attrs.contains(Attrs::DIM) {
+                fg = blend(fg, bg);
+            }
+            if cell.attrs.contains(Attrs::REVERSE) {
+                std::mem::swap(&mut fg, &mut bg);
+            }
+            let mut glyph = font::glyph(cell.ch, cell.attrs.contains(Attrs::BOLD));
+            if cell.attrs.contains(Attrs::UNDERLINE) {
+                glyph[font::UNDERLINE_ROW] = 0xff;
+            }
+
+            let origin_x = x as u32 * font::CELL_WIDTH;
+            let origin_y = y as u32 * font::CELL_HEIGHT;
+            for (py, bits) in glyph.iter().enumerate() {
+                for px in 0..font::CELL_WIDTH {
+                    let (r, g, b) = if bits & (0x80 >> px) != 0 { fg } else { bg };
+                    image.put_pixel(origin_x + px, origin_y + py as u32, Rgba([r, g, b, 255]));
+                }
+            }
+        }
+    }
+    image
+}
+
+/// Halfway between two colors, how dim text is drawn
+fn blend(a: (u8, u8, u8), b: (u8, u8, u8)) -> (u8, u8, u8) {
+    let mid = |x: u8, y: u8| ((x as u16 + y as u16) / 2) as u8;
+    (mid(a.0, b.0), mid(a.1, b.1), mid(a.2, b.2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{frame_delays, rasterize, Format, DEFAULT_DELAY, DEFAULT_FG};
+    use crate::config::BonsaiConfig;
+    use crate::terminal::{Attrs, Cell, Frame};
+    use crossterm::style::Color;
+    use std::path::Path;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn cells_rasterize_with_their_colors_and_the_default_background() {
+        let frame = Frame {
+            cells: vec![vec![
+                Cell {
+                    ch: '█',
+                    fg: Some(Color::Rgb {
+                        r: 10,
+                        g: 20,
+                        b: 30,
+                    }),
+                    ..Cell::default()
+                },
+                Cell {
+                    ch: ' ',
+                    bg: Some(Color::DarkRed),
+                    ..Cell::default()
+                },
+                Cell {
+                    ch: '▀',
+                    ..Cell::default()
+                },
+            ]],
+            default_bg: Some(Color::Rgb { r: 1, g: 2, b: 3 }),
+            ..Frame::default()
+        };
+        let image = rasterize(&frame);
+        assert_eq!(image.dimensions(), (24, 16));
+        assert_eq!(image.get_pixel(4, 8).0, [10, 20, 30, 255]);
+        assert_eq!(image.get_pixel(12, 8).0, [205, 0, 0, 255]);
+        let (r, g, b) = DEFAULT_FG;
+        assert_eq!(image.get_pixel(20, 2).0, [r, g, b, 255]);
+        assert_eq!(image.get_pixel(20, 14).0, [1, 2, 3, 255]);
+    }
+
+    #[test]
+    fn reverse_swaps_colors_and_underline_fills_the_bottom_row() {
+        let frame = Frame {
+            cells: vec![vec![Cell {
+                ch: ' ',
+                fg: Some(Color::DarkRed),
+                attrs: Attrs::REVERSE | Attrs::UNDERLINE,
+                ..Cell::default()
+            }]],
+            ..Frame::default()
+        };
+        let image = rasterize(&frame);
+        assert_eq!(image.get_pixel(4, 8).0, [205, 0, 0, 255]);
+        assert_eq!(image.get_pixel(4, 15).0, [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn gif_delays_follow_slept_time() {
+        let at = |ms: u64| Frame {
+            elapsed: Duration::from_millis(ms),
+            ..Frame::default()
+        };
+        let delays = frame_delays(&[at(0), at(50), at(50), at(55)]);
+        assert_eq!(
+            delays,
+            [
+                Duration::from_millis(50),
+                DEFAULT_DELAY,
+                Duration::from_millis(20),
+                Duration::from_millis(20),
+            ]
+        );
+        assert_eq!(frame_delays(&[at(0)]), [DEFAULT_DELAY]);
+        assert_eq!(
+            Format::from_path(Path::new("a.GIF")).ok(),
+            Some(Format::Gif)
+        );
+        assert!(Format::from_path(Path::new("a.jpg")).is_err());
+    }
+
+    #[test]
+    fn static_programs_export_once_they_stop_drawing() {
+        let path = std::env::temp_dir().join(format!(
+            "termart-export-{}-{}.gif",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        let config = BonsaiConfig {
+            live: false,
+            infinite: false,
+            print: false,
+            time_step: 0.03,
+            time_wait: 4.0,
+            life_start: 32,
+            multiplier: 5,
+            seed: Some(1),
+            base_type: 1,
+            leaves: vec!["&".to_string()],
+            message: None,
+        };
+
+        // A finished bonsai waits for a key; the export must not wait with it
+        let (sender, receiver) = mpsc::channel();
+        let target = path.clone();
+        thread::spawn(move || {
+            let _ = sender.send(super::run(&target, 5, || crate::bonsai::run(config)));
+        });
+        receiver
+            .recv_timeout(Duration::from_secs(10))
+            .expect("export of a static program finishes")
+            .expect("bonsai exports");
+        assert!(std::fs::metadata(&path).is_ok_and(|file| file.len() > 0));
+        std::fs::remove_file(&path).expect("test export should be removed");
+    }
+}
@@ -4,6 +4,7 @@ use crate::monitor::layout::{
     draw_meter_btop_scheme, header_color_scheme, muted_color_scheme, text_color_scheme,
 };
 use crate::terminal::Terminal;
+use crate::unicode;
 use aes::Aes256;
 use std::io::Write;
 
@@ -19,7 +20,6 @@ macro_rules! debug_log {
 use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
 use base64::Engine;
 use crossterm::event::KeyCode;
-use crossterm::terminal::size;
 use pbkdf2::pbkdf2_hmac;
 
 const HELP: HelpSpec =
@@ -1384,7 +1384,13 @@ impl FahDisplay {
         if has_local {
             // Section header
             let header = format!(" {} ", self.local_hostname);
-            term.set_str(inner_x, y, &header, Some(header_color_scheme(colors)), true);
+            term.set_str(
+                inner_x,
+                y,
+                unicode::truncate_to_width(&header, inner_w),
+                Some(header_color_scheme(colors)),
+                true,
+            );
             y += 1;
 
             for wu in &self.local_units {
@@ -1401,7 +1407,13 @@ impl FahDisplay {
 
             // Section header
             let header = format!(" {} ", machine.name);
-            term.set_str(inner_x, y, &header, Some(header_color_scheme(colors)), true);
+            term.set_str(
+                inner_x,
+                y,
+                unicode::truncate_to_width(&header, inner_w),
+                Some(header_color_scheme(colors)),
+                true,
+            );
             y += 1;
 
             for unit in &machine.units {
@@ -1430,7 +1442,8 @@ impl FahDisplay {
                 format_number(data.wus),
                 format_number(data.rank)
             );
-            let stats_x = inner_x + ((inner_w.saturating_sub(stats.len())) / 2) as i32;
+            let stats_x =
+                inner_x + ((inner_w.saturating_sub(unicode::str_width(&stats))) / 2) as i32;
             term.set_str(stats_x, y, &stats, Some(text_color_scheme(colors)), false);
         }
     }
@@ -1703,7 +1716,7 @@ pub fn run(config: FahConfig) -> io::Result<()> {
             last_machine = Instant::now();
         }
 
-        if let Ok((new_w, new_h)) = size() {
+        if let Ok((new_w, new_h)) = term.query_size() {
             let (cur_w, cur_h) = term.size();
             if new_w != cur_w || new_h != cur_h {
                 term.resize(new_w, new_h);
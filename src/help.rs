@@ -1,9 +1,10 @@
 use crate::terminal::Terminal;
+use crate::unicode;
 use crossterm::cursor::MoveTo;
 use crossterm::event::KeyCode;
 use crossterm::queue;
 use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
-use std::io::{self, stdout, Write};
+use std::io::{self, Write};
 
 const SEPARATOR_WIDTH: usize = 23;
 
@@ -44,6 +45,7 @@ const BASIC_CONTROLS: &[HelpEntry] = &[
 
 const COLORED_CONTROLS: &[HelpEntry] = &[
     HelpEntry::new("!-()", "Color scheme"),
+    HelpEntry::new("~", "Next scheme (incl. custom)"),
     HelpEntry::new("q/Esc", "Quit"),
     HelpEntry::new("?", "Toggle help"),
 ];
@@ -52,6 +54,7 @@ const ANIMATED_CONTROLS: &[HelpEntry] = &[
     HelpEntry::new("Space", "Pause/resume"),
     HelpEntry::new("1-9", "Speed (1=fast)"),
     HelpEntry::new("!-()", "Color scheme"),
+    HelpEntry::new("~", "Next scheme (incl. custom)"),
     HelpEntry::new("q/Esc", "Quit"),
     HelpEntry::new("?", "Toggle help"),
 ];
@@ -59,6 +62,7 @@ const ANIMATED_CONTROLS: &[HelpEntry] = &[
 const PAUSABLE_CONTROLS: &[HelpEntry] = &[
     HelpEntry::new("Space", "Pause/resume"),
     HelpEntry::new("!-()", "Color scheme"),
+    HelpEntry::new("~", "Next scheme (incl. custom)"),
     HelpEntry::new("q/Esc", "Quit"),
     HelpEntry::new("?", "Toggle help"),
 ];
@@ -66,6 +70,7 @@ const PAUSABLE_CONTROLS: &[HelpEntry] = &[
 const SPEED_CONTROLS: &[HelpEntry] = &[
     HelpEntry::new("1-9", "Speed (1=fast)"),
     HelpEntry::new("!-()", "Color scheme"),
+    HelpEntry::new("~", "Next scheme (incl. custom)"),
     HelpEntry::new("q/Esc", "Quit"),
     HelpEntry::new("?", "Toggle help"),
 ];
@@ -78,6 +83,7 @@ const MONITOR_CONTROLS: &[HelpEntry] = &[
     HelpEntry::new("1-9", "Interval preset (1=fast)"),
     HelpEntry::new("d", "Default interval"),
     HelpEntry::new("!-()", "Color scheme"),
+    HelpEntry::new("~", "Next scheme (incl. custom)"),
     HelpEntry::new("q/Esc", "Quit"),
     HelpEntry::new("?", "Toggle help"),
 ];
@@ -144,20 +150,19 @@ impl HelpSpec {
             .controls
             .iter()
             .chain(global)
-            .map(|entry| entry.keys.chars().count())
+            .map(|entry| unicode::str_width(entry.keys))
             .max()
             .unwrap_or(0);
         let local_lines = format_entries(self.controls, key_width);
         let global_lines = format_entries(global, key_width);
         let content_width = std::iter::once(self.title)
             .chain((!self.controls.is_empty()).then_some("GLOBAL CONTROLS"))
-            .map(str::chars)
-            .map(Iterator::count)
+            .map(unicode::str_width)
             .chain(
                 local_lines
                     .iter()
                     .chain(&global_lines)
-                    .map(|line| line.chars().count()),
+                    .map(|line| unicode::str_width(line)),
             )
             .max()
             .unwrap_or(0)
@@ -181,10 +186,9 @@ fn format_entries(entries: &[HelpEntry], key_width: usize) -> Vec<String> {
         .iter()
         .map(|entry| {
             format!(
-                "{:<width$}  {}",
-                entry.keys,
-                entry.action,
-                width = key_width
+                "{}  {}",
+                unicode::pad_to_width(entry.keys, key_width),
+                entry.action
             )
         })
         .collect()
@@ -197,7 +201,11 @@ pub fn render_help_overlay(term: &mut Terminal, width: u16, height: u16, help_te
     }
 
     let lines: Vec<&str> = help_text.lines().collect();
-    let max_width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let max_width = lines
+        .iter()
+        .map(|l| unicode::str_width(l))
+        .max()
+        .unwrap_or(0);
     let box_width = max_width + 4; // 2 chars padding each side
     let box_height = lines.len() + 2; // 1 row padding top/bottom
 
@@ -238,17 +246,14 @@ pub fn render_help_overlay(term: &mut Terminal, width: u16, height: u16, help_te
         let y = start_y + 1 + i;
         term.set(start_x as i32, y as i32, '│', Some(border_color), false);
 
-        let padding = max_width.saturating_sub(line.chars().count());
-        let padded = format!(" {}{} ", line, " ".repeat(padding));
-        for (j, ch) in padded.chars().enumerate() {
-            term.set(
-                (start_x + 1 + j) as i32,
-                y as i32,
-                ch,
-                Some(text_color),
-                false,
-            );
-        }
+        let padded = format!(" {} ", unicode::pad_to_width(line, max_width));
+        term.set_str(
+            (start_x + 1) as i32,
+            y as i32,
+            &padded,
+            Some(text_color),
+            false,
+        );
 
         term.set(
             (start_x + box_width - 1) as i32,
@@ -299,7 +304,11 @@ pub fn show_help_modal(term: &mut Terminal, help_text: &str) -> io::Result<bool>
     }
 
     let (width, height) = term.size();
-    render_help_overlay_direct(width, height, help_text)?;
+    if !term.is_headless() {
+        let mut out = Vec::new();
+        render_help_overlay_direct(&mut out, width, height, help_text)?;
+        term.write_raw(&out)?;
+    }
 
     loop {
         if let Some(code) = term.wait_key(50)? {
@@ -321,9 +330,18 @@ pub fn show_help_spec_modal(term: &mut Terminal, spec: &HelpSpec) -> io::Result<
     show_help_modal(term, &spec.render())
 }
 
-fn render_help_overlay_direct(width: u16, height: u16, help_text: &str) -> io::Result<()> {
+fn render_help_overlay_direct(
+    out: &mut impl Write,
+    width: u16,
+    height: u16,
+    help_text: &str,
+) -> io::Result<()> {
     let lines: Vec<&str> = help_text.lines().collect();
-    let max_width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let max_width = lines
+        .iter()
+        .map(|l| unicode::str_width(l))
+        .max()
+        .unwrap_or(0);
     let box_width = max_width + 4;
     let box_height = lines.len() + 2;
 
@@ -333,8 +351,6 @@ fn render_help_overlay_direct(width: u16, height: u16, help_text: &str) -> io::R
     let border_color = Color::White;
     let text_color = Color::Grey;
 
-    let mut out = stdout();
-
     // Top border
     queue!(
         out,
@@ -365,8 +381,7 @@ fn render_help_overlay_direct(width: u16, height: u16, help_text: &str) -> io::R
             Print('│')
         )?;
 
-        let padding = max_width.saturating_sub(line.chars().count());
-        let padded = format!(" {}{} ", line, " ".repeat(padding));
+        let padded = format!(" {} ", unicode::pad_to_width(line, max_width));
         queue!(out, SetForegroundColor(text_color))?;
         queue!(out, MoveTo((start_x + 1) as u16, y as u16), Print(padded))?;
 
@@ -400,13 +415,13 @@ fn render_help_overlay_direct(width: u16, height: u16, help_text: &str) -> io::R
     )?;
 
     queue!(out, SetAttribute(Attribute::Reset), ResetColor)?;
-    out.flush()?;
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{HelpEntry, HelpSpec};
+    use super::{render_help_overlay, HelpEntry, HelpSpec};
+    use crate::terminal::{run_headless, ScriptedInput, Terminal};
 
     #[test]
     fn structured_help_aligns_controls_and_adds_shared_controls() {
@@ -445,4 +460,18 @@ mod tests {
         assert!(help.contains("+/-    Fine interval adjustment"));
         assert!(help.contains("d      Default interval"));
     }
+
+    #[test]
+    fn overlay_borders_line_up_around_wide_text() {
+        let (_, frame) = run_headless(30, 8, [ScriptedInput::Idle(0)], || {
+            let mut term = Terminal::new(true)?;
+            render_help_overlay(&mut term, 30, 8, "コンテナ名\nweb");
+            term.present()
+        });
+        let text = frame.text();
+        let rows: Vec<&str> = text.lines().filter(|line| line.contains('│')).collect();
+        assert_eq!(rows.len(), 2, "{text}");
+        let right_border = |row: &str| crate::unicode::str_width(&row[..row.rfind('│').unwrap()]);
+        assert_eq!(right_border(rows[0]), right_border(rows[1]), "{text}");
+    }
 }
@@ -8,11 +8,15 @@
 #![allow(clippy::manual_is_multiple_of)]
 #![allow(clippy::manual_range_contains)]
 #![allow(clippy::unnecessary_map_or)]
+#![allow(clippy::collapsible_match)]
 
+mod asciicast;
 mod bonsai;
+mod canvas;
 mod colors;
 mod config;
 mod evdev_util;
+mod export;
 mod fah;
 mod fractal;
 mod help;
@@ -21,12 +25,17 @@ mod net_geo;
 mod pomodoro;
 mod settings;
 mod terminal;
+mod theme;
 mod tui;
+mod unicode;
 mod viz;
 mod weather;
 
 use clap::{Args, Parser, Subcommand};
 use config::{BonsaiConfig, FractalConfig, FractalKind};
+use monitor::alert::Alerts;
+use monitor::output::{OutputConfig, OutputFormat};
+use monitor::replay::SampleLog;
 use monitor::{MonitorConfig, MonitorType};
 use std::io;
 use std::path::PathBuf;
@@ -98,6 +107,76 @@ struct MonitorOptions {
     debug: bool,
 }
 
+#[derive(Args, Clone)]
+struct HistoryOptions {
+    /// Samples kept for the history view (g)
+    #[arg(long, value_name = "SAMPLES", default_value_t = monitor::history::DEFAULT_WINDOW)]
+    history: usize,
+}
+
+#[derive(Args, Clone)]
+struct OutputOptions {
+    /// Print one record per sample to stdout instead of the TUI
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+
+    /// Stop after this many records
+    #[arg(long, value_name = "N", requires = "format")]
+    records: Option<u64>,
+}
+
+#[derive(Args, Clone)]
+struct RecordOptions {
+    /// Append each sample to FILE for `termart replay`
+    #[arg(long, value_name = "FILE", conflicts_with = "format")]
+    record_samples: Option<PathBuf>,
+}
+
+impl RecordOptions {
+    fn sample_log(&self) -> SampleLog {
+        let Some(path) = &self.record_samples else {
+            return SampleLog::default();
+        };
+        SampleLog::open(path).unwrap_or_else(|e| {
+            eprintln!(
+                "Error: Failed to record samples to {}: {}",
+                path.display(),
+                e
+            );
+            std::process::exit(1);
+        })
+    }
+}
+
+impl OutputOptions {
+    fn config(&self) -> Option<OutputConfig> {
+        self.format.map(|format| OutputConfig {
+            format,
+            records: self.records,
+        })
+    }
+
+    /// Alert rules for the TUI; `--format` runs don't check them
+    fn alerts(&self) -> Alerts {
+        if self.format.is_some() {
+            return Alerts::default();
+        }
+        load_alerts(&settings::Settings::load())
+    }
+
+    /// Scripts read stderr, so a failed `--format` run reports its error as
+    /// a plain message and exits non-zero
+    fn finish(&self, result: io::Result<()>) -> io::Result<()> {
+        match result {
+            Err(e) if self.format.is_some() => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            result => result,
+        }
+    }
+}
+
 #[derive(Args, Clone)]
 struct TuiOptions {
     /// UI refresh interval (seconds)
@@ -115,6 +194,28 @@ struct TuiOptions {
 #[command(version)]
 #[command(about = "Terminal-based generative art", long_about = None)]
 struct Cli {
+    /// Color depth to render with (default: detect from COLORTERM/TERM)
+    #[arg(long, global = true, value_enum)]
+    color_depth: Option<terminal::ColorDepth>,
+
+    /// Record the session to an asciicast v2 file (play with `asciinema play`)
+    #[arg(long, global = true, value_name = "FILE")]
+    record: Option<PathBuf>,
+
+    /// Render offscreen and save the output as a PNG (last frame) or animated GIF
+    #[arg(long, global = true, value_name = "FILE")]
+    export: Option<PathBuf>,
+
+    /// Number of frames to capture with --export
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        default_value_t = 1,
+        requires = "export"
+    )]
+    frames: usize,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -358,36 +459,93 @@ enum Commands {
     Cpu {
         #[command(flatten)]
         opts: MonitorOptions,
+
+        #[command(flatten)]
+        history: HistoryOptions,
+
+        #[command(flatten)]
+        output: OutputOptions,
+
+        #[command(flatten)]
+        record: RecordOptions,
     },
 
     /// Memory usage monitor
     Mem {
         #[command(flatten)]
         opts: MonitorOptions,
+
+        #[command(flatten)]
+        history: HistoryOptions,
+
+        #[command(flatten)]
+        output: OutputOptions,
+
+        #[command(flatten)]
+        record: RecordOptions,
     },
 
     /// Disk space usage
     Disk {
         #[command(flatten)]
         opts: MonitorOptions,
+
+        #[command(flatten)]
+        output: OutputOptions,
+
+        #[command(flatten)]
+        record: RecordOptions,
     },
 
     /// Disk I/O rates
     Io {
         #[command(flatten)]
         opts: MonitorOptions,
+
+        #[command(flatten)]
+        history: HistoryOptions,
+
+        #[command(flatten)]
+        output: OutputOptions,
+
+        #[command(flatten)]
+        record: RecordOptions,
     },
 
     /// Network I/O rates
     Net {
         #[command(flatten)]
         opts: MonitorOptions,
+
+        #[command(flatten)]
+        history: HistoryOptions,
+
+        #[command(flatten)]
+        output: OutputOptions,
+
+        #[command(flatten)]
+        record: RecordOptions,
     },
 
     /// GPU usage monitor (NVIDIA)
     Gpu {
         #[command(flatten)]
         opts: MonitorOptions,
+
+        #[command(flatten)]
+        output: OutputOptions,
+
+        #[command(flatten)]
+        record: RecordOptions,
+    },
+
+    /// Multi-panel dashboard of the system monitors (layout in config.toml)
+    Dash {
+        #[command(flatten)]
+        opts: MonitorOptions,
+
+        #[command(flatten)]
+        history: HistoryOptions,
     },
 
     /// Process list (top processes by CPU/memory)
@@ -403,6 +561,9 @@ enum Commands {
         /// Include kernel threads
         #[arg(long)]
         all: bool,
+
+        #[command(flatten)]
+        output: OutputOptions,
     },
 
     /// Docker container stats
@@ -410,6 +571,66 @@ enum Commands {
         /// Update interval (seconds)
         #[arg(short, long, default_value = "2.0", value_parser = parse_positive_f32)]
         time: f32,
+
+        /// Container engine; the first with a socket is used by default
+        #[arg(long, value_enum)]
+        engine: Option<monitor::docker_api::Engine>,
+
+        /// Engine API socket, instead of the engine's usual locations
+        #[arg(long)]
+        socket: Option<std::path::PathBuf>,
+
+        #[command(flatten)]
+        output: OutputOptions,
+    },
+
+    /// cgroup v2 tree: CPU, memory, IO and PIDs per slice and service
+    Cgroups {
+        /// Update interval (seconds)
+        #[arg(short, long, default_value = "2.0", value_parser = parse_positive_f32)]
+        time: f32,
+
+        /// cgroup2 mount, or a group below it to show only its subtree
+        #[arg(long, default_value = monitor::cgroups::DEFAULT_ROOT)]
+        root: PathBuf,
+
+        #[command(flatten)]
+        output: OutputOptions,
+    },
+
+    /// Hardware sensors: temperatures, fans, voltages, currents and power
+    Sensors {
+        /// Update interval (seconds)
+        #[arg(short, long, default_value = "2.0", value_parser = parse_positive_f32)]
+        time: f32,
+
+        #[command(flatten)]
+        output: OutputOptions,
+    },
+
+    /// Serve monitor metrics for Prometheus at /metrics
+    Export {
+        /// Address to listen on
+        #[arg(short, long, default_value = "127.0.0.1:9184")]
+        listen: String,
+
+        /// Seconds between samples
+        #[arg(short, long, default_value = "5.0", value_parser = parse_positive_f32)]
+        time: f32,
+    },
+
+    /// Replay samples saved with --record-samples in the monitor's TUI
+    Replay {
+        /// Recording to play back
+        file: PathBuf,
+
+        /// Playback rate relative to the recorded pace
+        #[arg(short, long, default_value = "1.0", value_parser = parse_positive_f32)]
+        speed: f32,
+
+        /// Samples kept for the history view (g)
+        #[arg(long, value_name = "SAMPLES", default_value_t = monitor::history::DEFAULT_WINDOW)]
+        history: usize,
     },
 
     /// Live weather display with ASCII art
@@ -508,6 +729,12 @@ enum Commands {
         auth: bool,
     },
 
+    /// Manage color schemes
+    Theme {
+        #[command(subcommand)]
+        action: ThemeCommand,
+    },
+
     /// OpenAI Codex usage monitor
     CodexTokens {
         /// UI refresh interval (seconds)
@@ -520,6 +747,27 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum ThemeCommand {
+    /// Import a base16 YAML, Alacritty TOML or .itermcolors theme as a color scheme
+    Import {
+        /// Theme file to import
+        file: PathBuf,
+
+        /// Scheme name (default: the theme's own name or file name)
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Theme format (default: detect from extension and content)
+        #[arg(short, long, value_enum)]
+        format: Option<theme::ThemeFormat>,
+
+        /// Replace an existing scheme of the same name
+        #[arg(long)]
+        force: bool,
+    },
+}
+
 fn run_viz(kind: FractalKind, opts: VizOptions) -> io::Result<()> {
     let config = FractalConfig {
         kind,
@@ -530,13 +778,34 @@ fn run_viz(kind: FractalKind, opts: VizOptions) -> io::Result<()> {
     fractal::run(config)
 }
 
-fn run_monitor(mtype: MonitorType, opts: MonitorOptions) -> io::Result<()> {
+fn load_alerts(settings: &settings::Settings) -> Alerts {
+    Alerts::from_settings(&settings.alerts).unwrap_or_else(|e| {
+        eprintln!(
+            "Error: Invalid [alerts] rule in {}: {}",
+            settings::Settings::config_path().display(),
+            e
+        );
+        std::process::exit(1);
+    })
+}
+
+fn run_monitor(
+    mtype: MonitorType,
+    opts: MonitorOptions,
+    history: usize,
+    output: OutputOptions,
+    record: RecordOptions,
+) -> io::Result<()> {
     let config = MonitorConfig {
         monitor_type: mtype,
         time_step: opts.time,
+        history,
+        output: output.config(),
+        sample_log: record.sample_log(),
+        alerts: output.alerts(),
         debug: opts.debug,
     };
-    monitor::run(config)
+    output.finish(monitor::run(config))
 }
 
 fn run_tui(kind: FractalKind, opts: TuiOptions) -> io::Result<()> {
@@ -552,7 +821,37 @@ fn run_tui(kind: FractalKind, opts: TuiOptions) -> io::Result<()> {
 fn main() -> io::Result<()> {
     let cli = Cli::parse();
 
-    match cli.command {
+    if let Some(depth) = cli.color_depth {
+        terminal::ColorDepth::set_override(depth);
+    }
+
+    // Load custom schemes now: warnings about invalid ones go to stderr, which
+    // a running TUI would draw over
+    colors::scheme_count();
+
+    if let Some(path) = &cli.record {
+        if let Err(e) = asciicast::start(path) {
+            eprintln!("Error: Failed to record to {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(path) = &cli.export {
+        let command = cli.command;
+        if let Err(e) = export::run(path, cli.frames, move || run_command(command)) {
+            eprintln!("Error: Failed to export {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let result = run_command(cli.command);
+    asciicast::report();
+    result
+}
+
+fn run_command(command: Commands) -> io::Result<()> {
+    match command {
         Commands::Bonsai {
             live,
             infinite,
@@ -675,23 +974,147 @@ fn main() -> io::Result<()> {
         Commands::Pong { time } => {
             viz::pong::run(time)?;
         }
-        Commands::Cpu { opts } => run_monitor(MonitorType::Cpu, opts)?,
-        Commands::Mem { opts } => run_monitor(MonitorType::Mem, opts)?,
-        Commands::Disk { opts } => run_monitor(MonitorType::Disk, opts)?,
-        Commands::Io { opts } => run_monitor(MonitorType::Io, opts)?,
-        Commands::Net { opts } => run_monitor(MonitorType::Net, opts)?,
-        Commands::Gpu { opts } => run_monitor(MonitorType::Gpu, opts)?,
-        Commands::Ps { time, count, all } => {
+        Commands::Cpu {
+            opts,
+            history,
+            output,
+            record,
+        } => run_monitor(MonitorType::Cpu, opts, history.history, output, record)?,
+        Commands::Mem {
+            opts,
+            history,
+            output,
+            record,
+        } => run_monitor(MonitorType::Mem, opts, history.history, output, record)?,
+        Commands::Disk {
+            opts,
+            output,
+            record,
+        } => run_monitor(
+            MonitorType::Disk,
+            opts,
+            monitor::history::DEFAULT_WINDOW,
+            output,
+            record,
+        )?,
+        Commands::Io {
+            opts,
+            history,
+            output,
+            record,
+        } => run_monitor(MonitorType::Io, opts, history.history, output, record)?,
+        Commands::Net {
+            opts,
+            history,
+            output,
+            record,
+        } => run_monitor(MonitorType::Net, opts, history.history, output, record)?,
+        Commands::Gpu {
+            opts,
+            output,
+            record,
+        } => run_monitor(
+            MonitorType::Gpu,
+            opts,
+            monitor::history::DEFAULT_WINDOW,
+            output,
+            record,
+        )?,
+        Commands::Dash { opts, history } => {
+            let settings = settings::Settings::load();
+            let mut config = monitor::dash::DashConfig::from_settings(&settings.dash, opts.time)
+                .unwrap_or_else(|e| {
+                    eprintln!(
+                        "Error: Invalid [dash] layout in {}: {}",
+                        settings::Settings::config_path().display(),
+                        e
+                    );
+                    std::process::exit(1);
+                });
+            config.history = history.history;
+            config.alerts = load_alerts(&settings);
+            monitor::dash::run(config)?;
+        }
+        Commands::Ps {
+            time,
+            count,
+            all,
+            output,
+        } => {
+            let settings = settings::Settings::load();
+            let columns = monitor::ps_column::PsColumns::from_settings(&settings.ps)
+                .unwrap_or_else(|e| {
+                    eprintln!(
+                        "Error: Invalid [ps] columns in {}: {}",
+                        settings::Settings::config_path().display(),
+                        e
+                    );
+                    std::process::exit(1);
+                });
             let config = monitor::ps::PsConfig {
                 time_step: time,
                 max_procs: count,
                 show_kernel: all,
+                columns,
+                output: output.config(),
             };
-            monitor::ps::run(config)?;
+            output.finish(monitor::ps::run(config))?;
         }
-        Commands::Docker { time } => {
-            let config = monitor::docker::DockerConfig { time_step: time };
-            monitor::docker::run(config)?;
+        Commands::Docker {
+            time,
+            engine,
+            socket,
+            output,
+        } => {
+            let config = monitor::docker::DockerConfig {
+                time_step: time,
+                engine,
+                socket,
+                output: output.config(),
+                alerts: output.alerts(),
+            };
+            output.finish(monitor::docker::run(config))?;
+        }
+        Commands::Cgroups { time, root, output } => {
+            let config = monitor::cgroups::CgroupsConfig {
+                time_step: time,
+                root,
+                output: output.config(),
+            };
+            output.finish(monitor::cgroups::run(config))?;
+        }
+        Commands::Sensors { time, output } => {
+            let config = monitor::sensors::SensorsConfig {
+                time_step: time,
+                root: PathBuf::from(monitor::sensors::HWMON_ROOT),
+                output: output.config(),
+            };
+            output.finish(monitor::sensors::run(config))?;
+        }
+        Commands::Export { listen, time } => {
+            let config = monitor::prometheus::ExportConfig {
+                listen: listen.clone(),
+                time_step: time,
+            };
+            if let Err(e) = monitor::prometheus::run(config) {
+                eprintln!("Error: Failed to serve metrics on {}: {}", listen, e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Replay {
+            file,
+            speed,
+            history,
+        } => {
+            let config = monitor::replay::ReplayConfig {
+                path: file.clone(),
+                speed,
+                history,
+            };
+            if let Err(e) = monitor::replay::run(config) {
+                eprintln!("Error: Failed to replay {}: {}", file.display(), e);
+                std::process::exit(1);
+            }
         }
         Commands::Weather {
             location,
@@ -757,6 +1180,21 @@ fn main() -> io::Result<()> {
             };
             viz::tokeneater::run(config)?;
         }
+        Commands::Theme {
+            action:
+                ThemeCommand::Import {
+                    file,
+                    name,
+                    format,
+                    force,
+                },
+        } => match theme::import(&file, name, format, force) {
+            Ok(path) => println!("Imported {} to {}", file.display(), path.display()),
+            Err(e) => {
+                eprintln!("Error: Failed to import {}: {}", file.display(), e);
+                std::process::exit(1);
+            }
+        },
         Commands::CodexTokens { time, refresh } => {
             let config = viz::codex_tokens::CodexTokenConfig {
                 time_step: time,
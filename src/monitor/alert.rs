@@ -0,0 +1,593 @@
+//! Threshold alerts
+//!
+//! `[alerts]` in config.toml lists rules such as `cpu.total > 90 for 30s` or
+//! `disk./home.percent > 95`. The monitors check them after every sample. A
+//! rule fires once its condition has held for its duration: the offending row
+//! flashes, the terminal bell rings and, when configured, `notify-send` or a
+//! user command runs. It fires again only after the reading recovers.
+
+use crate::monitor::output::{Collector, Snapshot};
+use crate::monitor::{command_output_with_timeout, MonitorState};
+use crate::settings::AlertSettings;
+use crate::terminal::{Attrs, Terminal};
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Metrics each monitor offers, and whether they are per item
+/// (`disk./home.percent`) rather than monitor-wide (`cpu.total`)
+const METRICS: [(&str, bool, &[&str]); 7] = [
+    ("cpu", false, &["total", "iowait", "temp", "load"]),
+    ("mem", false, &["percent", "swap"]),
+    ("disk", true, &["percent"]),
+    ("io", true, &["read", "write"]),
+    ("net", true, &["rx", "tx"]),
+    ("gpu", true, &["util", "temp", "mem", "power"]),
+    ("docker", true, &["cpu", "mem"]),
+];
+
+/// Rates are compared in MiB/s
+const MIB: f64 = 1024.0 * 1024.0;
+
+/// How long a flashing row stays in each phase
+const FLASH_PHASE: Duration = Duration::from_millis(500);
+
+/// Hooks get this long before they are killed
+const HOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+    Above,
+    AtLeast,
+    Below,
+    AtMost,
+}
+
+impl Comparison {
+    fn parse(op: &str) -> Option<Self> {
+        match op {
+            ">" => Some(Comparison::Above),
+            ">=" => Some(Comparison::AtLeast),
+            "<" => Some(Comparison::Below),
+            "<=" => Some(Comparison::AtMost),
+            _ => None,
+        }
+    }
+
+    fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Above => value > threshold,
+            Comparison::AtLeast => value >= threshold,
+            Comparison::Below => value < threshold,
+            Comparison::AtMost => value <= threshold,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Comparison::Above => ">",
+            Comparison::AtLeast => ">=",
+            Comparison::Below => "<",
+            Comparison::AtMost => "<=",
+        }
+    }
+}
+
+/// One parsed `[alerts]` rule
+#[derive(Clone, Debug, PartialEq)]
+struct AlertRule {
+    monitor: &'static str,
+    /// Item to watch; every item of a list monitor when `None`
+    item: Option<String>,
+    metric: &'static str,
+    comparison: Comparison,
+    threshold: f64,
+    /// How long the condition must hold before the rule fires
+    hold: Duration,
+}
+
+impl AlertRule {
+    /// Parse `<monitor>[.<item>].<metric> <op> <threshold> [for <duration>]`
+    fn parse(text: &str) -> Result<Self, String> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let (path, op, threshold, hold) = match words[..] {
+            [path, op, threshold] => (path, op, threshold, None),
+            [path, op, threshold, "for", hold] => (path, op, threshold, Some(hold)),
+            _ => {
+                return Err(format!(
+                    "'{text}' is not '<metric> <op> <threshold> [for <duration>]'"
+                ))
+            }
+        };
+
+        let (name, rest) = path.split_once('.').unwrap_or((path, ""));
+        let Some(&(monitor, per_item, metrics)) = METRICS.iter().find(|(m, _, _)| *m == name)
+        else {
+            return Err(format!(
+                "unknown monitor '{name}' in '{text}' (expected cpu, mem, disk, io, net, gpu or docker)"
+            ));
+        };
+        let (item, metric) = match rest.rsplit_once('.') {
+            Some((item, metric)) => (Some(item), metric),
+            None => (None, rest),
+        };
+        let Some(&metric) = metrics.iter().find(|m| **m == metric) else {
+            return Err(format!(
+                "unknown metric '{metric}' in '{text}' ({monitor} has {})",
+                metrics.join(", ")
+            ));
+        };
+        if item.is_some() && !per_item {
+            return Err(format!("{monitor} metrics take no item in '{text}'"));
+        }
+        if item.is_some_and(str::is_empty) {
+            return Err(format!("empty item in '{text}'"));
+        }
+
+        let comparison = Comparison::parse(op).ok_or_else(|| {
+            format!("unknown comparison '{op}' in '{text}' (expected >, >=, < or <=)")
+        })?;
+        let threshold = threshold
+            .parse::<f64>()
+            .ok()
+            .filter(|t| t.is_finite())
+            .ok_or_else(|| format!("threshold '{threshold}' in '{text}' is not a number"))?;
+        let hold = match hold {
+            Some(hold) => parse_duration(hold).ok_or_else(|| {
+                format!("duration '{hold}' in '{text}' is not like 30s, 5m or 1h")
+            })?,
+            None => Duration::ZERO,
+        };
+
+        Ok(Self {
+            monitor,
+            item: item.map(str::to_string),
+            metric,
+            comparison,
+            threshold,
+            hold,
+        })
+    }
+
+    /// The rule's metric path for one reading, e.g. `disk./home.percent`
+    fn path(&self, item: Option<&str>) -> String {
+        match item {
+            Some(item) => format!("{}.{item}.{}", self.monitor, self.metric),
+            None => format!("{}.{}", self.monitor, self.metric),
+        }
+    }
+}
+
+/// `30s`, `5m`, `1h`, or plain seconds
+fn parse_duration(text: &str) -> Option<Duration> {
+    let (number, scale) = if let Some(n) = text.strip_suffix('h') {
+        (n, 3600.0)
+    } else if let Some(n) = text.strip_suffix('m') {
+        (n, 60.0)
+    } else {
+        (text.strip_suffix('s').unwrap_or(text), 1.0)
+    };
+    number
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite() && *n >= 0.0)
+        .map(|n| Duration::from_secs_f64(n * scale))
+}
+
+/// One value a rule can test
+#[derive(Debug, PartialEq)]
+struct Reading {
+    item: Option<String>,
+    metric: &'static str,
+    value: f64,
+}
+
+impl Reading {
+    fn new(item: Option<&str>, metric: &'static str, value: impl Into<f64>) -> Self {
+        Self {
+            item: item.map(str::to_string),
+            metric,
+            value: value.into(),
+        }
+    }
+}
+
+/// The monitor a snapshot came from and the readings rules can test
+fn readings(snapshot: &Snapshot) -> (&'static str, Vec<Reading>) {
+    let mut readings = Vec::new();
+    let monitor = match snapshot {
+        Snapshot::Cpu(cpu) => {
+            readings.push(Reading::new(None, "total", cpu.total));
+            readings.push(Reading::new(None, "iowait", cpu.iowait));
+            if let Some(temp) = cpu.temperature {
+                readings.push(Reading::new(None, "temp", temp));
+            }
+            if let Some([load, _, _]) = cpu.load {
+                readings.push(Reading::new(None, "load", load));
+            }
+            "cpu"
+        }
+        Snapshot::Mem(info) => {
+            readings.push(Reading::new(None, "percent", info.mem_percent()));
+            if info.swap_total > 0 {
+                readings.push(Reading::new(None, "swap", info.swap_percent()));
+            }
+            "mem"
+        }
+        Snapshot::Disk { filesystems } => {
+            for fs in filesystems.iter() {
+                readings.push(Reading::new(Some(&fs.mount_point), "percent", fs.percent()));
+            }
+            "disk"
+        }
+        Snapshot::Io { disks } => {
+            for disk in disks.iter() {
+                let name = Some(disk.name.as_str());
+                readings.push(Reading::new(name, "read", disk.read_rate / MIB));
+                readings.push(Reading::new(name, "write", disk.write_rate / MIB));
+            }
+            "io"
+        }
+        Snapshot::Net { interfaces } => {
+            for iface in interfaces.iter() {
+                let name = Some(iface.name.as_str());
+                readings.push(Reading::new(name, "rx", iface.rx_rate / MIB));
+                readings.push(Reading::new(name, "tx", iface.tx_rate / MIB));
+            }
+            "net"
+        }
+        Snapshot::Gpu { gpus } => {
+            for (index, gpu) in gpus.iter().enumerate() {
+                let index = index.to_string();
+                let item = Some(index.as_str());
+                readings.push(Reading::new(item, "util", gpu.utilization));
+                readings.push(Reading::new(item, "mem", gpu.memory_percent()));
+                if let Some(temp) = gpu.temperature {
+                    readings.push(Reading::new(item, "temp", temp));
+                }
+                if let Some(power) = gpu.power_draw {
+                    readings.push(Reading::new(item, "power", power));
+                }
+            }
+            "gpu"
+        }
+        Snapshot::Docker { containers } => {
+            for container in containers.iter().filter(|c| c.has_stats()) {
+                let name = Some(container.name.as_str());
+                readings.push(Reading::new(name, "cpu", container.stats.cpu_pct));
+                readings.push(Reading::new(name, "mem", container.stats.mem_pct));
+            }
+            "docker"
+        }
+        Snapshot::Ps { .. } => "ps",
+        Snapshot::Cgroups { .. } => "cgroups",
+        Snapshot::Sensors { .. } => "sensors",
+    };
+    (monitor, readings)
+}
+
+/// Rows a monitor flashes for its firing alerts, keyed by item name, or by
+/// metric for monitor-wide rules
+#[derive(Clone, Debug, Default)]
+pub struct Flash {
+    keys: HashSet<String>,
+    since: Option<Instant>,
+}
+
+impl Flash {
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys.contains(key)
+    }
+
+    /// Whether any row is flashing
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn is_active(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    /// Whether flashing rows are in their highlighted phase
+    fn lit(&self) -> bool {
+        self.since
+            .is_some_and(|since| (since.elapsed().as_millis() / FLASH_PHASE.as_millis()) % 2 == 0)
+    }
+
+    /// Flash a drawn row if any of `keys` is alerting
+    pub fn mark(&self, term: &mut Terminal, x: i32, y: i32, width: usize, keys: &[&str]) {
+        if self.lit() && keys.iter().any(|key| self.contains(key)) {
+            term.toggle_attrs(x, y, width, Attrs::REVERSE);
+        }
+    }
+}
+
+/// A rule and the readings it is tracking, keyed by item ("" for
+/// monitor-wide rules)
+struct RuleState {
+    text: String,
+    rule: AlertRule,
+    /// When each reading started meeting the condition
+    pending: HashMap<String, Instant>,
+    firing: HashSet<String>,
+}
+
+/// An alert that just fired
+#[derive(Debug, PartialEq)]
+struct Fired {
+    rule: String,
+    metric: String,
+    value: f64,
+    threshold: f64,
+    message: String,
+}
+
+/// Every configured rule with its hooks
+#[derive(Default)]
+pub struct Alerts {
+    rules: Vec<RuleState>,
+    notify: bool,
+    command: Option<String>,
+    flash_since: Option<Instant>,
+}
+
+impl Alerts {
+    pub fn from_settings(settings: &AlertSettings) -> Result<Self, String> {
+        let rules = settings
+            .rules
+            .iter()
+            .map(|text| {
+                AlertRule::parse(text).map(|rule| RuleState {
+                    text: text.trim().to_string(),
+                    rule,
+                    pending: HashMap::new(),
+                    firing: HashSet::new(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            rules,
+            notify: settings.notify,
+            command: settings.command.clone(),
+            flash_since: None,
+        })
+    }
+
+    /// Test the rules against the monitor's latest sample. Newly fired alerts
+    /// ring the bell and run the hooks; the result is what the monitor should
+    /// flash.
+    pub fn check(
+        &mut self,
+        collector: &impl Collector,
+        state: &mut MonitorState,
+        term: &Terminal,
+    ) -> Flash {
+        if self.rules.is_empty() {
+            return Flash::default();
+        }
+        let (fired, flash) = self.evaluate(&collector.snapshot(), Instant::now());
+
+        if let Some(alert) = fired.first() {
+            let _ = term.write_raw(b"\x07");
+            state.set_feedback(format!("ALERT {}", alert.message));
+        }
+        for alert in &fired {
+            self.run_hooks(alert);
+        }
+        state.set_alerts(
+            self.rules
+                .iter()
+                .flat_map(|rule| {
+                    let mut items: Vec<&String> = rule.firing.iter().collect();
+                    items.sort();
+                    items
+                        .into_iter()
+                        .map(|item| rule.rule.path((!item.is_empty()).then_some(item.as_str())))
+                })
+                .collect(),
+        );
+        flash
+    }
+
+    fn evaluate(&mut self, snapshot: &Snapshot, now: Instant) -> (Vec<Fired>, Flash) {
+        let (monitor, readings) = readings(snapshot);
+        let mut fired = Vec::new();
+        let mut keys = HashSet::new();
+
+        for state in self.rules.iter_mut().filter(|s| s.rule.monitor == monitor) {
+            let rule = &state.rule;
+            let mut seen = HashSet::new();
+            for reading in &readings {
+                if reading.metric != rule.metric
+                    || (rule.item.is_some() && rule.item != reading.item)
+                {
+                    continue;
+                }
+                let item = reading.item.clone().unwrap_or_default();
+                seen.insert(item.clone());
+                if !rule.comparison.holds(reading.value, rule.threshold) {
+                    state.pending.remove(&item);
+                    state.firing.remove(&item);
+                    continue;
+                }
+
+                let since = *state.pending.entry(item.clone()).or_insert(now);
+                if now.duration_since(since) < rule.hold {
+                    continue;
+                }
+                keys.insert(reading.item.clone().unwrap_or(rule.metric.to_string()));
+                if state.firing.insert(item) {
+                    let metric = rule.path(reading.item.as_deref());
+                    fired.push(Fired {
+                        rule: state.text.clone(),
+                        message: format!(
+                            "{metric} {} {} {}",
+                            format_number(reading.value),
+                            rule.comparison.symbol(),
+                            format_number(rule.threshold)
+                        ),
+                        metric,
+                        value: reading.value,
+                        threshold: rule.threshold,
+                    });
+                }
+            }
+            // Items that disappeared (an unmounted disk, a stopped container)
+            // are no longer alerting
+            state.pending.retain(|item, _| seen.contains(item));
+            state.firing.retain(|item| seen.contains(item));
+        }
+
+        if keys.is_empty() {
+            self.flash_since = None;
+        } else if !fired.is_empty() || self.flash_since.is_none() {
+            self.flash_since = Some(now);
+        }
+        let flash = Flash {
+            keys,
+            since: self.flash_since,
+        };
+        (fired, flash)
+    }
+
+    /// Send the desktop notification and run the user command in the
+    /// background, so a slow hook never stalls the monitor
+    fn run_hooks(&self, alert: &Fired) {
+        if self.notify {
+            let mut command = Command::new("notify-send");
+            command
+                .args(["--app-name=termart", "termart alert"])
+                .arg(&alert.message);
+            thread::spawn(move || command_output_with_timeout(&mut command, HOOK_TIMEOUT));
+        }
+        if let Some(hook) = &self.command {
+            let mut command = Command::new("sh");
+            command
+                .arg("-c")
+                .arg(hook)
+                .env("TERMART_ALERT", &alert.message)
+                .env("TERMART_ALERT_RULE", &alert.rule)
+                .env("TERMART_ALERT_METRIC", &alert.metric)
+                .env("TERMART_ALERT_VALUE", format_number(alert.value))
+                .env("TERMART_ALERT_THRESHOLD", format_number(alert.threshold));
+            thread::spawn(move || command_output_with_timeout(&mut command, HOOK_TIMEOUT));
+        }
+    }
+}
+
+/// Readings to one decimal place, without a trailing `.0`
+fn format_number(value: f64) -> String {
+    let text = format!("{value:.1}");
+    text.strip_suffix(".0").map(str::to_string).unwrap_or(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AlertRule, Alerts, Comparison};
+    use crate::monitor::disk::DiskInfo;
+    use crate::monitor::mem::MemInfo;
+    use crate::monitor::output::Snapshot;
+    use crate::settings::AlertSettings;
+    use std::time::{Duration, Instant};
+
+    fn alerts(rules: &[&str]) -> Alerts {
+        let settings = AlertSettings {
+            rules: rules.iter().map(|r| r.to_string()).collect(),
+            ..AlertSettings::default()
+        };
+        Alerts::from_settings(&settings).expect("valid rules")
+    }
+
+    fn mem(percent: u64) -> MemInfo {
+        MemInfo {
+            mem_total: 100,
+            mem_available: 100 - percent,
+            mem_free: 0,
+            buffers: 0,
+            cached: 0,
+            swap_total: 0,
+            swap_free: 0,
+        }
+    }
+
+    #[test]
+    fn rules_parse_paths_comparisons_and_durations() {
+        let rule = AlertRule::parse("disk./home.percent >= 95 for 2m").expect("valid rule");
+        assert_eq!(rule.monitor, "disk");
+        assert_eq!(rule.item.as_deref(), Some("/home"));
+        assert_eq!(rule.metric, "percent");
+        assert_eq!(rule.comparison, Comparison::AtLeast);
+        assert_eq!(rule.hold, Duration::from_secs(120));
+
+        let rule = AlertRule::parse("docker.web.app.cpu > 200").expect("dotted item");
+        assert_eq!(rule.item.as_deref(), Some("web.app"));
+        assert_eq!(
+            AlertRule::parse("gpu.temp < 80").expect("all gpus").item,
+            None
+        );
+
+        for (text, error) in [
+            ("cpu.total 90", "is not '<metric> <op> <threshold>"),
+            ("swap.used > 1", "unknown monitor 'swap'"),
+            (
+                "cpu.busy > 1",
+                "unknown metric 'busy' in 'cpu.busy > 1' (cpu has total",
+            ),
+            ("cpu.0.total > 1", "cpu metrics take no item"),
+            ("mem.percent = 1", "unknown comparison '='"),
+            ("mem.percent > high", "threshold 'high'"),
+            ("mem.percent > 1 for soon", "duration 'soon'"),
+        ] {
+            let message = AlertRule::parse(text).expect_err(text);
+            assert!(message.contains(error), "{text}: {message}");
+        }
+    }
+
+    #[test]
+    fn alerts_fire_once_after_holding_and_rearm_when_the_reading_recovers() {
+        let mut alerts = alerts(&["mem.percent > 85 for 30s"]);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        let (fired, flash) = alerts.evaluate(&Snapshot::Mem(&mem(90)), at(0));
+        assert!(fired.is_empty() && !flash.is_active());
+        let (fired, flash) = alerts.evaluate(&Snapshot::Mem(&mem(90)), at(30));
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].message, "mem.percent 90 > 85");
+        assert!(flash.contains("percent"));
+
+        let (fired, flash) = alerts.evaluate(&Snapshot::Mem(&mem(95)), at(31));
+        assert!(fired.is_empty() && flash.is_active());
+
+        let (_, flash) = alerts.evaluate(&Snapshot::Mem(&mem(50)), at(32));
+        assert!(!flash.is_active());
+        alerts.evaluate(&Snapshot::Mem(&mem(90)), at(33));
+        let (fired, _) = alerts.evaluate(&Snapshot::Mem(&mem(90)), at(63));
+        assert_eq!(fired.len(), 1);
+    }
+
+    #[test]
+    fn list_rules_track_each_item_and_only_match_their_monitor() {
+        let mut alerts = alerts(&["disk.percent > 90", "disk./home.percent > 50"]);
+        let disk = |mount: &str, used| DiskInfo {
+            mount_point: mount.to_string(),
+            total: 100,
+            used,
+            available: 100 - used,
+        };
+        let filesystems = [disk("/", 95), disk("/home", 60), disk("/boot", 10)];
+        let (fired, flash) = alerts.evaluate(
+            &Snapshot::Disk {
+                filesystems: &filesystems,
+            },
+            Instant::now(),
+        );
+        let messages: Vec<&str> = fired.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["disk./.percent 95 > 90", "disk./home.percent 60 > 50"]
+        );
+        assert!(flash.contains("/") && flash.contains("/home") && !flash.contains("/boot"));
+
+        let (fired, flash) = alerts.evaluate(&Snapshot::Mem(&mem(99)), Instant::now());
+        assert!(fired.is_empty() && !flash.is_active());
+    }
+}
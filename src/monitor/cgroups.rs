@@ -0,0 +1,789 @@
+//! Control group monitor - per-slice and per-service usage from cgroup v2
+//!
+//! Every group under the cgroup2 mount is read on each sample and shown as a
+//! tree. A group's files already count everything below it, so a slice's row
+//! is the total of its services.
+
+use crate::colors::ColorState;
+use crate::help::{HelpEntry, HelpSpec};
+use crate::monitor::layout::{
+    cpu_gradient_color_scheme, draw_meter_btop_scheme, format_bytes, format_rate,
+    muted_color_scheme, text_color_scheme,
+};
+use crate::monitor::output::{self, Collector, OutputConfig, Snapshot};
+use crate::monitor::ps::get_mem_total;
+use crate::monitor::tree::{self, TreeRow};
+use crate::monitor::{MonitorAction, MonitorState};
+use crate::terminal::{Attrs, Terminal};
+use crate::unicode;
+use crossterm::event::KeyCode;
+use crossterm::style::Color;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Where systemd mounts the unified hierarchy
+pub const DEFAULT_ROOT: &str = "/sys/fs/cgroup";
+
+/// Width of the CPU and memory meters
+const METER_WIDTH: usize = 8;
+/// Columns after the name: a meter and a value for CPU and memory, two rates
+/// for IO and the process count
+const CPU_WIDTH: usize = METER_WIDTH + 8;
+const MEM_WIDTH: usize = METER_WIDTH + 10;
+const IO_WIDTH: usize = 24;
+const PIDS_WIDTH: usize = 7;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SortBy {
+    Cpu,
+    Mem,
+    Io,
+    Pids,
+    Name,
+}
+
+impl SortBy {
+    fn next(self) -> Self {
+        match self {
+            SortBy::Cpu => SortBy::Mem,
+            SortBy::Mem => SortBy::Io,
+            SortBy::Io => SortBy::Pids,
+            SortBy::Pids => SortBy::Name,
+            SortBy::Name => SortBy::Cpu,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SortBy::Cpu => "CPU",
+            SortBy::Mem => "MEMORY",
+            SortBy::Io => "IO",
+            SortBy::Pids => "PIDS",
+            SortBy::Name => "CGROUP",
+        }
+    }
+
+    /// Usage sorts put the busiest group first, names sort A-Z
+    fn compare(self, a: &CgroupInfo, b: &CgroupInfo) -> Ordering {
+        match self {
+            SortBy::Cpu => b.cpu_pct.total_cmp(&a.cpu_pct),
+            SortBy::Mem => b.mem_current.cmp(&a.mem_current),
+            SortBy::Io => {
+                (b.io_read_rate + b.io_write_rate).total_cmp(&(a.io_read_rate + a.io_write_rate))
+            }
+            SortBy::Pids => b.pids.cmp(&a.pids),
+            SortBy::Name => a.name().cmp(b.name()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CgroupInfo {
+    /// Path below the root, e.g. `system.slice/nginx.service`
+    pub path: String,
+    /// Share of one CPU, from `cpu.stat` usage since the last sample
+    pub cpu_pct: f32,
+    pub mem_current: u64,
+    /// `memory.max`; `None` when unlimited or the memory controller is off
+    pub mem_max: Option<u64>,
+    /// `memory.current` against `memory.max`, or against RAM when unlimited
+    pub mem_pct: f32,
+    pub io_read_rate: f64,
+    pub io_write_rate: f64,
+    pub pids: u64,
+    pub pids_max: Option<u64>,
+    #[serde(skip)]
+    usage_usec: u64,
+    #[serde(skip)]
+    io_read_bytes: u64,
+    #[serde(skip)]
+    io_write_bytes: u64,
+}
+
+impl CgroupInfo {
+    /// The last path component, e.g. `nginx.service`
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+
+    /// Path of the parent group; empty for groups directly under the root
+    fn parent(&self) -> &str {
+        self.path.rsplit_once('/').map_or("", |(parent, _)| parent)
+    }
+
+    /// Read the group in `dir`; files of controllers that are not enabled
+    /// for it read as zero
+    fn read(dir: &Path, path: String) -> Self {
+        let (io_read_bytes, io_write_bytes) = read_io_bytes(dir);
+        Self {
+            path,
+            mem_current: read_number(dir, "memory.current").unwrap_or(0),
+            mem_max: read_number(dir, "memory.max"),
+            pids: read_number(dir, "pids.current").unwrap_or(0),
+            pids_max: read_number(dir, "pids.max"),
+            usage_usec: read_keyed(dir, "cpu.stat", "usage_usec").unwrap_or(0),
+            io_read_bytes,
+            io_write_bytes,
+            ..Self::default()
+        }
+    }
+}
+
+pub struct CgroupMonitor {
+    root: PathBuf,
+    /// Every group, parents before their children
+    groups: Vec<CgroupInfo>,
+    mem_total: u64,
+    sort_by: SortBy,
+    /// Groups whose children are hidden, by path
+    collapsed: HashSet<String>,
+    selected: Option<String>,
+    error_msg: Option<String>,
+}
+
+impl CgroupMonitor {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        // Hybrid systemd setups keep v1 controllers at the usual mount and the
+        // unified hierarchy under it
+        let mut root = root.into();
+        if !is_v2(&root) && is_v2(&root.join("unified")) {
+            root.push("unified");
+        }
+        Self {
+            root,
+            groups: Vec::new(),
+            mem_total: get_mem_total().unwrap_or(1),
+            sort_by: SortBy::Cpu,
+            collapsed: HashSet::new(),
+            selected: None,
+            error_msg: None,
+        }
+    }
+
+    pub fn update(&mut self, elapsed: f32) -> io::Result<()> {
+        if !is_v2(&self.root) {
+            let message = format!("No cgroup v2 hierarchy at {}", self.root.display());
+            self.groups.clear();
+            self.reconcile_selection();
+            self.error_msg = Some(message.clone());
+            return Err(io::Error::new(io::ErrorKind::NotFound, message));
+        }
+
+        let mut groups = Vec::new();
+        walk(&self.root, "", &mut groups);
+
+        let seconds = elapsed.max(f32::EPSILON) as f64;
+        let previous: HashMap<&str, &CgroupInfo> = self
+            .groups
+            .iter()
+            .map(|group| (group.path.as_str(), group))
+            .collect();
+        for group in &mut groups {
+            let limit = group.mem_max.unwrap_or(self.mem_total).max(1);
+            group.mem_pct = (group.mem_current as f64 / limit as f64 * 100.0) as f32;
+            // A group seen for the first time has no rates until the next sample
+            let Some(prev) = previous.get(group.path.as_str()) else {
+                continue;
+            };
+            let usage = group.usage_usec.saturating_sub(prev.usage_usec);
+            group.cpu_pct = (usage as f64 / 1e6 / seconds * 100.0) as f32;
+            group.io_read_rate =
+                group.io_read_bytes.saturating_sub(prev.io_read_bytes) as f64 / seconds;
+            group.io_write_rate =
+                group.io_write_bytes.saturating_sub(prev.io_write_bytes) as f64 / seconds;
+        }
+
+        self.groups = groups;
+        let paths: HashSet<&str> = self.groups.iter().map(|g| g.path.as_str()).collect();
+        self.collapsed.retain(|path| paths.contains(path.as_str()));
+        self.error_msg = None;
+        self.reconcile_selection();
+        Ok(())
+    }
+
+    pub fn cycle_sort(&mut self) {
+        self.sort_by = self.sort_by.next();
+    }
+
+    pub fn sort_label(&self) -> &'static str {
+        self.sort_by.label()
+    }
+
+    pub fn select_next(&mut self) {
+        self.move_selection(1);
+    }
+
+    pub fn select_previous(&mut self) {
+        self.move_selection(-1);
+    }
+
+    pub fn selection_label(&self) -> Option<String> {
+        self.selected_group().map(|group| group.path.clone())
+    }
+
+    /// Hide or show the selected group's children; false if it has none
+    pub fn set_collapsed(&mut self, collapsed: bool) -> bool {
+        let Some(path) = self.selected.clone() else {
+            return false;
+        };
+        if !self.groups.iter().any(|group| group.parent() == path) {
+            return false;
+        }
+        if collapsed {
+            self.collapsed.insert(path)
+        } else {
+            self.collapsed.remove(&path)
+        }
+    }
+
+    pub fn toggle_collapsed(&mut self) -> bool {
+        let collapsed = self
+            .selected
+            .as_ref()
+            .is_some_and(|path| self.collapsed.contains(path));
+        self.set_collapsed(!collapsed)
+    }
+
+    fn selected_group(&self) -> Option<&CgroupInfo> {
+        let path = self.selected.as_deref()?;
+        self.groups.iter().find(|group| group.path == path)
+    }
+
+    fn move_selection(&mut self, direction: i32) {
+        let rows = self.rows();
+        if rows.is_empty() {
+            self.selected = None;
+            return;
+        }
+
+        let current = self.selected_row(&rows).unwrap_or(0);
+        let next = if direction < 0 {
+            current.checked_sub(1).unwrap_or(rows.len() - 1)
+        } else {
+            (current + 1) % rows.len()
+        };
+        self.selected = Some(self.groups[rows[next].index].path.clone());
+    }
+
+    /// Keep the selection on a listed row; a group that went away or is
+    /// hidden in a collapsed subtree hands it to the first row
+    fn reconcile_selection(&mut self) {
+        let rows = self.rows();
+        if rows.is_empty() {
+            self.selected = None;
+        } else if self.selected_row(&rows).is_none() {
+            self.selected = Some(self.groups[rows[0].index].path.clone());
+        }
+    }
+
+    fn selected_row(&self, rows: &[TreeRow]) -> Option<usize> {
+        let path = self.selected.as_deref()?;
+        rows.iter()
+            .position(|row| self.groups[row.index].path == path)
+    }
+
+    /// Groups under their parents, siblings in the sort order
+    fn rows(&self) -> Vec<TreeRow> {
+        let by_path: HashMap<&str, usize> = self
+            .groups
+            .iter()
+            .enumerate()
+            .map(|(index, group)| (group.path.as_str(), index))
+            .collect();
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); self.groups.len()];
+        let mut roots = Vec::new();
+        for (index, group) in self.groups.iter().enumerate() {
+            match by_path.get(group.parent()) {
+                Some(&parent) => children[parent].push(index),
+                None => roots.push(index),
+            }
+        }
+
+        let order = |a: &usize, b: &usize| {
+            self.sort_by
+                .compare(&self.groups[*a], &self.groups[*b])
+                .then(a.cmp(b))
+        };
+        roots.sort_by(order);
+        for siblings in &mut children {
+            siblings.sort_by(order);
+        }
+
+        tree::rows(&roots, &children, |index| {
+            self.collapsed.contains(&self.groups[index].path)
+        })
+    }
+
+    pub fn render(&self, term: &mut Terminal, w: usize, h: usize, colors: &ColorState) {
+        if h < 4 || w < 60 {
+            return;
+        }
+
+        let title = format!("cgroups {}", self.root.display());
+        term.set_str(
+            0,
+            0,
+            unicode::truncate_to_width(&title, w),
+            Some(text_color_scheme(colors)),
+            true,
+        );
+        let count = format!("{} groups", self.groups.len());
+        term.set_str(
+            (w - count.len()) as i32,
+            0,
+            &count,
+            Some(muted_color_scheme(colors)),
+            false,
+        );
+
+        if let Some(msg) = &self.error_msg {
+            term.set_str(0, 2, msg, Some(Color::Red), false);
+            return;
+        }
+        if self.groups.is_empty() {
+            term.set_str(0, 2, "No cgroups", Some(muted_color_scheme(colors)), false);
+            return;
+        }
+
+        // Narrow terminals drop the IO rates before squeezing the names
+        let show_io = w >= 100;
+        let io_width = if show_io { IO_WIDTH } else { 0 };
+        let name_w = w - (1 + CPU_WIDTH + MEM_WIDTH + io_width + PIDS_WIDTH);
+
+        let sorted = |sort: SortBy| {
+            if sort == self.sort_by {
+                format!(
+                    "{}{}",
+                    sort.label(),
+                    if sort == SortBy::Name { '↑' } else { '↓' }
+                )
+            } else {
+                sort.label().to_string()
+            }
+        };
+        let mut header = format!(
+            "{:<name_w$} {:<CPU_WIDTH$}{:<MEM_WIDTH$}",
+            sorted(SortBy::Name),
+            sorted(SortBy::Cpu),
+            sorted(SortBy::Mem),
+        );
+        if show_io {
+            let io = format!("{} R/W", sorted(SortBy::Io));
+            header.push_str(&format!("{io:>IO_WIDTH$}"));
+        }
+        header.push_str(&format!("{:>PIDS_WIDTH$}", sorted(SortBy::Pids)));
+        term.set_str(
+            0,
+            1,
+            unicode::truncate_to_width(&header, w),
+            Some(text_color_scheme(colors)),
+            true,
+        );
+
+        // The selected group's limits take the last line
+        let rows = self.rows();
+        let available_rows = h - 3;
+        let show_count = rows.len().min(available_rows);
+        let selected_index = self.selected_row(&rows).unwrap_or(0);
+        let start = selected_index.saturating_add(1).saturating_sub(show_count);
+
+        for (y, row) in (2..).zip(rows.iter().skip(start).take(show_count)) {
+            let group = &self.groups[row.index];
+            let selected = self.selected.as_deref() == Some(group.path.as_str());
+            let attrs = if selected {
+                Attrs::REVERSE
+            } else {
+                Attrs::NONE
+            };
+            let mut put = |x: usize, text: &str, color: Color| {
+                term.set_str_with_attrs(x as i32, y, text, Some(color), None, attrs);
+            };
+            if selected {
+                put(0, &" ".repeat(w), text_color_scheme(colors));
+            }
+
+            let marker = match row.expandable {
+                Some(true) => "▸ ",
+                Some(false) => "▾ ",
+                None => "",
+            };
+            let name = format!("{}{marker}{}", row.prefix, group.name());
+            let name = unicode::truncate_to_width(&name, name_w.saturating_sub(1));
+            let padded = format!("{name}{}", " ".repeat(name_w - unicode::str_width(name)));
+            put(0, &padded, text_color_scheme(colors));
+
+            let cpu_x = name_w + 1;
+            let cpu = format!(" {:>5.1}%", group.cpu_pct);
+            put(
+                cpu_x + METER_WIDTH,
+                &cpu,
+                cpu_gradient_color_scheme(group.cpu_pct.min(100.0), colors),
+            );
+
+            let mem_x = cpu_x + CPU_WIDTH;
+            let mem = format!(" {:>9}", format_bytes(group.mem_current));
+            put(
+                mem_x + METER_WIDTH,
+                &mem,
+                cpu_gradient_color_scheme(group.mem_pct.min(100.0), colors),
+            );
+
+            let mut x = mem_x + MEM_WIDTH;
+            if show_io {
+                let io = format!(
+                    " {:>11} {:>11}",
+                    format_rate(group.io_read_rate),
+                    format_rate(group.io_write_rate)
+                );
+                put(x, &io, muted_color_scheme(colors));
+                x += IO_WIDTH;
+            }
+            put(
+                x,
+                &format!(" {:>6}", group.pids),
+                muted_color_scheme(colors),
+            );
+
+            draw_meter_btop_scheme(term, cpu_x as i32, y, METER_WIDTH, group.cpu_pct, colors);
+            draw_meter_btop_scheme(term, mem_x as i32, y, METER_WIDTH, group.mem_pct, colors);
+        }
+
+        if let Some(group) = self.selected_group() {
+            let limit = |max: Option<u64>, format: fn(u64) -> String| {
+                max.map_or_else(|| "max".to_string(), format)
+            };
+            let detail = format!(
+                "{}  memory {} of {}  pids {} of {}",
+                group.path,
+                format_bytes(group.mem_current),
+                limit(group.mem_max, format_bytes),
+                group.pids,
+                limit(group.pids_max, |max| max.to_string()),
+            );
+            term.set_str(
+                0,
+                h as i32 - 1,
+                unicode::truncate_to_width(&detail, w),
+                Some(muted_color_scheme(colors)),
+                false,
+            );
+        }
+    }
+}
+
+impl Collector for CgroupMonitor {
+    fn sample(&mut self, elapsed: f32) -> io::Result<()> {
+        self.update(elapsed)
+    }
+
+    fn snapshot(&self) -> Snapshot<'_> {
+        Snapshot::Cgroups {
+            groups: &self.groups,
+        }
+    }
+
+    fn needs_baseline(&self) -> bool {
+        true
+    }
+}
+
+/// Every v2 group, the root included, lists its available controllers
+fn is_v2(dir: &Path) -> bool {
+    dir.join("cgroup.controllers").exists()
+}
+
+/// Append the groups below `dir`, each followed by its own subgroups
+fn walk(dir: &Path, path: &str, groups: &mut Vec<CgroupInfo>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut children: Vec<(String, PathBuf)> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .map(|entry| {
+            (
+                entry.file_name().to_string_lossy().into_owned(),
+                entry.path(),
+            )
+        })
+        .collect();
+    children.sort();
+
+    for (name, child) in children {
+        let child_path = if path.is_empty() {
+            name
+        } else {
+            format!("{path}/{name}")
+        };
+        groups.push(CgroupInfo::read(&child, child_path.clone()));
+        walk(&child, &child_path, groups);
+    }
+}
+
+/// A single-value file; `max` (no limit) reads as `None`
+fn read_number(dir: &Path, file: &str) -> Option<u64> {
+    fs::read_to_string(dir.join(file)).ok()?.trim().parse().ok()
+}
+
+/// A `key value` line of a flat-keyed file such as `cpu.stat`
+fn read_keyed(dir: &Path, file: &str, key: &str) -> Option<u64> {
+    let content = fs::read_to_string(dir.join(file)).ok()?;
+    content.lines().find_map(|line| {
+        line.strip_prefix(key)?
+            .strip_prefix(' ')?
+            .trim()
+            .parse()
+            .ok()
+    })
+}
+
+/// Bytes read and written, summed over the devices in `io.stat`
+fn read_io_bytes(dir: &Path) -> (u64, u64) {
+    let content = fs::read_to_string(dir.join("io.stat")).unwrap_or_default();
+    let mut totals = (0, 0);
+    for field in content.split_whitespace() {
+        let Some((key, value)) = field.split_once('=') else {
+            continue;
+        };
+        let value: u64 = value.parse().unwrap_or(0);
+        match key {
+            "rbytes" => totals.0 += value,
+            "wbytes" => totals.1 += value,
+            _ => {}
+        }
+    }
+    totals
+}
+
+pub struct CgroupsConfig {
+    pub time_step: f32,
+    /// The cgroup2 mount, or a group below it to show only its subtree
+    pub root: PathBuf,
+    pub output: Option<OutputConfig>,
+}
+
+pub fn run(config: CgroupsConfig) -> io::Result<()> {
+    let mut monitor = CgroupMonitor::new(config.root);
+    if let Some(output) = config.output {
+        return output::stream(&mut monitor, output, config.time_step);
+    }
+
+    let mut term = Terminal::new(true)?;
+    let mut state = MonitorState::new(config.time_step, 0.5);
+    const HELP: HelpSpec = HelpSpec::monitor(
+        "CGROUPS",
+        &[
+            HelpEntry::new("↑/↓ or j/k", "Select group"),
+            HelpEntry::new("←/→ or h/l", "Collapse/expand group"),
+            HelpEntry::new("Enter", "Toggle group"),
+            HelpEntry::new("m/s", "Cycle sort column"),
+        ],
+    );
+
+    state.record_sample(monitor.update(1.0));
+    let mut last_sample = std::time::Instant::now();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    loop {
+        let mut action = MonitorAction::None;
+        if let Ok(Some((code, mods))) = term.check_key() {
+            match code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    monitor.select_previous();
+                    if let Some(label) = monitor.selection_label() {
+                        state.set_feedback(format!("Selected: {label}"));
+                    }
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    monitor.select_next();
+                    if let Some(label) = monitor.selection_label() {
+                        state.set_feedback(format!("Selected: {label}"));
+                    }
+                }
+                KeyCode::Left | KeyCode::Char('h') => {
+                    if monitor.set_collapsed(true) {
+                        state.set_feedback("Collapsed");
+                    }
+                }
+                KeyCode::Right | KeyCode::Char('l') => {
+                    if monitor.set_collapsed(false) {
+                        state.set_feedback("Expanded");
+                    }
+                }
+                KeyCode::Enter => {
+                    monitor.toggle_collapsed();
+                }
+                KeyCode::Char('m') | KeyCode::Char('s') => {
+                    monitor.cycle_sort();
+                    state.set_feedback(format!("Sort: {}", monitor.sort_label()));
+                }
+                _ => action = state.handle_key(code, mods),
+            }
+            if action == MonitorAction::Quit {
+                break;
+            }
+        }
+
+        if let Ok((new_w, new_h)) = term.query_size() {
+            let (cur_w, cur_h) = term.size();
+            if new_w != cur_w || new_h != cur_h {
+                term.resize(new_w, new_h);
+                term.clear_screen()?;
+            }
+        }
+
+        if state.should_sample(action) {
+            let elapsed = last_sample.elapsed().as_secs_f32().max(f32::EPSILON);
+            if state.record_sample(monitor.update(elapsed)) {
+                last_sample = std::time::Instant::now();
+            }
+        }
+
+        term.clear();
+
+        let (w, h) = term.size();
+        monitor.render(&mut term, w as usize, h as usize, &state.colors);
+        state.render_help(&mut term, w, h, &HELP);
+
+        term.present()?;
+        term.sleep(state.poll_delay());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CgroupMonitor, CgroupsConfig};
+    use crate::terminal::{run_headless, ScriptedInput};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    /// A cgroup2 hierarchy in a temporary directory
+    fn hierarchy() -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "termart-cgroups-{}-{}",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        fs::create_dir_all(&root).expect("create test hierarchy");
+        fs::write(root.join("cgroup.controllers"), "cpu io memory pids\n").expect("write");
+        root
+    }
+
+    fn group(root: &Path, path: &str, usage_usec: u64, memory: &str, io: &str) {
+        let dir = root.join(path);
+        fs::create_dir_all(&dir).expect("create group");
+        let files = [
+            (
+                "cpu.stat",
+                format!("usage_usec {usage_usec}\nuser_usec 0\n"),
+            ),
+            ("memory.current", "1048576\n".to_string()),
+            ("memory.max", format!("{memory}\n")),
+            ("io.stat", format!("{io}\n")),
+            ("pids.current", "3\n".to_string()),
+            ("pids.max", "max\n".to_string()),
+        ];
+        for (file, content) in files {
+            fs::write(dir.join(file), content).expect("write group file");
+        }
+    }
+
+    fn tree_lines(monitor: &CgroupMonitor) -> Vec<String> {
+        monitor
+            .rows()
+            .iter()
+            .map(|row| format!("{}{}", row.prefix, monitor.groups[row.index].name()))
+            .collect()
+    }
+
+    #[test]
+    fn groups_form_a_tree_with_rates_between_samples() {
+        let root = hierarchy();
+        group(&root, "system.slice", 0, "max", "8:0 rbytes=0 wbytes=0");
+        group(&root, "system.slice/db.service", 0, "4194304", "");
+        group(&root, "system.slice/web.service", 0, "max", "");
+        group(&root, "user.slice", 0, "max", "");
+
+        let mut monitor = CgroupMonitor::new(&root);
+        monitor.update(1.0).expect("read hierarchy");
+        let db = &monitor.groups[1];
+        assert_eq!(db.path, "system.slice/db.service");
+        assert_eq!(db.mem_max, Some(4_194_304));
+        assert_eq!(db.mem_pct, 25.0);
+        assert_eq!(db.pids_max, None);
+        assert_eq!(db.cpu_pct, 0.0);
+
+        group(
+            &root,
+            "system.slice",
+            1_500_000,
+            "max",
+            "8:0 rbytes=4096 wbytes=0\n8:16 rbytes=4096 wbytes=2048",
+        );
+        group(&root, "system.slice/web.service", 1_000_000, "max", "");
+        monitor.update(2.0).expect("read hierarchy");
+        let slice = &monitor.groups[0];
+        assert_eq!(slice.cpu_pct, 75.0);
+        assert_eq!(slice.io_read_rate, 4096.0);
+        assert_eq!(slice.io_write_rate, 1024.0);
+
+        // Siblings order by CPU; collapsing hides the subtree
+        assert_eq!(
+            tree_lines(&monitor),
+            [
+                "system.slice",
+                "├─ web.service",
+                "└─ db.service",
+                "user.slice"
+            ]
+        );
+        assert_eq!(monitor.selection_label().as_deref(), Some("system.slice"));
+        assert!(monitor.set_collapsed(true));
+        assert_eq!(tree_lines(&monitor), ["system.slice", "user.slice"]);
+        monitor.select_next();
+        assert!(!monitor.set_collapsed(true));
+        monitor.select_previous();
+        assert!(monitor.toggle_collapsed());
+
+        monitor.select_next();
+        monitor.select_next();
+        fs::remove_dir_all(root.join("system.slice/db.service")).expect("remove group");
+        monitor.update(1.0).expect("read hierarchy");
+        assert_eq!(monitor.selection_label().as_deref(), Some("system.slice"));
+
+        fs::remove_dir_all(&root).expect("test hierarchy should be removed");
+        assert!(monitor.update(1.0).is_err());
+        assert!(monitor.groups.is_empty());
+        assert!(monitor.selection_label().is_none());
+    }
+
+    #[test]
+    fn cgroup_tree_renders_offscreen() {
+        let root = hierarchy();
+        group(&root, "system.slice", 0, "max", "");
+        group(&root, "system.slice/sshd.service", 0, "max", "");
+        let config = CgroupsConfig {
+            time_step: 1.0,
+            root: root.clone(),
+            output: None,
+        };
+        let (result, frame) =
+            run_headless(120, 12, [ScriptedInput::Idle(1)], || super::run(config));
+        result.expect("headless cgroups monitor");
+        let text = frame.text();
+        assert!(text.contains("▾ system.slice"), "{text}");
+        assert!(text.contains("└─ sshd.service"), "{text}");
+        assert!(text.contains("IO R/W"), "{text}");
+        assert!(
+            text.contains("memory 1.0MiB of max  pids 3 of max"),
+            "{text}"
+        );
+        fs::remove_dir_all(&root).expect("test hierarchy should be removed");
+    }
+}
@@ -1,25 +1,32 @@
 use crate::colors::ColorState;
 use crate::help::HelpSpec;
+use crate::monitor::alert::Flash;
+use crate::monitor::history::{HistoryStore, MonitorView, VIEW_TOGGLE_HELP};
 use crate::monitor::layout::{
-    cpu_gradient_color_scheme, draw_core_graphs_scheme, draw_meter_btop_scheme, muted_color_scheme,
-    temp_gradient_color_scheme, text_color_scheme, Rect,
+    cpu_gradient_color_scheme, draw_core_graphs_scheme, draw_history_view, draw_meter_btop_scheme,
+    muted_color_scheme, temp_gradient_color_scheme, text_color_scheme, GraphStyle, HistoryRow,
+    Rect,
 };
+use crate::monitor::output::{Collector, Snapshot};
 use crate::monitor::{MonitorAction, MonitorConfig, MonitorState};
 use crate::terminal::Terminal;
-use crossterm::terminal::size;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io;
 
-fn get_uptime() -> Option<String> {
+fn get_uptime_secs() -> Option<f64> {
     let uptime = fs::read_to_string("/proc/uptime").ok()?;
-    let secs: f64 = uptime.split_whitespace().next()?.parse().ok()?;
+    uptime.split_whitespace().next()?.parse().ok()
+}
+
+fn format_uptime(secs: f64) -> String {
     let days = (secs / 86400.0) as u64;
     let hours = ((secs % 86400.0) / 3600.0) as u64;
     let mins = ((secs % 3600.0) / 60.0) as u64;
     if days > 0 {
-        Some(format!("{}d {:02}:{:02}", days, hours, mins))
+        format!("{}d {:02}:{:02}", days, hours, mins)
     } else {
-        Some(format!("{:02}:{:02}", hours, mins))
+        format!("{:02}:{:02}", hours, mins)
     }
 }
 
@@ -202,6 +209,15 @@ pub struct CpuMonitor {
     cpu_freq_ghz: Option<f32>,
     freq_update_counter: u32,
     thermal_zone_path: Option<String>, // Cache the working thermal zone
+    // Readings taken alongside usage so rendering reads no files
+    temperature: Option<u32>,
+    core_temps: Vec<Option<u32>>,
+    load: Option<[f32; 3]>,
+    uptime_secs: Option<f64>,
+    pub history: HistoryStore,
+    pub view: MonitorView,
+    /// Rows flashing for firing alerts
+    pub flash: Flash,
 }
 
 impl CpuMonitor {
@@ -223,6 +239,13 @@ impl CpuMonitor {
             cpu_freq_ghz: get_cpu_freq(),
             freq_update_counter: 0,
             thermal_zone_path: Self::discover_thermal_zone(),
+            temperature: None,
+            core_temps: Vec::new(),
+            load: None,
+            uptime_secs: None,
+            history: HistoryStore::default(),
+            view: MonitorView::default(),
+            flash: Flash::default(),
         }
     }
 
@@ -331,6 +354,9 @@ impl CpuMonitor {
                     self.usage_per_core.push(usage);
                 }
             }
+
+            self.history.record("total", self.usage_total);
+            self.history.record("iowait", self.iowait_pct);
         }
 
         // Update CPU frequency every 10 updates (reduces file I/O)
@@ -340,15 +366,38 @@ impl CpuMonitor {
             self.freq_update_counter = 0;
         }
 
+        self.temperature = get_cpu_temp_from_path(self.thermal_zone_path.as_ref());
+        self.core_temps =
+            get_core_temps_from_path(self.thermal_zone_path.as_ref(), self.usage_per_core.len());
+        self.load = get_loadavg().map(|(one, five, fifteen)| [one, five, fifteen]);
+        self.uptime_secs = get_uptime_secs();
+
         self.prev_state = Some(current);
         Ok(())
     }
 
+    /// Show a recorded sample in place of live readings
+    pub fn restore(&mut self, snapshot: &CpuSnapshot) {
+        if snapshot.model.is_some() && snapshot.model != self.cpu_model {
+            self.cpu_model = snapshot.model.clone();
+            self.cpu_model_short = None;
+        }
+        self.usage_total = snapshot.total;
+        self.iowait_pct = snapshot.iowait;
+        self.usage_per_core = snapshot.cores.clone();
+        self.core_temps = snapshot.core_temps.clone();
+        self.cpu_freq_ghz = snapshot.freq_ghz;
+        self.temperature = snapshot.temperature;
+        self.load = snapshot.load;
+        self.uptime_secs = snapshot.uptime_secs;
+        self.history.record("total", self.usage_total);
+        self.history.record("iowait", self.iowait_pct);
+    }
+
     pub fn render_fullscreen(&self, term: &mut Terminal, w: usize, h: usize, colors: &ColorState) {
         self.render_at(term, 0, 0, w, h, colors);
     }
 
-    #[allow(dead_code)]
     pub fn render(&self, term: &mut Terminal, bx: &Rect, colors: &ColorState) {
         let x = bx.inner_x();
         let y = bx.inner_y();
@@ -366,6 +415,25 @@ impl CpuMonitor {
         h: usize,
         colors: &ColorState,
     ) {
+        if self.view == MonitorView::History {
+            let rows = [
+                HistoryRow {
+                    label: "CPU",
+                    value: format!("{:.0}%", self.usage_total),
+                    history: self.history.get("total"),
+                    max: 100.0,
+                },
+                HistoryRow {
+                    label: "IO Wait",
+                    value: format!("{:.1}%", self.iowait_pct),
+                    history: self.history.get("iowait"),
+                    max: 100.0,
+                },
+            ];
+            draw_history_view(term, x, y, w, h, "CPU", &rows, GraphStyle::Area, colors);
+            return;
+        }
+
         if h < 3 || w < 20 {
             return;
         }
@@ -433,7 +501,7 @@ impl CpuMonitor {
 
         // Total CPU meter - align with core layout below
         // Layout: label(4) + meter(dynamic) + pct(5) + space(1) + temp_meter(5) + temp(6)
-        let pkg_temp = get_cpu_temp_from_path(self.thermal_zone_path.as_ref());
+        let pkg_temp = self.temperature;
         let col_width = (info_w - 1) / 2; // Match core column width
         let label_w = 4;
         let pct_w = 5;
@@ -483,12 +551,18 @@ impl CpuMonitor {
                 false,
             );
         }
+        self.flash.mark(
+            term,
+            info_x,
+            cy,
+            col_width,
+            &["total", "iowait", "temp", "load"],
+        );
         cy += 1;
 
         // Per-core meters with temps (linear meter style)
         if visible_cores > 0 {
-            let core_temps =
-                get_core_temps_from_path(self.thermal_zone_path.as_ref(), visible_cores);
+            let core_temps = &self.core_temps[..visible_cores.min(self.core_temps.len())];
             draw_core_graphs_scheme(
                 term,
                 info_x,
@@ -496,14 +570,16 @@ impl CpuMonitor {
                 info_w,
                 cores_rows,
                 &self.usage_per_core[..visible_cores],
-                &core_temps,
+                core_temps,
                 colors,
             );
             cy += cores_rows as i32;
         }
 
         // Uptime (left), IO Wait (after uptime), Load average (right) on same line
-        let uptime_str = get_uptime().unwrap_or_else(|| "??:??".to_string());
+        let uptime_str = self
+            .uptime_secs
+            .map_or_else(|| "??:??".to_string(), format_uptime);
         let up_str = format!("up {}", uptime_str);
         term.set_str(info_x, cy, &up_str, Some(muted_color_scheme(colors)), false);
 
@@ -519,8 +595,8 @@ impl CpuMonitor {
         let iowait_x = info_x + up_str.len() as i32;
         term.set_str(iowait_x, cy, &iowait_str, Some(iowait_color), false);
 
-        let load = get_loadavg().unwrap_or((0.0, 0.0, 0.0));
-        let lav_str = format!("Load: {:.2}  {:.2}  {:.2}", load.0, load.1, load.2);
+        let load = self.load.unwrap_or_default();
+        let lav_str = format!("Load: {:.2}  {:.2}  {:.2}", load[0], load[1], load[2]);
         term.set_str(
             info_x + core_section_w as i32 - lav_str.len() as i32,
             cy,
@@ -531,11 +607,53 @@ impl CpuMonitor {
     }
 }
 
+/// CPU readings for `--format` output and recordings
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CpuSnapshot {
+    pub model: Option<String>,
+    pub total: f32,
+    pub iowait: f32,
+    pub cores: Vec<f32>,
+    pub core_temps: Vec<Option<u32>>,
+    pub freq_ghz: Option<f32>,
+    pub temperature: Option<u32>,
+    /// 1, 5 and 15 minute load averages
+    pub load: Option<[f32; 3]>,
+    pub uptime_secs: Option<f64>,
+}
+
+impl Collector for CpuMonitor {
+    fn sample(&mut self, _elapsed: f32) -> io::Result<()> {
+        self.update()
+    }
+
+    fn snapshot(&self) -> Snapshot<'_> {
+        Snapshot::Cpu(CpuSnapshot {
+            model: self.cpu_model.clone(),
+            total: self.usage_total,
+            iowait: self.iowait_pct,
+            cores: self.usage_per_core.clone(),
+            core_temps: self.core_temps.clone(),
+            freq_ghz: self.cpu_freq_ghz,
+            temperature: self.temperature,
+            load: self.load,
+            uptime_secs: self.uptime_secs,
+        })
+    }
+
+    fn needs_baseline(&self) -> bool {
+        true
+    }
+}
+
 pub fn run(config: MonitorConfig) -> io::Result<()> {
     let mut term = Terminal::new(true)?;
     let mut state = MonitorState::new(config.time_step, 0.5);
     let mut monitor = CpuMonitor::new();
-    const HELP: HelpSpec = HelpSpec::monitor("CPU MONITOR", &[]);
+    let mut sample_log = config.sample_log;
+    let mut alerts = config.alerts;
+    monitor.history.set_window(config.history);
+    const HELP: HelpSpec = HelpSpec::monitor("CPU MONITOR", &[VIEW_TOGGLE_HELP]);
 
     state.record_sample(monitor.update());
     std::thread::sleep(std::time::Duration::from_millis(100));
@@ -543,13 +661,15 @@ pub fn run(config: MonitorConfig) -> io::Result<()> {
     loop {
         let mut action = MonitorAction::None;
         if let Ok(Some((code, mods))) = term.check_key() {
-            action = state.handle_key(code, mods);
-            if action == MonitorAction::Quit {
-                break;
+            if !state.handle_view_key(code, &mut monitor.view) {
+                action = state.handle_key(code, mods);
+                if action == MonitorAction::Quit {
+                    break;
+                }
             }
         }
 
-        if let Ok((new_w, new_h)) = size() {
+        if let Ok((new_w, new_h)) = term.query_size() {
             let (cur_w, cur_h) = term.size();
             if new_w != cur_w || new_h != cur_h {
                 term.resize(new_w, new_h);
@@ -557,8 +677,9 @@ pub fn run(config: MonitorConfig) -> io::Result<()> {
             }
         }
 
-        if state.should_sample(action) {
-            state.record_sample(monitor.update());
+        if state.should_sample(action) && state.record_sample(monitor.update()) {
+            sample_log.save(&monitor, &mut state);
+            monitor.flash = alerts.check(&monitor, &mut state, &term);
         }
 
         term.clear();
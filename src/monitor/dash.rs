@@ -0,0 +1,462 @@
+//! Multi-panel dashboard
+//!
+//! Lays the system monitors out in a grid read from `[dash]` in config.toml
+//! and draws each one into a bordered panel through its `Rect` renderer.
+//! Every panel keeps its own `MonitorState`, so panels sample on independent
+//! intervals and the interval and pause keys act on the focused panel.
+
+use crate::help::{HelpEntry, HelpSpec};
+use crate::monitor::alert::Alerts;
+use crate::monitor::history::{DEFAULT_WINDOW, VIEW_TOGGLE_HELP};
+use crate::monitor::layout::{draw_panel_box, header_color_scheme, muted_color_scheme, Rect};
+use crate::monitor::{format_interval, MonitorAction, MonitorState, MonitorType, SystemMonitor};
+use crate::settings::DashSettings;
+use crate::terminal::Terminal;
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::io;
+use std::time::Instant;
+
+/// Smallest interval accepted from the `[dash.intervals]` table
+const MIN_CONFIG_INTERVAL: f32 = 0.001;
+
+/// One panel of the grid as configured
+#[derive(Clone, Debug, PartialEq)]
+pub struct PanelSpec {
+    pub kind: MonitorType,
+    /// Grid columns the panel covers within its row
+    pub span: usize,
+    /// Seconds between samples
+    pub interval: f32,
+}
+
+pub struct DashConfig {
+    pub rows: Vec<Vec<PanelSpec>>,
+    /// Samples kept for each panel's history view
+    pub history: usize,
+    /// Threshold rules checked on every panel sample
+    pub alerts: Alerts,
+}
+
+impl DashConfig {
+    /// Build the grid from config.toml. Adjacent repeats of a name in a row
+    /// merge into one wider panel; panels without an entry in `intervals`
+    /// sample every `default_interval` seconds.
+    pub fn from_settings(settings: &DashSettings, default_interval: f32) -> Result<Self, String> {
+        for (name, interval) in &settings.intervals {
+            if parse_panel(name).is_none() {
+                return Err(format!("unknown panel '{name}' in [dash.intervals]"));
+            }
+            if !interval.is_finite() || *interval < MIN_CONFIG_INTERVAL {
+                return Err(format!(
+                    "interval for '{name}' must be at least {MIN_CONFIG_INTERVAL} seconds"
+                ));
+            }
+        }
+
+        let mut rows = Vec::new();
+        for names in &settings.rows {
+            let mut row: Vec<PanelSpec> = Vec::new();
+            let mut previous: Option<&str> = None;
+            for name in names {
+                let kind = parse_panel(name).ok_or_else(|| {
+                    format!("unknown panel '{name}' (expected cpu, mem, disk, io, net or gpu)")
+                })?;
+                match row.last_mut() {
+                    Some(last) if previous == Some(name.as_str()) => last.span += 1,
+                    _ => row.push(PanelSpec {
+                        kind,
+                        span: 1,
+                        interval: settings
+                            .intervals
+                            .get(name.as_str())
+                            .copied()
+                            .unwrap_or(default_interval),
+                    }),
+                }
+                previous = Some(name);
+            }
+            if !row.is_empty() {
+                rows.push(row);
+            }
+        }
+
+        if rows.is_empty() {
+            return Err("[dash] rows must name at least one panel".to_string());
+        }
+        Ok(Self {
+            rows,
+            history: DEFAULT_WINDOW,
+            alerts: Alerts::default(),
+        })
+    }
+}
+
+fn parse_panel(name: &str) -> Option<MonitorType> {
+    match name {
+        "cpu" => Some(MonitorType::Cpu),
+        "mem" => Some(MonitorType::Mem),
+        "disk" => Some(MonitorType::Disk),
+        "io" => Some(MonitorType::Io),
+        "net" => Some(MonitorType::Net),
+        "gpu" => Some(MonitorType::Gpu),
+        _ => None,
+    }
+}
+
+/// Shortest interval each monitor supports, as in its standalone command
+fn min_interval(kind: MonitorType) -> f32 {
+    match kind {
+        MonitorType::Cpu | MonitorType::Mem | MonitorType::Gpu => 0.5,
+        MonitorType::Io | MonitorType::Net => 1.0,
+        MonitorType::Disk => 2.0,
+    }
+}
+
+struct Panel {
+    kind: MonitorType,
+    row: usize,
+    span: usize,
+    monitor: SystemMonitor,
+    state: MonitorState,
+    pending: MonitorAction,
+    last_sample: Option<Instant>,
+}
+
+impl Panel {
+    fn new(spec: &PanelSpec, row: usize, history: usize) -> Self {
+        let mut monitor = SystemMonitor::new(spec.kind);
+        monitor.set_history_window(history);
+        Self {
+            kind: spec.kind,
+            row,
+            span: spec.span,
+            monitor,
+            state: MonitorState::new(spec.interval, min_interval(spec.kind)),
+            pending: MonitorAction::None,
+            last_sample: None,
+        }
+    }
+
+    /// Sample the panel if its interval is up; true when a sample was taken
+    fn sample_if_due(&mut self) -> bool {
+        let action = std::mem::replace(&mut self.pending, MonitorAction::None);
+        if !self.state.should_sample(action) {
+            return false;
+        }
+        let elapsed = self
+            .last_sample
+            .map_or(1.0, |last| last.elapsed().as_secs_f32().max(f32::EPSILON));
+        if !self.state.record_sample(self.monitor.update(elapsed)) {
+            return false;
+        }
+        self.last_sample = Some(Instant::now());
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        match self.kind {
+            MonitorType::Cpu => "CPU",
+            MonitorType::Mem => "Memory",
+            MonitorType::Disk => "Disk",
+            MonitorType::Io => "Disk I/O",
+            MonitorType::Net => "Network",
+            MonitorType::Gpu => "GPU",
+        }
+    }
+
+    fn title(&self) -> String {
+        let status = if self.state.sample_error.is_some() {
+            "ERROR".to_string()
+        } else if self.state.paused {
+            "PAUSED".to_string()
+        } else {
+            format_interval(self.state.speed)
+        };
+        format!(" {} · {} ", self.name(), status)
+    }
+}
+
+struct Dashboard {
+    panels: Vec<Panel>,
+    rows: usize,
+    focus: usize,
+    zoomed: bool,
+    state: MonitorState,
+}
+
+impl Dashboard {
+    fn new(config: &DashConfig) -> Self {
+        let panels = config
+            .rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row, specs)| {
+                specs
+                    .iter()
+                    .map(move |spec| Panel::new(spec, row, config.history))
+            })
+            .collect();
+        Self {
+            panels,
+            rows: config.rows.len(),
+            focus: 0,
+            zoomed: false,
+            // Only colors, help and feedback live here; sampling is per panel
+            state: MonitorState::new(1.0, 0.5),
+        }
+    }
+
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> MonitorAction {
+        match code {
+            KeyCode::Tab | KeyCode::Right => self.move_focus(1),
+            KeyCode::BackTab | KeyCode::Left => self.move_focus(self.panels.len() - 1),
+            KeyCode::Enter => {
+                self.zoomed = !self.zoomed;
+                let panel = self.panels[self.focus].name();
+                self.state.set_feedback(if self.zoomed {
+                    format!("Zoom: {panel}")
+                } else {
+                    "Zoom off".to_string()
+                });
+            }
+            KeyCode::Char('g') => {
+                let panel = &mut self.panels[self.focus];
+                let message = match panel.monitor.toggle_view() {
+                    Some(view) => format!("{}: View: {}", panel.name(), view.label()),
+                    None => format!("{}: No history view", panel.name()),
+                };
+                self.state.set_feedback(message);
+            }
+            KeyCode::Char('q' | '?') | KeyCode::Esc => {
+                return self.state.handle_key(code, modifiers)
+            }
+            _ if self.state.colors.handle_key(code, modifiers) => {
+                self.state
+                    .set_feedback(format!("Color: {}", self.state.colors.name()));
+            }
+            _ => {
+                let panel = &mut self.panels[self.focus];
+                panel.pending = panel.state.handle_key(code, modifiers);
+                if let Some((message, _)) = panel.state.feedback.take() {
+                    self.state
+                        .set_feedback(format!("{}: {message}", panel.name()));
+                }
+            }
+        }
+        MonitorAction::None
+    }
+
+    fn move_focus(&mut self, step: usize) {
+        self.focus = (self.focus + step) % self.panels.len();
+        self.state
+            .set_feedback(format!("Focus: {}", self.panels[self.focus].name()));
+    }
+
+    fn render(&self, term: &mut Terminal, w: u16, h: u16) {
+        let colors = &self.state.colors;
+        let rects = if self.zoomed {
+            vec![(
+                self.focus,
+                Rect {
+                    x: 0,
+                    y: 0,
+                    width: w,
+                    height: h,
+                },
+            )]
+        } else {
+            self.panel_rects(w, h).into_iter().enumerate().collect()
+        };
+
+        for (index, bx) in rects {
+            let panel = &self.panels[index];
+            let focused = index == self.focus;
+            let border = if focused {
+                header_color_scheme(colors)
+            } else {
+                muted_color_scheme(colors)
+            };
+            draw_panel_box(term, &bx, &panel.title(), border, focused);
+            panel.monitor.render(term, &bx, colors);
+        }
+    }
+
+    /// Rows share the height equally and each row's panels share its width
+    /// in proportion to their spans
+    fn panel_rects(&self, w: u16, h: u16) -> Vec<Rect> {
+        let rows = self.rows.max(1) as u32;
+        let mut rects = Vec::with_capacity(self.panels.len());
+        for row in 0..self.rows {
+            let top = h as u32 * row as u32 / rows;
+            let bottom = h as u32 * (row as u32 + 1) / rows;
+            let panels: Vec<&Panel> = self.panels.iter().filter(|p| p.row == row).collect();
+            let columns = panels.iter().map(|p| p.span).sum::<usize>().max(1) as u32;
+
+            let mut column = 0;
+            for panel in panels {
+                let left = w as u32 * column / columns;
+                column += panel.span as u32;
+                let right = w as u32 * column / columns;
+                rects.push(Rect {
+                    x: left as i32,
+                    y: top as i32,
+                    width: (right - left) as u16,
+                    height: (bottom - top) as u16,
+                });
+            }
+        }
+        rects
+    }
+}
+
+pub fn run(config: DashConfig) -> io::Result<()> {
+    let mut term = Terminal::new(true)?;
+    let mut dash = Dashboard::new(&config);
+    let mut alerts = config.alerts;
+    const HELP: HelpSpec = HelpSpec::monitor(
+        "DASHBOARD",
+        &[
+            HelpEntry::new("Tab/→", "Focus next panel"),
+            HelpEntry::new("Shift+Tab/←", "Focus previous panel"),
+            HelpEntry::new("Enter", "Zoom focused panel"),
+            VIEW_TOGGLE_HELP,
+            HelpEntry::new("Space,r,+/-,1-9", "Act on focused panel"),
+        ],
+    );
+
+    loop {
+        if let Ok(Some((code, mods))) = term.check_key() {
+            if dash.handle_key(code, mods) == MonitorAction::Quit {
+                break;
+            }
+        }
+
+        if let Ok((new_w, new_h)) = term.query_size() {
+            let (cur_w, cur_h) = term.size();
+            if new_w != cur_w || new_h != cur_h {
+                term.resize(new_w, new_h);
+                term.clear_screen()?;
+            }
+        }
+
+        for panel in &mut dash.panels {
+            if panel.sample_if_due() {
+                let flash = alerts.check(&panel.monitor, &mut dash.state, &term);
+                panel.monitor.set_flash(flash);
+            }
+        }
+
+        term.clear();
+
+        let (w, h) = term.size();
+        dash.render(&mut term, w, h);
+        dash.state.render_help(&mut term, w, h, &HELP);
+
+        term.present()?;
+        term.sleep(dash.state.poll_delay());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, DashConfig, Dashboard, PanelSpec};
+    use crate::monitor::{MonitorAction, MonitorType};
+    use crate::settings::DashSettings;
+    use crate::terminal::{run_headless, ScriptedInput};
+    use crossterm::event::{KeyCode, KeyModifiers};
+    use std::collections::HashMap;
+
+    fn settings(rows: &[&[&str]], intervals: &[(&str, f32)]) -> DashSettings {
+        DashSettings {
+            rows: rows
+                .iter()
+                .map(|row| row.iter().map(|name| name.to_string()).collect())
+                .collect(),
+            intervals: intervals
+                .iter()
+                .map(|(name, interval)| (name.to_string(), *interval))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn config_merges_repeated_names_and_applies_intervals() {
+        let config = DashConfig::from_settings(
+            &settings(&[&["cpu", "cpu", "mem"], &[], &["net"]], &[("mem", 3.0)]),
+            1.0,
+        )
+        .expect("valid layout");
+        assert_eq!(
+            config.rows,
+            [
+                vec![
+                    PanelSpec {
+                        kind: MonitorType::Cpu,
+                        span: 2,
+                        interval: 1.0
+                    },
+                    PanelSpec {
+                        kind: MonitorType::Mem,
+                        span: 1,
+                        interval: 3.0
+                    },
+                ],
+                vec![PanelSpec {
+                    kind: MonitorType::Net,
+                    span: 1,
+                    interval: 1.0
+                }],
+            ]
+        );
+
+        assert!(DashConfig::from_settings(&settings(&[&["cpu", "swap"]], &[]), 1.0).is_err());
+        assert!(DashConfig::from_settings(&settings(&[&["cpu"]], &[("cpu", 0.0)]), 1.0).is_err());
+        assert!(DashConfig::from_settings(&settings(&[], &[]), 1.0).is_err());
+    }
+
+    #[test]
+    fn panels_tile_the_screen_and_keys_target_the_focused_panel() {
+        let config =
+            DashConfig::from_settings(&settings(&[&["cpu", "cpu", "mem"], &["mem"]], &[]), 1.0)
+                .expect("valid layout");
+        let mut dash = Dashboard::new(&config);
+        let rects: Vec<_> = dash
+            .panel_rects(90, 25)
+            .iter()
+            .map(|r| (r.x, r.y, r.width, r.height))
+            .collect();
+        assert_eq!(rects, [(0, 0, 60, 12), (60, 0, 30, 12), (0, 12, 90, 13)]);
+
+        dash.handle_key(KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(dash.focus, 1);
+        dash.handle_key(KeyCode::BackTab, KeyModifiers::NONE);
+        dash.handle_key(KeyCode::BackTab, KeyModifiers::NONE);
+        assert_eq!(dash.focus, 2);
+
+        dash.handle_key(KeyCode::Char(' '), KeyModifiers::NONE);
+        assert!(dash.panels[2].state.paused);
+        assert!(!dash.panels[0].state.paused);
+        assert_eq!(dash.panels[2].title(), " Memory · PAUSED ");
+
+        dash.handle_key(KeyCode::Char('@'), KeyModifiers::NONE);
+        assert_eq!(dash.state.colors.scheme, 2);
+        assert_eq!(
+            dash.handle_key(KeyCode::Char('q'), KeyModifiers::NONE),
+            MonitorAction::Quit
+        );
+    }
+
+    #[test]
+    fn dashboard_renders_every_panel_offscreen() {
+        let config = DashConfig::from_settings(&settings(&[&["cpu", "mem"]], &[]), 1.0)
+            .expect("valid layout");
+        let (result, frame) = run_headless(120, 30, [ScriptedInput::Idle(1)], || run(config));
+        result.expect("headless dashboard");
+        let text = frame.text();
+        assert!(text.contains("╭─ CPU · 1.0s"), "{text}");
+        assert!(text.contains("╭─ Memory · 1.0s"), "{text}");
+        assert!(text.contains("Available"), "{text}");
+    }
+}
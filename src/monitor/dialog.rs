@@ -0,0 +1,203 @@
+//! Modal dialogs for monitor actions
+//!
+//! A dialog takes every key while it is open. Actions that change the system
+//! always end in a `Confirm`; an `Input` collects a value first and turns into
+//! a `Confirm` once the value parses.
+
+use crate::help::render_help_overlay;
+use crate::terminal::Terminal;
+use crate::unicode;
+use crossterm::event::KeyCode;
+use std::fmt::Display;
+
+/// Turns the typed text into an action or an error shown under the input
+type Parser<A> = Box<dyn Fn(&str) -> Result<A, String>>;
+
+const MAX_INPUT_CHARS: usize = 64;
+
+pub enum Dialog<A> {
+    /// Ask before running `action`, which displays as the question
+    Confirm { title: &'static str, action: A },
+    Input {
+        title: &'static str,
+        label: String,
+        input: String,
+        error: Option<String>,
+        parse: Parser<A>,
+    },
+    /// Information (usually an error) closed by any key
+    Message { title: &'static str, text: String },
+}
+
+pub enum Outcome<A> {
+    Open(Dialog<A>),
+    Closed,
+    Confirmed(A),
+}
+
+impl<A: Display> Dialog<A> {
+    pub fn confirm(title: &'static str, action: A) -> Self {
+        Dialog::Confirm { title, action }
+    }
+
+    pub fn input(
+        title: &'static str,
+        label: impl Into<String>,
+        initial: impl Into<String>,
+        parse: impl Fn(&str) -> Result<A, String> + 'static,
+    ) -> Self {
+        Dialog::Input {
+            title,
+            label: label.into(),
+            input: initial.into(),
+            error: None,
+            parse: Box::new(parse),
+        }
+    }
+
+    pub fn message(title: &'static str, text: impl Into<String>) -> Self {
+        Dialog::Message {
+            title,
+            text: text.into(),
+        }
+    }
+
+    pub fn handle_key(self, code: KeyCode) -> Outcome<A> {
+        match self {
+            Dialog::Confirm { action, .. } if matches!(code, KeyCode::Char('y' | 'Y')) => {
+                Outcome::Confirmed(action)
+            }
+            Dialog::Confirm { .. }
+                if matches!(code, KeyCode::Char('n' | 'N' | 'q') | KeyCode::Esc) =>
+            {
+                Outcome::Closed
+            }
+            Dialog::Input { .. } if code == KeyCode::Esc => Outcome::Closed,
+            Dialog::Input {
+                title,
+                label,
+                mut input,
+                mut error,
+                parse,
+            } => {
+                match code {
+                    KeyCode::Enter => match parse(input.trim()) {
+                        Ok(action) => return Outcome::Open(Dialog::confirm(title, action)),
+                        Err(e) => error = Some(e),
+                    },
+                    KeyCode::Backspace => {
+                        input.pop();
+                    }
+                    KeyCode::Char(c)
+                        if !c.is_control() && input.chars().count() < MAX_INPUT_CHARS =>
+                    {
+                        input.push(c);
+                    }
+                    _ => {}
+                }
+                Outcome::Open(Dialog::Input {
+                    title,
+                    label,
+                    input,
+                    error,
+                    parse,
+                })
+            }
+            Dialog::Message { .. } => Outcome::Closed,
+            dialog => Outcome::Open(dialog),
+        }
+    }
+
+    fn text(&self) -> String {
+        let (title, body, keys) = match self {
+            Dialog::Confirm { title, action } => {
+                (*title, action.to_string(), "[y] Yes   [n/Esc] No")
+            }
+            Dialog::Input {
+                title,
+                label,
+                input,
+                error,
+                ..
+            } => {
+                let mut body = format!("{label}\n> {input}_");
+                if let Some(error) = error {
+                    body.push_str(&format!("\n{error}"));
+                }
+                (*title, body, "[Enter] OK   [Esc] Cancel")
+            }
+            Dialog::Message { title, text } => (*title, text.clone(), "Press any key"),
+        };
+        let width = body
+            .lines()
+            .chain([title, keys])
+            .map(unicode::str_width)
+            .max()
+            .unwrap_or(0);
+        format!("{title}\n{}\n{body}\n\n{keys}", "─".repeat(width))
+    }
+
+    pub fn render(&self, term: &mut Terminal, width: u16, height: u16) {
+        render_help_overlay(term, width, height, &self.text());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dialog, Outcome};
+    use crossterm::event::KeyCode;
+
+    fn press(dialog: Dialog<u32>, keys: &[KeyCode]) -> Outcome<u32> {
+        let mut outcome = Outcome::Open(dialog);
+        for &key in keys {
+            let Outcome::Open(dialog) = outcome else {
+                break;
+            };
+            outcome = dialog.handle_key(key);
+        }
+        outcome
+    }
+
+    fn parse_small(text: &str) -> Result<u32, String> {
+        match text.parse() {
+            Ok(n) if n < 10 => Ok(n),
+            _ => Err(format!("not a digit: {text}")),
+        }
+    }
+
+    #[test]
+    fn input_confirms_before_running_and_keeps_errors_inline() {
+        let dialog = Dialog::input("PICK", "Digit:", "1", parse_small);
+        let outcome = press(
+            dialog,
+            &[KeyCode::Char('2'), KeyCode::Enter, KeyCode::Backspace],
+        );
+        let Outcome::Open(dialog) = outcome else {
+            panic!("an invalid value keeps the input open");
+        };
+        assert!(dialog.text().contains("> 1_\nnot a digit: 12"));
+
+        let outcome = press(dialog, &[KeyCode::Enter]);
+        let Outcome::Open(dialog) = outcome else {
+            panic!("a valid value asks for confirmation");
+        };
+        assert_eq!(
+            dialog.text(),
+            "PICK\n────────────────────\n1\n\n[y] Yes   [n/Esc] No"
+        );
+        assert!(matches!(
+            press(dialog, &[KeyCode::Char('x'), KeyCode::Char('y')]),
+            Outcome::Confirmed(1)
+        ));
+
+        let dialog = Dialog::confirm("PICK", 3);
+        assert!(matches!(press(dialog, &[KeyCode::Esc]), Outcome::Closed));
+        let dialog = Dialog::<u32>::message("RENICE", "ビルドサーバー監視プロセス");
+        assert!(dialog.text().contains(&format!("\n{}\n", "─".repeat(26))));
+        let dialog = Dialog::<u32>::message("ERROR", "Permission denied");
+        assert!(matches!(
+            press(dialog, &[KeyCode::Char('y')]),
+            Outcome::Closed
+        ));
+    }
+}
@@ -1,13 +1,16 @@
 use crate::colors::ColorState;
 use crate::help::HelpSpec;
+use crate::monitor::alert::Flash;
 use crate::monitor::layout::{
     cpu_gradient_color_scheme, draw_meter_btop_scheme, format_bytes, header_color_scheme,
     muted_color_scheme, text_color_scheme, Rect,
 };
+use crate::monitor::output::{Collector, Snapshot};
 use crate::monitor::{MonitorAction, MonitorConfig, MonitorState};
 use crate::terminal::Terminal;
+use crate::unicode;
 use crossterm::style::Color;
-use crossterm::terminal::size;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io;
 
@@ -35,6 +38,7 @@ fn decode_mount_field(field: &str) -> String {
     String::from_utf8_lossy(&decoded).into_owned()
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct DiskInfo {
     pub mount_point: String,
     pub total: u64,
@@ -43,7 +47,7 @@ pub struct DiskInfo {
 }
 
 impl DiskInfo {
-    fn percent(&self) -> f32 {
+    pub fn percent(&self) -> f32 {
         let usable = self.used.saturating_add(self.available);
         if usable > 0 {
             (self.used as f32 / usable as f32) * 100.0
@@ -55,11 +59,16 @@ impl DiskInfo {
 
 pub struct DiskMonitor {
     pub disks: Vec<DiskInfo>,
+    /// Rows flashing for firing alerts
+    pub flash: Flash,
 }
 
 impl DiskMonitor {
     pub fn new() -> Self {
-        Self { disks: Vec::new() }
+        Self {
+            disks: Vec::new(),
+            flash: Flash::default(),
+        }
     }
 
     pub fn update(&mut self) -> io::Result<()> {
@@ -127,7 +136,6 @@ impl DiskMonitor {
         }
     }
 
-    #[allow(dead_code)]
     pub fn render(&self, term: &mut Terminal, bx: &Rect, colors: &ColorState) {
         let x = bx.inner_x();
         let y = bx.inner_y();
@@ -188,6 +196,7 @@ impl DiskMonitor {
             let pct = disk.percent();
             let size_str = format!("{}/{}", format_bytes(disk.used), format_bytes(disk.total));
             self.draw_disk_row(term, x, cy, w, &disk.mount_point, pct, &size_str, colors);
+            self.flash.mark(term, x, cy, w, &[&disk.mount_point]);
             cy += 1;
         }
 
@@ -226,19 +235,18 @@ impl DiskMonitor {
         let mut pos = x;
 
         // Mount point (truncated if needed)
-        let mount_display: String = if mount.len() < mount_w {
-            format!("{:<width$}", mount, width = mount_w)
+        let mount_display: String = if unicode::str_width(mount) < mount_w {
+            unicode::pad_to_width(mount, mount_w)
         } else if mount == "/" {
-            format!("{:<width$}", "/", width = mount_w)
+            unicode::pad_to_width("/", mount_w)
         } else {
             // Show last component
             let short = mount.split('/').next_back().unwrap_or("?");
-            if short.len() < mount_w {
-                format!("{:<width$}", short, width = mount_w)
+            if unicode::str_width(short) < mount_w {
+                unicode::pad_to_width(short, mount_w)
             } else {
-                // Truncate by chars, not bytes, to avoid panicking on a multibyte boundary.
-                let truncated: String = short.chars().take(mount_w.saturating_sub(1)).collect();
-                format!("{:<width$}", truncated, width = mount_w)
+                let truncated = unicode::truncate_to_width(short, mount_w.saturating_sub(1));
+                unicode::pad_to_width(truncated, mount_w)
             }
         };
         term.set_str(
@@ -282,10 +290,24 @@ struct StatVfs {
     bavail: u64,
 }
 
+impl Collector for DiskMonitor {
+    fn sample(&mut self, _elapsed: f32) -> io::Result<()> {
+        self.update()
+    }
+
+    fn snapshot(&self) -> Snapshot<'_> {
+        Snapshot::Disk {
+            filesystems: &self.disks,
+        }
+    }
+}
+
 pub fn run(config: MonitorConfig) -> io::Result<()> {
     let mut term = Terminal::new(true)?;
     let mut state = MonitorState::new(config.time_step, 2.0);
     let mut monitor = DiskMonitor::new();
+    let mut sample_log = config.sample_log;
+    let mut alerts = config.alerts;
     const HELP: HelpSpec = HelpSpec::monitor("DISK MONITOR", &[]);
 
     loop {
@@ -297,7 +319,7 @@ pub fn run(config: MonitorConfig) -> io::Result<()> {
             }
         }
 
-        if let Ok((new_w, new_h)) = size() {
+        if let Ok((new_w, new_h)) = term.query_size() {
             let (cur_w, cur_h) = term.size();
             if new_w != cur_w || new_h != cur_h {
                 term.resize(new_w, new_h);
@@ -305,8 +327,9 @@ pub fn run(config: MonitorConfig) -> io::Result<()> {
             }
         }
 
-        if state.should_sample(action) {
-            state.record_sample(monitor.update());
+        if state.should_sample(action) && state.record_sample(monitor.update()) {
+            sample_log.save(&monitor, &mut state);
+            monitor.flash = alerts.check(&monitor, &mut state, &term);
         }
 
         term.clear();
@@ -1,33 +1,46 @@
 use crate::colors::ColorState;
 use crate::help::HelpSpec;
+use crate::monitor::alert::Flash;
+use crate::monitor::history::{HistoryStore, MonitorView, VIEW_TOGGLE_HELP};
 use crate::monitor::layout::{
-    activity_percent, cpu_gradient_color_scheme, draw_meter_btop_scheme, format_bytes, format_rate,
-    header_color_scheme, muted_color_scheme, text_color_scheme, update_activity_scale, Rect,
+    activity_percent, cpu_gradient_color_scheme, draw_history_view, draw_meter_btop_scheme,
+    format_bytes, format_rate, header_color_scheme, muted_color_scheme, text_color_scheme,
+    update_activity_scale, GraphStyle, HistoryRow, Rect,
 };
+use crate::monitor::output::{Collector, Snapshot};
 use crate::monitor::{MonitorAction, MonitorConfig, MonitorState};
 use crate::terminal::Terminal;
 use crossterm::style::Color;
-use crossterm::terminal::size;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io;
 
-#[derive(Clone)]
-struct DiskStats {
-    name: String,
-    read_bytes: u64,
-    write_bytes: u64,
-    read_rate: f64,
-    write_rate: f64,
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DiskStats {
+    pub name: String,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    pub read_rate: f64,
+    pub write_rate: f64,
+    #[serde(skip)]
     prev_read_bytes: u64,
+    #[serde(skip)]
     prev_write_bytes: u64,
 }
 
+/// Smallest full-scale value of the history graphs, so idle noise stays flat
+const HISTORY_SCALE_FLOOR: f32 = 1024.0;
+
 pub struct IoMonitor {
     disks: Vec<DiskStats>,
     pub total_read_rate: f64,
     pub total_write_rate: f64,
     pub peak_read_rate: f64,
     pub peak_write_rate: f64,
+    pub history: HistoryStore,
+    pub view: MonitorView,
+    /// Rows flashing for firing alerts
+    pub flash: Flash,
 }
 
 impl IoMonitor {
@@ -38,6 +51,9 @@ impl IoMonitor {
             total_write_rate: 0.0,
             peak_read_rate: 100.0 * 1024.0 * 1024.0, // Start with 100MB/s as minimum scale
             peak_write_rate: 100.0 * 1024.0 * 1024.0,
+            history: HistoryStore::default(),
+            view: MonitorView::default(),
+            flash: Flash::default(),
         }
     }
 
@@ -116,6 +132,16 @@ impl IoMonitor {
             new_disks.push(disk);
         }
 
+        self.apply(new_disks, interval);
+        Ok(())
+    }
+
+    /// Show recorded disk rates in place of live readings
+    pub fn restore(&mut self, disks: &[DiskStats], interval: f32) {
+        self.apply(disks.to_vec(), interval.max(f32::EPSILON));
+    }
+
+    fn apply(&mut self, new_disks: Vec<DiskStats>, interval: f32) {
         // Calculate totals
         self.total_read_rate = 0.0;
         self.total_write_rate = 0.0;
@@ -140,10 +166,10 @@ impl IoMonitor {
             SCALE_FLOOR,
         );
 
-        Ok(())
+        self.history.record("read", self.total_read_rate as f32);
+        self.history.record("write", self.total_write_rate as f32);
     }
 
-    #[allow(dead_code)]
     pub fn render(&self, term: &mut Terminal, bx: &Rect, colors: &ColorState) {
         let x = bx.inner_x();
         let y = bx.inner_y();
@@ -165,6 +191,34 @@ impl IoMonitor {
         h: usize,
         colors: &ColorState,
     ) {
+        if self.view == MonitorView::History {
+            let row = |label, name, rate| {
+                let history = self.history.get(name);
+                HistoryRow {
+                    label,
+                    value: format_rate(rate),
+                    history,
+                    max: history.max().max(HISTORY_SCALE_FLOOR),
+                }
+            };
+            let rows = [
+                row("Read", "read", self.total_read_rate),
+                row("Write", "write", self.total_write_rate),
+            ];
+            draw_history_view(
+                term,
+                x,
+                y,
+                w,
+                h,
+                "Disk I/O",
+                &rows,
+                GraphStyle::Line,
+                colors,
+            );
+            return;
+        }
+
         if h < 4 || w < 30 {
             return;
         }
@@ -205,6 +259,7 @@ impl IoMonitor {
         for disk in self.disks.iter().take(num_disks) {
             // Disk name as label
             term.set_str(x, cy, &disk.name, Some(header_color_scheme(colors)), false);
+            self.flash.mark(term, x, cy, w, &[&disk.name]);
             cy += 1;
 
             // Read for this disk
@@ -288,11 +343,28 @@ impl IoMonitor {
     }
 }
 
+impl Collector for IoMonitor {
+    fn sample(&mut self, elapsed: f32) -> io::Result<()> {
+        self.update(elapsed)
+    }
+
+    fn snapshot(&self) -> Snapshot<'_> {
+        Snapshot::Io { disks: &self.disks }
+    }
+
+    fn needs_baseline(&self) -> bool {
+        true
+    }
+}
+
 pub fn run(config: MonitorConfig) -> io::Result<()> {
     let mut term = Terminal::new(true)?;
     let mut state = MonitorState::new(config.time_step, 1.0);
     let mut monitor = IoMonitor::new();
-    const HELP: HelpSpec = HelpSpec::monitor("DISK I/O MONITOR", &[]);
+    let mut sample_log = config.sample_log;
+    let mut alerts = config.alerts;
+    monitor.history.set_window(config.history);
+    const HELP: HelpSpec = HelpSpec::monitor("DISK I/O MONITOR", &[VIEW_TOGGLE_HELP]);
 
     state.record_sample(monitor.update(1.0));
     let mut last_sample = std::time::Instant::now();
@@ -301,13 +373,15 @@ pub fn run(config: MonitorConfig) -> io::Result<()> {
     loop {
         let mut action = MonitorAction::None;
         if let Ok(Some((code, mods))) = term.check_key() {
-            action = state.handle_key(code, mods);
-            if action == MonitorAction::Quit {
-                break;
+            if !state.handle_view_key(code, &mut monitor.view) {
+                action = state.handle_key(code, mods);
+                if action == MonitorAction::Quit {
+                    break;
+                }
             }
         }
 
-        if let Ok((new_w, new_h)) = size() {
+        if let Ok((new_w, new_h)) = term.query_size() {
             let (cur_w, cur_h) = term.size();
             if new_w != cur_w || new_h != cur_h {
                 term.resize(new_w, new_h);
@@ -318,6 +392,8 @@ pub fn run(config: MonitorConfig) -> io::Result<()> {
         if state.should_sample(action) {
             let elapsed = last_sample.elapsed().as_secs_f32().max(f32::EPSILON);
             if state.record_sample(monitor.update(elapsed)) {
+                sample_log.save(&monitor, &mut state);
+                monitor.flash = alerts.check(&monitor, &mut state, &term);
                 last_sample = std::time::Instant::now();
             }
         }
@@ -1,17 +1,26 @@
-//! Docker container monitor - shows container resource usage
+//! Docker container monitor - shows container state and resource usage
+//! from the Engine API
 
 use crate::colors::ColorState;
 use crate::help::{render_help_overlay, HelpEntry, HelpSpec};
-use crate::monitor::layout::{cpu_gradient_color_scheme, muted_color_scheme, text_color_scheme};
-use crate::monitor::{command_output_with_timeout, truncate_message, MonitorAction, MonitorState};
-use crate::terminal::Terminal;
+use crate::monitor::alert::{Alerts, Flash};
+use crate::monitor::dialog::{Dialog, Outcome};
+use crate::monitor::docker_action::ContainerAction;
+use crate::monitor::docker_api::{ContainerStats, DockerClient, Engine, StatsStream};
+use crate::monitor::docker_logs::LogPane;
+use crate::monitor::layout::{
+    cpu_gradient_color_scheme, format_bytes, muted_color_scheme, text_color_scheme, Rect,
+};
+use crate::monitor::output::{self, Collector, OutputConfig, Snapshot};
+use crate::monitor::{truncate_message, MonitorAction, MonitorState};
+use crate::terminal::{Attrs, Terminal};
+use crate::unicode;
 use crossterm::style::Color;
-use crossterm::terminal::size;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::io;
-use std::process::Command;
-use std::time::Duration;
-
-const COLLECTOR_TIMEOUT: Duration = Duration::from_secs(3);
+use std::path::PathBuf;
+use std::thread::JoinHandle;
 
 #[derive(Clone, Copy, PartialEq)]
 enum SortBy {
@@ -38,33 +47,120 @@ impl SortBy {
     }
 }
 
-#[derive(Clone)]
-struct ContainerInfo {
-    name: String,
-    cpu_pct: f32,
-    mem_usage: String,
-    mem_pct: f32,
-    net_io: String,
+#[derive(Clone, Serialize)]
+pub struct ContainerInfo {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    /// The Compose project the container belongs to, from its labels
+    pub project: Option<String>,
+    /// created, running, paused, restarting, removing, exited or dead
+    pub state: String,
+    /// The daemon's summary, e.g. "Up 2 hours (healthy)"
+    pub status: String,
+    /// starting, healthy or unhealthy for containers with a health check
+    pub health: Option<String>,
+    pub restart_count: u64,
+    /// Unix time of the last start
+    pub started_at: Option<i64>,
+    /// Whether output comes from a TTY, which the log stream does not multiplex
+    #[serde(skip)]
+    pub tty: bool,
+    #[serde(flatten)]
+    pub stats: ContainerStats,
+}
+
+impl ContainerInfo {
+    /// Whether the daemon reports resource usage for the container
+    pub fn has_stats(&self) -> bool {
+        matches!(self.state.as_str(), "running" | "paused")
+    }
+
+    /// Health when the container has a check, otherwise its state
+    fn state_label(&self) -> &str {
+        match &self.health {
+            Some(health) if self.state == "running" => health,
+            _ => &self.state,
+        }
+    }
+
+    fn uptime(&self) -> Option<String> {
+        let started = self.started_at.filter(|_| self.has_stats())?;
+        Some(format_uptime(chrono::Utc::now().timestamp() - started))
+    }
+}
+
+/// Which row is selected; rows are found again by name after every sample
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum RowKey {
+    Project(String),
+    Container(String),
+}
+
+/// A line of the container list
+enum Row<'a> {
+    /// A Compose project's header, with totals over its running containers
+    Project {
+        name: &'a str,
+        members: Vec<&'a ContainerInfo>,
+        totals: ContainerStats,
+        collapsed: bool,
+    },
+    Container {
+        container: &'a ContainerInfo,
+        /// Listed under its project's header
+        grouped: bool,
+    },
+}
+
+impl Row<'_> {
+    fn key(&self) -> RowKey {
+        match self {
+            Row::Project { name, .. } => RowKey::Project(name.to_string()),
+            Row::Container { container, .. } => RowKey::Container(container.name.clone()),
+        }
+    }
 }
 
 pub struct DockerMonitor {
+    client: DockerClient,
+    /// Open stats streams by container ID
+    streams: HashMap<String, StatsStream>,
     containers: Vec<ContainerInfo>,
     docker_available: bool,
     error_msg: Option<String>,
     sort_by: SortBy,
-    selected_name: Option<String>,
+    /// Group containers under their Compose project
+    grouped: bool,
+    /// Projects whose containers are hidden under the header
+    collapsed: HashSet<String>,
+    selected: Option<RowKey>,
     detail_open: bool,
+    /// The selected container's logs, while the pane is open
+    logs: Option<LogPane>,
+    /// Rows flashing for firing alerts
+    pub flash: Flash,
 }
 
 impl DockerMonitor {
     pub fn new() -> Self {
+        Self::with_client(DockerClient::discover(None))
+    }
+
+    pub fn with_client(client: DockerClient) -> Self {
         Self {
+            client,
+            streams: HashMap::new(),
             containers: Vec::new(),
             docker_available: true,
             error_msg: None,
             sort_by: SortBy::Cpu,
-            selected_name: None,
+            grouped: true,
+            collapsed: HashSet::new(),
+            selected: None,
             detail_open: false,
+            logs: None,
+            flash: Flash::default(),
         }
     }
 
@@ -73,16 +169,129 @@ impl DockerMonitor {
         self.sort_containers();
     }
 
+    pub fn toggle_grouping(&mut self) {
+        self.grouped = !self.grouped;
+        self.reconcile_selection();
+    }
+
+    /// Collapse or expand the selected project, or the project of the
+    /// selected container. Returns whether anything changed.
+    pub fn set_collapsed(&mut self, collapse: bool) -> bool {
+        if !self.grouped {
+            return false;
+        }
+        let project = match &self.selected {
+            Some(RowKey::Project(name)) => name.clone(),
+            _ => match self.selected_container().and_then(|c| c.project.clone()) {
+                Some(project) => project,
+                None => return false,
+            },
+        };
+        let changed = if collapse {
+            self.selected = Some(RowKey::Project(project.clone()));
+            self.detail_open = false;
+            self.collapsed.insert(project)
+        } else {
+            self.collapsed.remove(&project)
+        };
+        self.sync_logs();
+        changed
+    }
+
+    /// Collapse or expand the selected project header; false when a
+    /// container is selected instead
+    pub fn toggle_collapsed(&mut self) -> bool {
+        let Some(RowKey::Project(name)) = &self.selected else {
+            return false;
+        };
+        let collapse = !self.collapsed.contains(name);
+        self.set_collapsed(collapse);
+        true
+    }
+
+    /// Projects first, each header followed by its containers unless
+    /// collapsed, then containers outside any project. Projects sort by
+    /// their totals; containers keep the list's order.
+    fn rows(&self) -> Vec<Row<'_>> {
+        if !self.grouped {
+            return self
+                .containers
+                .iter()
+                .map(|container| Row::Container {
+                    container,
+                    grouped: false,
+                })
+                .collect();
+        }
+
+        let mut projects: Vec<(&str, Vec<&ContainerInfo>)> = Vec::new();
+        let mut loose = Vec::new();
+        for container in &self.containers {
+            let Some(project) = container.project.as_deref() else {
+                loose.push(container);
+                continue;
+            };
+            match projects.iter_mut().find(|(name, _)| *name == project) {
+                Some((_, members)) => members.push(container),
+                None => projects.push((project, vec![container])),
+            }
+        }
+        let mut projects: Vec<(&str, Vec<&ContainerInfo>, ContainerStats)> = projects
+            .into_iter()
+            .map(|(name, members)| {
+                let totals = totals(&members);
+                (name, members, totals)
+            })
+            .collect();
+        match self.sort_by {
+            SortBy::Cpu => projects.sort_by(|a, b| b.2.cpu_pct.total_cmp(&a.2.cpu_pct)),
+            SortBy::Mem => projects.sort_by(|a, b| b.2.mem_pct.total_cmp(&a.2.mem_pct)),
+            SortBy::Name => projects.sort_by(|a, b| a.0.cmp(b.0)),
+        }
+
+        let mut rows = Vec::new();
+        for (name, members, totals) in projects {
+            let collapsed = self.collapsed.contains(name);
+            let shown = if collapsed {
+                Vec::new()
+            } else {
+                members.clone()
+            };
+            rows.push(Row::Project {
+                name,
+                members,
+                totals,
+                collapsed,
+            });
+            rows.extend(shown.into_iter().map(|container| Row::Container {
+                container,
+                grouped: true,
+            }));
+        }
+        rows.extend(loose.into_iter().map(|container| Row::Container {
+            container,
+            grouped: false,
+        }));
+        rows
+    }
+
+    fn selected_row(&self, rows: &[Row]) -> Option<usize> {
+        let selected = self.selected.as_ref()?;
+        rows.iter().position(|row| &row.key() == selected)
+    }
+
     fn sort_containers(&mut self) {
         match self.sort_by {
             SortBy::Cpu => self.containers.sort_by(|a, b| {
-                b.cpu_pct
-                    .partial_cmp(&a.cpu_pct)
+                b.stats
+                    .cpu_pct
+                    .partial_cmp(&a.stats.cpu_pct)
                     .unwrap_or(std::cmp::Ordering::Equal)
             }),
             SortBy::Mem => self.containers.sort_by(|a, b| {
-                b.mem_pct
-                    .partial_cmp(&a.mem_pct)
+                b.stats
+                    .mem_pct
+                    .partial_cmp(&a.stats.mem_pct)
                     .unwrap_or(std::cmp::Ordering::Equal)
             }),
             SortBy::Name => self.containers.sort_by(|a, b| a.name.cmp(&b.name)),
@@ -90,72 +299,75 @@ impl DockerMonitor {
     }
 
     pub fn update(&mut self) -> io::Result<()> {
-        // Run docker stats with custom format
-        let output = command_output_with_timeout(
-            Command::new("docker").args([
-                "stats",
-                "--no-stream",
-                "--format",
-                "{{.Name}}\t{{.CPUPerc}}\t{{.MemUsage}}\t{{.MemPerc}}\t{{.NetIO}}",
-            ]),
-            COLLECTOR_TIMEOUT,
-        );
-
-        match output {
-            Ok(result) => {
-                if !result.status.success() {
-                    let stderr = String::from_utf8_lossy(&result.stderr);
-                    let message = if stderr.contains("Cannot connect")
-                        || stderr.contains("permission denied")
-                    {
-                        "Docker daemon not accessible".to_string()
-                    } else {
-                        let message = stderr
-                            .lines()
-                            .map(str::trim)
-                            .find(|line| !line.is_empty())
-                            .unwrap_or("");
-                        if message.is_empty() {
-                            "docker stats failed".to_string()
-                        } else {
-                            truncate_message(message, 256)
-                        }
-                    };
-                    if message == "Docker daemon not accessible" {
-                        self.docker_available = false;
-                    }
-                    self.error_msg = Some(message.clone());
-                    self.containers.clear();
-                    self.reconcile_selection();
-                    return Err(io::Error::other(message));
-                }
-
-                self.docker_available = true;
-                self.error_msg = None;
-
-                let stdout = String::from_utf8_lossy(&result.stdout);
-                self.containers = stdout
-                    .lines()
-                    .filter(|line| !line.is_empty())
-                    .filter_map(parse_container_line)
-                    .collect();
-                self.sort_containers();
-                self.reconcile_selection();
-            }
+        let mut containers = match self.client.containers() {
+            Ok(containers) => containers,
             Err(e) => {
-                let message = if e.kind() == io::ErrorKind::NotFound {
+                let engine = self.client.engine();
+                let socket = self.client.socket().display();
+                let message = if !self.client.socket().exists() {
+                    self.docker_available = false;
+                    let hint = match engine {
+                        Engine::Docker => "",
+                        Engine::Podman => " (systemctl --user start podman.socket)",
+                    };
+                    format!("{} not running: no socket at {socket}{hint}", engine.name())
+                } else if e.kind() == io::ErrorKind::PermissionDenied {
+                    self.docker_available = false;
+                    format!(
+                        "{} not accessible: permission denied on {socket}",
+                        engine.name()
+                    )
+                } else if e.kind() == io::ErrorKind::ConnectionRefused {
                     self.docker_available = false;
-                    "Docker not installed".to_string()
+                    format!(
+                        "{} not accessible: nothing listening on {socket}",
+                        engine.name()
+                    )
                 } else {
-                    format!("Error: {e}")
+                    truncate_message(&format!("Error: {e}"), 256)
                 };
                 self.error_msg = Some(message.clone());
                 self.containers.clear();
+                self.streams.clear();
                 self.reconcile_selection();
                 return Err(io::Error::new(e.kind(), message));
             }
+        };
+
+        for container in containers.iter_mut().filter(|c| c.has_stats()) {
+            let stream = self.streams.get(&container.id);
+            let latest = stream.and_then(StatsStream::latest);
+            if stream.map_or(true, StatsStream::is_finished) {
+                match self.client.stats_stream(&container.id) {
+                    Ok(stream) => {
+                        self.streams.insert(container.id.clone(), stream);
+                    }
+                    Err(_) => {
+                        self.streams.remove(&container.id);
+                    }
+                }
+            }
+            // A reopened stream has nothing yet; keep the last reading
+            container.stats = latest
+                .or_else(|| {
+                    self.containers
+                        .iter()
+                        .find(|previous| previous.id == container.id)
+                        .map(|previous| previous.stats.clone())
+                })
+                .unwrap_or_default();
         }
+        self.streams.retain(|id, _| {
+            containers
+                .iter()
+                .any(|container| &container.id == id && container.has_stats())
+        });
 
+        self.docker_available = true;
+        self.error_msg = None;
+        self.containers = containers;
+        self.sort_containers();
+        self.reconcile_selection();
         Ok(())
     }
 
@@ -177,68 +389,128 @@ impl DockerMonitor {
         self.detail_open = false;
     }
 
-    pub fn selection_label(&self) -> Option<&str> {
-        self.selected_container()
-            .map(|container| container.name.as_str())
+    pub fn toggle_logs(&mut self) {
+        self.logs = match (&self.logs, self.selected_container()) {
+            (None, Some(container)) => Some(LogPane::open(&self.client, container)),
+            _ => None,
+        };
+    }
+
+    pub fn close_logs(&mut self) {
+        self.logs = None;
+    }
+
+    pub fn logs_mut(&mut self) -> Option<&mut LogPane> {
+        self.logs.as_mut()
+    }
+
+    /// Keep the log pane on the selected container, reopening it when the
+    /// selection moves or the container is recreated under the same name
+    fn sync_logs(&mut self) {
+        let Some(logs) = &self.logs else {
+            return;
+        };
+        self.logs = match self.selected_container() {
+            Some(container) if container.id == logs.id() => return,
+            Some(container) => Some(LogPane::open(&self.client, container)),
+            None => None,
+        };
+    }
+
+    /// The confirmation for an action key on the selected container
+    pub fn action_dialog(&self, key: char) -> Option<Dialog<ContainerAction>> {
+        ContainerAction::dialog(self.selected_container()?, key)
+    }
+
+    pub fn client(&self) -> &DockerClient {
+        &self.client
+    }
+
+    pub fn selection_label(&self) -> Option<String> {
+        match self.selected.as_ref()? {
+            RowKey::Project(name) => {
+                let count = self
+                    .containers
+                    .iter()
+                    .filter(|container| container.project.as_ref() == Some(name))
+                    .count();
+                Some(format!("{name} ({count} containers)"))
+            }
+            RowKey::Container(_) => self.selected_container().map(|c| c.name.clone()),
+        }
     }
 
     pub fn detail_text(&self) -> Option<String> {
         let container = self.selected_container()?;
-        Some(format!(
-            "CONTAINER DETAILS\n───────────────────────\nName      {}\nCPU       {:.1}%\nMemory    {} ({:.1}%)\nNetwork   {}",
+        let stats = &container.stats;
+        let mut text = format!(
+            "CONTAINER DETAILS\n───────────────────────\nName      {}\nImage     {}\nID        {}\nStatus    {}\nRestarts  {}",
             container.name,
-            container.cpu_pct,
-            container.mem_usage,
-            container.mem_pct,
-            container.net_io
-        ))
+            container.image,
+            short_id(&container.id),
+            container.status,
+            container.restart_count
+        );
+        if container.has_stats() {
+            text.push_str(&format!(
+                "\nCPU       {:.1}%\nMemory    {} ({:.1}%)\nNetwork   {}\nBlock I/O {}\nPIDs      {}",
+                stats.cpu_pct,
+                pair(stats.mem_used, stats.mem_limit),
+                stats.mem_pct,
+                pair(stats.net_rx, stats.net_tx),
+                pair(stats.block_read, stats.block_write),
+                stats.pids
+            ));
+        }
+        Some(text)
     }
 
     fn move_selection(&mut self, direction: i32) {
-        if self.containers.is_empty() {
-            self.selected_name = None;
+        let rows = self.rows();
+        if rows.is_empty() {
+            self.selected = None;
             self.detail_open = false;
+            self.logs = None;
             return;
         }
 
-        let current = self
-            .selected_name
-            .as_deref()
-            .and_then(|name| {
-                self.containers
-                    .iter()
-                    .position(|container| container.name == name)
-            })
-            .unwrap_or(0);
+        let current = self.selected_row(&rows).unwrap_or(0);
         let next = if direction < 0 {
-            current
-                .checked_sub(1)
-                .unwrap_or(self.containers.len().saturating_sub(1))
+            current.checked_sub(1).unwrap_or(rows.len() - 1)
         } else {
-            (current + 1) % self.containers.len()
+            (current + 1) % rows.len()
         };
-        self.selected_name = Some(self.containers[next].name.clone());
+        self.selected = Some(rows[next].key());
+        self.sync_logs();
     }
 
     fn reconcile_selection(&mut self) {
-        if self.containers.is_empty() {
-            self.selected_name = None;
+        let rows = self.rows();
+        if rows.is_empty() {
+            self.selected = None;
             self.detail_open = false;
-        } else if !self.selected_name.as_deref().is_some_and(|name| {
-            self.containers
-                .iter()
-                .any(|container| container.name == name)
-        }) {
-            self.selected_name = Some(self.containers[0].name.clone());
+        } else if self.selected_row(&rows).is_none() {
+            // A container hidden in a collapsed project selects its header
+            let project = self
+                .selected_container()
+                .filter(|_| self.grouped)
+                .and_then(|container| container.project.clone());
+            self.selected = Some(match project {
+                Some(project) => RowKey::Project(project),
+                None => rows[0].key(),
+            });
             self.detail_open = false;
         }
+        self.sync_logs();
     }
 
     fn selected_container(&self) -> Option<&ContainerInfo> {
-        let name = self.selected_name.as_deref()?;
+        let Some(RowKey::Container(name)) = &self.selected else {
+            return None;
+        };
         self.containers
             .iter()
-            .find(|container| container.name == name)
+            .find(|container| &container.name == name)
     }
 
     pub fn render(&self, term: &mut Terminal, w: usize, h: usize, colors: &ColorState) {
@@ -246,14 +518,30 @@ impl DockerMonitor {
             return;
         }
 
+        // The log pane takes the lower half; the list fills what is left
+        let pane_height = match &self.logs {
+            Some(_) => (h / 2).max(8).min(h.saturating_sub(4)),
+            None => 0,
+        };
+        if let Some(logs) = &self.logs {
+            let area = Rect {
+                x: 0,
+                y: (h - pane_height) as i32,
+                width: w as u16,
+                height: pane_height as u16,
+            };
+            logs.render(term, &area, colors);
+        }
+        let h = h - pane_height;
+
         let header_y = 0;
         let mut y = 1;
 
         // Title with sort indicator
-        let title = "Docker Containers";
+        let title = format!("{} Containers", self.client.engine().name());
         let sort_str = format!("[m]Sort:{}", self.sort_by.label());
         let count_str = format!("[{}]", self.containers.len());
-        term.set_str(0, header_y, title, Some(text_color_scheme(colors)), true);
+        term.set_str(0, header_y, &title, Some(text_color_scheme(colors)), true);
         let sort_x = w.saturating_sub(sort_str.len() + count_str.len() + 2) as i32;
         term.set_str(
             sort_x,
@@ -272,8 +560,11 @@ impl DockerMonitor {
 
         // Error state
         if !self.docker_available || self.error_msg.is_some() {
-            let msg = self.error_msg.as_deref().unwrap_or("Docker unavailable");
-            term.set_str(0, y, msg, Some(Color::Red), false);
+            let msg = match &self.error_msg {
+                Some(msg) => msg.clone(),
+                None => format!("{} unavailable", self.client.engine().name()),
+            };
+            term.set_str(0, y, &msg, Some(Color::Red), false);
             return;
         }
 
@@ -282,7 +573,7 @@ impl DockerMonitor {
             term.set_str(
                 0,
                 y,
-                "No running containers",
+                "No containers",
                 Some(muted_color_scheme(colors)),
                 false,
             );
@@ -291,120 +582,279 @@ impl DockerMonitor {
 
         // Column header
         let header = format!(
-            "{:<20} {:>8} {:>16} {:>8} {:>16}",
-            "NAME", "CPU%", "MEM USAGE", "MEM%", "NET I/O"
+            " {:<20} {:<10} {:>6} {:>19} {:>6} {:>19} {:>19} {:>5} {:>7} {:>3}",
+            "NAME",
+            "STATE",
+            "CPU%",
+            "MEM USAGE",
+            "MEM%",
+            "NET I/O",
+            "BLOCK I/O",
+            "PIDS",
+            "UP",
+            "RST"
         );
-        let header_truncated: String = header.chars().take(w).collect();
         term.set_str(
             0,
             y,
-            &header_truncated,
+            unicode::truncate_to_width(&header, w),
             Some(text_color_scheme(colors)),
             false,
         );
         y += 1;
 
+        let rows = self.rows();
         let available_rows = h.saturating_sub(y as usize);
-        let show_count = self.containers.len().min(available_rows);
-        let selected_index = self
-            .selected_name
-            .as_deref()
-            .and_then(|name| {
-                self.containers
-                    .iter()
-                    .position(|container| container.name == name)
-            })
-            .unwrap_or(0);
-        let start = selected_index.saturating_add(1).saturating_sub(show_count);
+        let show_count = rows.len().min(available_rows);
+        let selected_index = self.selected_row(&rows);
+        let start = selected_index
+            .unwrap_or(0)
+            .saturating_add(1)
+            .saturating_sub(show_count);
 
-        // Container rows
-        for container in self.containers.iter().skip(start).take(show_count) {
+        // Project headers and container rows
+        for (index, row) in rows.iter().enumerate().skip(start).take(show_count) {
             if y >= h as i32 {
                 break;
             }
 
-            let row = format!(
-                "{}{:<20} {:>8} {:>16} {:>8} {:>16}",
-                if self.selected_name.as_deref() == Some(container.name.as_str()) {
-                    '>'
-                } else {
-                    ' '
-                },
-                truncate_str(&container.name, 20),
-                format!("{:.1}%", container.cpu_pct),
-                container.mem_usage,
-                format!("{:.1}%", container.mem_pct),
-                container.net_io
-            );
+            let (text, row_color, bold, names) = match row {
+                Row::Project {
+                    name,
+                    members,
+                    totals,
+                    collapsed,
+                } => {
+                    let up = members.iter().filter(|c| c.has_stats()).count();
+                    let text = format!(
+                        "{} {} {:>6} {:>19} {:>6} {:>19} {:>19} {:>5} {:>7} {:>3}",
+                        fit_column(
+                            &format!("{} {name}", if *collapsed { '▸' } else { '▾' }),
+                            20
+                        ),
+                        fit_column(&format!("{up}/{} up", members.len()), 10),
+                        format!("{:.1}%", totals.cpu_pct),
+                        format_bytes(totals.mem_used),
+                        format!("{:.1}%", totals.mem_pct),
+                        pair(totals.net_rx, totals.net_tx),
+                        pair(totals.block_read, totals.block_write),
+                        totals.pids,
+                        "",
+                        members.iter().map(|c| c.restart_count).sum::<u64>()
+                    );
+                    let names: Vec<&str> = members.iter().map(|c| c.name.as_str()).collect();
+                    (text, text_color_scheme(colors), true, names)
+                }
+                Row::Container { container, grouped } => {
+                    let stats = &container.stats;
+                    // Stopped containers have no usage to show
+                    let usage = |text: String| {
+                        if container.has_stats() {
+                            text
+                        } else {
+                            "-".to_string()
+                        }
+                    };
+                    let name = if *grouped {
+                        format!("  {}", container.name)
+                    } else {
+                        container.name.clone()
+                    };
+                    let text = format!(
+                        "{} {} {:>6} {:>19} {:>6} {:>19} {:>19} {:>5} {:>7} {:>3}",
+                        fit_column(&name, 20),
+                        fit_column(container.state_label(), 10),
+                        usage(format!("{:.1}%", stats.cpu_pct)),
+                        usage(pair(stats.mem_used, stats.mem_limit)),
+                        usage(format!("{:.1}%", stats.mem_pct)),
+                        usage(pair(stats.net_rx, stats.net_tx)),
+                        usage(pair(stats.block_read, stats.block_write)),
+                        usage(stats.pids.to_string()),
+                        container.uptime().unwrap_or_else(|| "-".to_string()),
+                        container.restart_count
+                    );
+                    // Color based on CPU usage
+                    let color = if container.health.as_deref() == Some("unhealthy") {
+                        Color::Red
+                    } else if container.has_stats() {
+                        cpu_gradient_color_scheme(stats.cpu_pct.min(100.0), colors)
+                    } else {
+                        muted_color_scheme(colors)
+                    };
+                    (text, color, false, vec![container.name.as_str()])
+                }
+            };
 
-            let row_truncated: String = row.chars().take(w).collect();
+            let selected = selected_index == Some(index);
+            let row = format!("{}{text}", if selected { '>' } else { ' ' });
+            let row = unicode::truncate_to_width(&row, w);
 
-            // Color based on CPU usage
-            let selected = self.selected_name.as_deref() == Some(container.name.as_str());
-            let row_color = if selected {
-                text_color_scheme(colors)
+            // The selection is a reverse-video bar
+            if selected {
+                let bar = format!("{row}{}", " ".repeat(w - unicode::str_width(row)));
+                term.set_str_with_attrs(0, y, &bar, Some(row_color), None, Attrs::REVERSE);
             } else {
-                cpu_gradient_color_scheme(container.cpu_pct.min(100.0), colors)
-            };
-            term.set_str(0, y, &row_truncated, Some(row_color), selected);
+                term.set_str(0, y, row, Some(row_color), bold);
+            }
+            self.flash.mark(term, 0, y, w, &names);
 
             y += 1;
         }
     }
 }
 
-fn parse_container_line(line: &str) -> Option<ContainerInfo> {
-    let parts: Vec<&str> = line.split('\t').collect();
-    if parts.len() < 5 {
-        return None;
+/// Totals over a project's running containers; the memory percentage is of
+/// their summed limits
+fn totals(members: &[&ContainerInfo]) -> ContainerStats {
+    let mut totals = ContainerStats::default();
+    for stats in members.iter().filter(|c| c.has_stats()).map(|c| &c.stats) {
+        totals.cpu_pct += stats.cpu_pct;
+        totals.mem_used += stats.mem_used;
+        totals.mem_limit += stats.mem_limit;
+        totals.net_rx += stats.net_rx;
+        totals.net_tx += stats.net_tx;
+        totals.block_read += stats.block_read;
+        totals.block_write += stats.block_write;
+        totals.pids += stats.pids;
+    }
+    if totals.mem_limit > 0 {
+        totals.mem_pct = (totals.mem_used as f64 / totals.mem_limit as f64 * 100.0) as f32;
     }
+    totals
+}
 
-    let cpu_str = parts[1].trim_end_matches('%');
-    let mem_pct_str = parts[3].trim_end_matches('%');
+/// Two byte counts as `used / limit` or `in / out`
+fn pair(first: u64, second: u64) -> String {
+    format!("{} / {}", format_bytes(first), format_bytes(second))
+}
 
-    Some(ContainerInfo {
-        name: parts[0].to_string(),
-        cpu_pct: cpu_str.parse().unwrap_or(0.0),
-        mem_usage: parts[2].to_string(),
-        mem_pct: mem_pct_str.parse().unwrap_or(0.0),
-        net_io: parts[4].to_string(),
-    })
+fn short_id(id: &str) -> &str {
+    &id[..id.len().min(12)]
 }
 
-fn truncate_str(s: &str, max_len: usize) -> String {
-    // Count/slice by chars, not bytes, to avoid panicking on a multibyte boundary.
-    if s.chars().count() <= max_len {
-        s.to_string()
+/// The largest two units of a duration, e.g. `3d4h`, `2h05m`, `12m`
+fn format_uptime(secs: i64) -> String {
+    let secs = secs.max(0);
+    let (days, hours, mins) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{days}d{hours}h")
+    } else if hours > 0 {
+        format!("{hours}h{mins:02}m")
+    } else if mins > 0 {
+        format!("{mins}m")
     } else {
-        let truncated: String = s.chars().take(max_len.saturating_sub(3)).collect();
-        format!("{}...", truncated)
+        format!("{secs}s")
     }
 }
 
+/// `s` padded or cut to exactly `width` display columns
+fn fit_column(s: &str, width: usize) -> String {
+    let mut fitted = if unicode::str_width(s) <= width {
+        s.to_string()
+    } else {
+        format!(
+            "{}...",
+            unicode::truncate_to_width(s, width.saturating_sub(3))
+        )
+    };
+    let used = unicode::str_width(&fitted);
+    fitted.push_str(&" ".repeat(width.saturating_sub(used)));
+    fitted
+}
+
 pub struct DockerConfig {
     pub time_step: f32,
+    /// Engine to connect to; the first one found when `None`
+    pub engine: Option<Engine>,
+    /// Socket to connect to instead of the engine's usual ones
+    pub socket: Option<PathBuf>,
+    pub output: Option<OutputConfig>,
+    /// Threshold rules checked on every sample
+    pub alerts: Alerts,
+}
+
+impl Collector for DockerMonitor {
+    fn sample(&mut self, _elapsed: f32) -> io::Result<()> {
+        self.update()
+    }
+
+    fn snapshot(&self) -> Snapshot<'_> {
+        Snapshot::Docker {
+            containers: &self.containers,
+        }
+    }
+
+    /// Stats streams deliver their first reading after about a second
+    fn needs_baseline(&self) -> bool {
+        true
+    }
 }
 
 pub fn run(config: DockerConfig) -> io::Result<()> {
+    let client = match config.socket {
+        Some(socket) => DockerClient::new(config.engine.unwrap_or(Engine::Docker), socket),
+        None => DockerClient::discover(config.engine),
+    };
+    if let Some(output) = config.output {
+        return output::stream(
+            &mut DockerMonitor::with_client(client),
+            output,
+            config.time_step,
+        );
+    }
+
     let mut term = Terminal::new(true)?;
     let mut state = MonitorState::new(config.time_step, 2.0);
-    let mut monitor = DockerMonitor::new();
+    let mut monitor = DockerMonitor::with_client(client);
+    let mut alerts = config.alerts;
     const HELP: HelpSpec = HelpSpec::monitor(
         "DOCKER STATS",
         &[
             HelpEntry::new("↑/↓ or j/k", "Select container"),
-            HelpEntry::new("Enter", "Toggle details"),
+            HelpEntry::new("Enter", "Toggle details or project"),
             HelpEntry::new("m/s", "Cycle sort"),
+            HelpEntry::new("g", "Group by Compose project"),
+            HelpEntry::new("←/→", "Collapse/expand project"),
+            HelpEntry::new("S/x/R", "Start/stop/restart"),
+            HelpEntry::new("p", "Pause/unpause"),
+            HelpEntry::new("D", "Remove"),
+            HelpEntry::new("l", "Toggle log pane"),
+            HelpEntry::new("f or End", "Follow logs"),
+            HelpEntry::new("PgUp/PgDn", "Scroll logs"),
+            HelpEntry::new("/", "Search logs"),
         ],
     );
+    let mut dialog: Option<Dialog<ContainerAction>> = None;
+    // Actions still running; stopping waits for the container to exit
+    let mut pending: Vec<JoinHandle<Result<String, String>>> = Vec::new();
 
     loop {
         let mut action = MonitorAction::None;
         if let Ok(Some((code, mods))) = term.check_key() {
             use crossterm::event::KeyCode;
-            if monitor.detail_open && code == KeyCode::Esc {
+            if let Some(open) = dialog.take() {
+                match open.handle_key(code) {
+                    Outcome::Open(open) => dialog = Some(open),
+                    Outcome::Closed => {}
+                    Outcome::Confirmed(container_action) => {
+                        state.set_feedback(container_action.progress());
+                        pending.push(container_action.spawn(monitor.client()));
+                    }
+                }
+            } else if let Some(logs) = monitor.logs_mut().filter(|logs| logs.view.filter_editing) {
+                logs.view.edit_filter(code);
+            } else if let Some(logs) = monitor
+                .logs_mut()
+                .filter(|logs| !logs.view.filter.is_empty() && code == KeyCode::Esc)
+            {
+                logs.view.filter.clear();
+                state.set_feedback("Filter cleared");
+            } else if monitor.detail_open && code == KeyCode::Esc {
                 monitor.close_details();
                 state.set_feedback("Details closed");
+            } else if monitor.logs_mut().is_some() && code == KeyCode::Esc {
+                monitor.close_logs();
+                state.set_feedback("Logs closed");
             } else {
                 match code {
                     KeyCode::Up | KeyCode::Char('k') => {
@@ -420,12 +870,57 @@ pub fn run(config: DockerConfig) -> io::Result<()> {
                         }
                     }
                     KeyCode::Enter => {
-                        monitor.toggle_details();
+                        if !monitor.toggle_collapsed() {
+                            monitor.toggle_details();
+                        }
                     }
                     KeyCode::Char('m') | KeyCode::Char('s') => {
                         monitor.cycle_sort();
                         state.set_feedback(format!("Sort: {}", monitor.sort_by.label()));
                     }
+                    KeyCode::Char('g') => {
+                        monitor.toggle_grouping();
+                        state.set_feedback(if monitor.grouped {
+                            "View: by project"
+                        } else {
+                            "View: list"
+                        });
+                    }
+                    KeyCode::Left => {
+                        if monitor.set_collapsed(true) {
+                            state.set_feedback("Collapsed");
+                        }
+                    }
+                    KeyCode::Right => {
+                        if monitor.set_collapsed(false) {
+                            state.set_feedback("Expanded");
+                        }
+                    }
+                    KeyCode::Char(key @ ('S' | 'x' | 'R' | 'p' | 'D')) => {
+                        dialog = monitor.action_dialog(key);
+                    }
+                    KeyCode::Char('l') => monitor.toggle_logs(),
+                    KeyCode::Char('f') | KeyCode::End => {
+                        if let Some(logs) = monitor.logs_mut() {
+                            let follow = code == KeyCode::End || !logs.view.following();
+                            logs.set_following(follow);
+                            state.set_feedback(if follow {
+                                "Logs: following"
+                            } else {
+                                "Logs: paused"
+                            });
+                        }
+                    }
+                    KeyCode::PageUp | KeyCode::PageDown => {
+                        if let Some(logs) = monitor.logs_mut() {
+                            logs.scroll_by(if code == KeyCode::PageUp { -10 } else { 10 });
+                        }
+                    }
+                    KeyCode::Char('/') => {
+                        if let Some(logs) = monitor.logs_mut() {
+                            logs.view.filter_editing = true;
+                        }
+                    }
                     _ => action = state.handle_key(code, mods),
                 }
             }
@@ -434,7 +929,23 @@ pub fn run(config: DockerConfig) -> io::Result<()> {
             }
         }
 
-        if let Ok((new_w, new_h)) = size() {
+        let (finished, running) = pending.into_iter().partition(|done| done.is_finished());
+        pending = running;
+        for done in finished {
+            match done.join() {
+                Ok(Ok(message)) => {
+                    state.set_feedback(message);
+                    action = MonitorAction::SampleNow;
+                }
+                Ok(Err(message)) if dialog.is_none() => {
+                    dialog = Some(Dialog::message("ERROR", message));
+                }
+                Ok(Err(message)) => state.set_feedback(message),
+                Err(_) => state.set_feedback("Container action failed"),
+            }
+        }
+
+        if let Ok((new_w, new_h)) = term.query_size() {
             let (cur_w, cur_h) = term.size();
             if new_w != cur_w || new_h != cur_h {
                 term.resize(new_w, new_h);
@@ -442,8 +953,8 @@ pub fn run(config: DockerConfig) -> io::Result<()> {
             }
         }
 
-        if state.should_sample(action) {
-            state.record_sample(monitor.update());
+        if state.should_sample(action) && state.record_sample(monitor.update()) {
+            monitor.flash = alerts.check(&monitor, &mut state, &term);
         }
 
         term.clear();
@@ -457,6 +968,10 @@ pub fn run(config: DockerConfig) -> io::Result<()> {
             }
         }
 
+        if let Some(dialog) = &dialog {
+            dialog.render(&mut term, w, h);
+        }
+
         state.render_help(&mut term, w, h, &HELP);
 
         term.present()?;
@@ -468,18 +983,199 @@ pub fn run(config: DockerConfig) -> io::Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::{ContainerInfo, DockerMonitor};
+    use super::{ContainerInfo, DockerMonitor, RowKey};
+    use crate::monitor::dialog::Dialog;
+    use crate::monitor::docker_action::ContainerAction;
+    use crate::monitor::docker_api::{ContainerStats, DockerClient, Engine, StatsStream};
+    use serde_json::{json, Value};
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixListener;
+    use std::path::PathBuf;
+    use std::thread;
+    use std::time::{Duration, Instant};
 
     fn container(name: &str, cpu_pct: f32, mem_pct: f32) -> ContainerInfo {
         ContainerInfo {
+            id: format!("{name:0>64}"),
             name: name.to_string(),
-            cpu_pct,
-            mem_usage: "100MiB / 1GiB".to_string(),
-            mem_pct,
-            net_io: "1kB / 2kB".to_string(),
+            image: "nginx".to_string(),
+            project: None,
+            state: "running".to_string(),
+            status: "Up 5 minutes".to_string(),
+            health: None,
+            restart_count: 0,
+            started_at: None,
+            tty: false,
+            stats: ContainerStats {
+                cpu_pct,
+                mem_pct,
+                ..ContainerStats::default()
+            },
         }
     }
 
+    /// A stand-in Engine API on a Unix socket. Each request (`GET /path`) is
+    /// answered with its route's JSON, chunked for stats as the daemon streams
+    /// them; a null route answers with no content.
+    fn engine(routes: Vec<(String, Value)>) -> PathBuf {
+        let socket = std::env::temp_dir().join(format!(
+            "termart-docker-{}-{}.sock",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        let listener = UnixListener::bind(&socket).expect("bind stand-in socket");
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(&stream);
+                let mut request = String::new();
+                reader.read_line(&mut request).expect("request line");
+                let mut header = String::new();
+                while reader.read_line(&mut header).is_ok_and(|n| n > 2) {
+                    header.clear();
+                }
+
+                let mut parts = request.split_whitespace();
+                let method = parts.next().unwrap_or_default();
+                let path = parts.next().unwrap_or_default();
+                let route = format!("{method} {path}");
+                let response = match routes.iter().find(|(known, _)| *known == route) {
+                    Some((_, body)) if path.contains("/stats") => {
+                        let body = body.to_string();
+                        let (first, second) = body.split_at(body.len() / 2);
+                        format!(
+                            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n{first}\r\n{:x}\r\n{second}\r\n0\r\n\r\n",
+                            first.len(),
+                            second.len()
+                        )
+                    }
+                    Some((_, Value::Null)) => "HTTP/1.1 204 No Content\r\n\r\n".to_string(),
+                    Some((_, body)) => {
+                        let body = body.to_string();
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+                            body.len()
+                        )
+                    }
+                    None => {
+                        let body = r#"{"message":"No such container"}"#;
+                        format!(
+                            "HTTP/1.1 404 Not Found\r\nContent-Length: {}\r\n\r\n{body}",
+                            body.len()
+                        )
+                    }
+                };
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        socket
+    }
+
+    #[test]
+    fn engine_api_fills_in_containers_and_streams_stats() {
+        let (web, db) = ("a".repeat(64), "b".repeat(64));
+        let socket = engine(vec![
+            (
+                "GET /containers/json?all=1".to_string(),
+                json!([
+                    {"Id": web, "Names": ["/web"], "Image": "nginx:1.25",
+                     "State": "running", "Status": "Up 2 hours (healthy)"},
+                    {"Id": db, "Names": ["/db"], "Image": "postgres:16",
+                     "State": "exited", "Status": "Exited (0) 3 minutes ago"}
+                ]),
+            ),
+            (
+                format!("GET /containers/{web}/json"),
+                json!({"RestartCount": 2, "State": {
+                    "StartedAt": "2024-05-01T10:00:00.123456789Z",
+                    "Health": {"Status": "healthy"}
+                }}),
+            ),
+            (
+                format!("GET /containers/{db}/json"),
+                json!({"RestartCount": 0, "State": {"StartedAt": "0001-01-01T00:00:00Z"}}),
+            ),
+            (
+                format!("GET /containers/{web}/stats?stream=1"),
+                json!({
+                    "memory_stats": {"usage": 50, "limit": 100},
+                    "blkio_stats": {"io_service_bytes_recursive": [
+                        {"op": "read", "value": 4096}
+                    ]},
+                    "pids_stats": {"current": 3}
+                }),
+            ),
+            (format!("POST /containers/{web}/stop?t=10"), Value::Null),
+        ]);
+
+        let mut monitor = DockerMonitor::with_client(DockerClient::new(Engine::Docker, &socket));
+        monitor.update().expect("stand-in answers");
+        assert_eq!(monitor.containers.len(), 2);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while monitor
+            .streams
+            .get(&web)
+            .and_then(StatsStream::latest)
+            .is_none()
+        {
+            assert!(Instant::now() < deadline, "stats never arrived");
+            thread::sleep(Duration::from_millis(10));
+        }
+        monitor.update().expect("stand-in answers");
+
+        let find = |name: &str| {
+            monitor
+                .containers
+                .iter()
+                .find(|container| container.name == name)
+                .expect("container listed")
+        };
+        let running = find("web");
+        assert_eq!(running.image, "nginx:1.25");
+        assert_eq!(running.state_label(), "healthy");
+        assert_eq!(running.restart_count, 2);
+        assert_eq!(running.started_at, Some(1_714_557_600));
+        assert_eq!(running.stats.mem_pct, 50.0);
+        assert_eq!(running.stats.block_read, 4096);
+        assert_eq!(running.stats.pids, 3);
+
+        let stopped = find("db");
+        assert!(!stopped.has_stats());
+        assert_eq!(stopped.started_at, None);
+        assert_eq!(stopped.state_label(), "exited");
+        assert!(!monitor.streams.contains_key(&db));
+
+        let confirmed = |dialog: Option<Dialog<ContainerAction>>| match dialog {
+            Some(Dialog::Confirm { action, .. }) => action,
+            _ => panic!("the action asks for confirmation"),
+        };
+        let stop = confirmed(ContainerAction::dialog(running, 'x'));
+        assert_eq!(stop.progress(), "Stopping web…");
+        let result = stop.spawn(monitor.client()).join().expect("action thread");
+        assert_eq!(result, Ok("Stopped web".to_string()));
+        let remove = confirmed(ContainerAction::dialog(stopped, 'D'));
+        assert_eq!(remove.to_string(), "Remove db?");
+        let result = remove
+            .spawn(monitor.client())
+            .join()
+            .expect("action thread");
+        assert_eq!(result, Err("db no longer exists.".to_string()));
+        assert!(matches!(
+            ContainerAction::dialog(running, 'S'),
+            Some(Dialog::Message { text, .. }) if text == "web is already running."
+        ));
+
+        std::fs::remove_file(&socket).expect("test socket should be removed");
+        let mut missing = DockerMonitor::with_client(DockerClient::new(Engine::Docker, &socket));
+        assert!(missing.update().is_err());
+        assert_eq!(
+            missing.error_msg,
+            Some(format!(
+                "Docker not running: no socket at {}",
+                socket.display()
+            ))
+        );
+    }
+
     #[test]
     fn container_selection_wraps_and_survives_sorting() {
         let mut monitor = DockerMonitor::new();
@@ -490,15 +1186,15 @@ mod tests {
         ];
         monitor.reconcile_selection();
 
-        assert_eq!(monitor.selected_name.as_deref(), Some("alpha"));
+        assert_eq!(monitor.selection_label().as_deref(), Some("alpha"));
         monitor.select_previous();
-        assert_eq!(monitor.selected_name.as_deref(), Some("gamma"));
+        assert_eq!(monitor.selection_label().as_deref(), Some("gamma"));
         monitor.select_next();
         monitor.select_next();
-        assert_eq!(monitor.selected_name.as_deref(), Some("beta"));
+        assert_eq!(monitor.selection_label().as_deref(), Some("beta"));
 
         monitor.cycle_sort();
-        assert_eq!(monitor.selected_name.as_deref(), Some("beta"));
+        assert_eq!(monitor.selection_label().as_deref(), Some("beta"));
         assert_eq!(monitor.containers[0].name, "beta");
     }
 
@@ -513,8 +1209,84 @@ mod tests {
         monitor.containers.clear();
         monitor.reconcile_selection();
 
-        assert!(monitor.selected_name.is_none());
+        assert!(monitor.selection_label().is_none());
         assert!(!monitor.detail_open);
         assert!(monitor.detail_text().is_none());
     }
+
+    #[test]
+    fn compose_projects_group_and_collapse_with_totals() {
+        let mut monitor = DockerMonitor::new();
+        let in_project = |project: &str, mut container: ContainerInfo| {
+            container.project = Some(project.to_string());
+            container
+        };
+        monitor.containers = vec![
+            in_project("shop", container("alpha", 60.0, 1.0)),
+            in_project("blog", container("beta", 50.0, 3.0)),
+            in_project("shop", container("gamma", 40.0, 2.0)),
+            container("delta", 5.0, 1.0),
+        ];
+        monitor.reconcile_selection();
+        let keys = |monitor: &DockerMonitor| -> Vec<RowKey> {
+            monitor.rows().iter().map(|row| row.key()).collect()
+        };
+        let project = |name: &str| RowKey::Project(name.to_string());
+        let member = |name: &str| RowKey::Container(name.to_string());
+        assert_eq!(
+            keys(&monitor),
+            [
+                project("shop"),
+                member("alpha"),
+                member("gamma"),
+                project("blog"),
+                member("beta"),
+                member("delta"),
+            ]
+        );
+        assert_eq!(
+            monitor.selection_label().as_deref(),
+            Some("shop (2 containers)")
+        );
+        let members: Vec<&ContainerInfo> = monitor.containers.iter().step_by(2).collect();
+        assert_eq!(super::totals(&members).cpu_pct, 100.0);
+        let mut limited = [container("small", 0.0, 50.0), container("large", 0.0, 10.0)];
+        limited[0].stats.mem_used = 50;
+        limited[0].stats.mem_limit = 100;
+        limited[1].stats.mem_used = 100;
+        limited[1].stats.mem_limit = 1000;
+        let sum = super::totals(&limited.iter().collect::<Vec<_>>());
+        assert_eq!((sum.mem_used, sum.mem_limit), (150, 1100));
+        assert!((sum.mem_pct - 150.0 / 11.0).abs() < 0.001);
+
+        // Collapsing from a member selects its project's header
+        monitor.select_next();
+        assert!(monitor.set_collapsed(true));
+        assert_eq!(monitor.selected, Some(project("shop")));
+        assert_eq!(
+            keys(&monitor),
+            [
+                project("shop"),
+                project("blog"),
+                member("beta"),
+                member("delta")
+            ]
+        );
+        assert!(monitor.toggle_collapsed());
+        assert_eq!(keys(&monitor).len(), 6);
+
+        monitor.toggle_grouping();
+        assert_eq!(keys(&monitor)[0], member("alpha"));
+        assert!(!monitor.set_collapsed(true));
+    }
+
+    #[test]
+    fn columns_fit_wide_names_by_display_width() {
+        use crate::unicode::str_width;
+
+        let short = super::fit_column("网站", 10);
+        assert_eq!((short.as_str(), str_width(&short)), ("网站      ", 10));
+        let long = super::fit_column("前端服务容器名称很长", 10);
+        assert_eq!((long.as_str(), str_width(&long)), ("前端服... ", 10));
+    }
 }
@@ -0,0 +1,153 @@
+//! Container actions for the container list: start, stop, restart, pause and
+//! remove
+
+use crate::monitor::dialog::Dialog;
+use crate::monitor::docker::ContainerInfo;
+use crate::monitor::docker_api::{DockerClient, Lifecycle, STOP_GRACE};
+use std::fmt;
+use std::io;
+use std::thread::{self, JoinHandle};
+
+/// An action on one container, shown as the confirmation question
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContainerAction {
+    pub id: String,
+    pub name: String,
+    pub lifecycle: Lifecycle,
+    /// Whether the container was up when asked; removing it kills it first
+    running: bool,
+}
+
+impl fmt::Display for ContainerAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = &self.name;
+        match self.lifecycle {
+            Lifecycle::Remove if self.running => {
+                write!(f, "Remove {name}?\nIt is still running and will be killed.")
+            }
+            Lifecycle::Stop => write!(
+                f,
+                "Stop {name}?\nIt gets SIGTERM, then SIGKILL after {}s.",
+                STOP_GRACE
+            ),
+            lifecycle => write!(f, "{} {name}?", verb(lifecycle)),
+        }
+    }
+}
+
+impl ContainerAction {
+    /// Confirm `key`'s action, or explain why it does not fit the container's
+    /// state
+    pub fn dialog(container: &ContainerInfo, key: char) -> Option<Dialog<ContainerAction>> {
+        let paused = container.state == "paused";
+        let up = container.has_stats();
+        let lifecycle = match key {
+            'S' => Lifecycle::Start,
+            'x' => Lifecycle::Stop,
+            'R' => Lifecycle::Restart,
+            'p' if paused => Lifecycle::Unpause,
+            'p' => Lifecycle::Pause,
+            'D' => Lifecycle::Remove,
+            _ => return None,
+        };
+        let title = title(lifecycle);
+        let unavailable = match lifecycle {
+            Lifecycle::Start if up => "is already running",
+            Lifecycle::Stop | Lifecycle::Pause if !up => "is not running",
+            _ => "",
+        };
+        if !unavailable.is_empty() {
+            return Some(Dialog::message(
+                title,
+                format!("{} {unavailable}.", container.name),
+            ));
+        }
+
+        Some(Dialog::confirm(
+            title,
+            ContainerAction {
+                id: container.id.clone(),
+                name: container.name.clone(),
+                lifecycle,
+                running: up,
+            },
+        ))
+    }
+
+    /// Feedback while the action runs, e.g. "Stopping web…"
+    pub fn progress(&self) -> String {
+        format!("{} {}…", ongoing(self.lifecycle), self.name)
+    }
+
+    /// Run the action on its own thread, since stopping waits for the
+    /// container to exit. The result is feedback or a message to show.
+    pub fn spawn(self, client: &DockerClient) -> JoinHandle<Result<String, String>> {
+        let client = client.clone();
+        thread::spawn(move || self.apply(&client))
+    }
+
+    fn apply(&self, client: &DockerClient) -> Result<String, String> {
+        client
+            .lifecycle(&self.id, self.lifecycle)
+            .map(|()| format!("{} {}", done(self.lifecycle), self.name))
+            .map_err(|e| self.describe_error(&e))
+    }
+
+    fn describe_error(&self, error: &io::Error) -> String {
+        let what = format!("{} {}", verb(self.lifecycle).to_lowercase(), self.name);
+        match error.kind() {
+            io::ErrorKind::PermissionDenied => format!(
+                "Permission denied: cannot {what}.\nThe Docker socket needs root or the docker group."
+            ),
+            io::ErrorKind::NotFound => format!("{} no longer exists.", self.name),
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+                format!("Cannot {what}:\nthe daemon did not answer in time.")
+            }
+            _ => format!("Cannot {what}:\n{error}"),
+        }
+    }
+}
+
+fn title(lifecycle: Lifecycle) -> &'static str {
+    match lifecycle {
+        Lifecycle::Start => "START",
+        Lifecycle::Stop => "STOP",
+        Lifecycle::Restart => "RESTART",
+        Lifecycle::Pause => "PAUSE",
+        Lifecycle::Unpause => "UNPAUSE",
+        Lifecycle::Remove => "REMOVE",
+    }
+}
+
+fn verb(lifecycle: Lifecycle) -> &'static str {
+    match lifecycle {
+        Lifecycle::Start => "Start",
+        Lifecycle::Stop => "Stop",
+        Lifecycle::Restart => "Restart",
+        Lifecycle::Pause => "Pause",
+        Lifecycle::Unpause => "Unpause",
+        Lifecycle::Remove => "Remove",
+    }
+}
+
+fn ongoing(lifecycle: Lifecycle) -> &'static str {
+    match lifecycle {
+        Lifecycle::Start => "Starting",
+        Lifecycle::Stop => "Stopping",
+        Lifecycle::Restart => "Restarting",
+        Lifecycle::Pause => "Pausing",
+        Lifecycle::Unpause => "Unpausing",
+        Lifecycle::Remove => "Removing",
+    }
+}
+
+fn done(lifecycle: Lifecycle) -> &'static str {
+    match lifecycle {
+        Lifecycle::Start => "Started",
+        Lifecycle::Stop => "Stopped",
+        Lifecycle::Restart => "Restarted",
+        Lifecycle::Pause => "Paused",
+        Lifecycle::Unpause => "Unpaused",
+        Lifecycle::Remove => "Removed",
+    }
+}
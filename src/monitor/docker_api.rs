@@ -0,0 +1,776 @@
+//! Docker Engine API client over the daemon's Unix socket
+//!
+//! Podman serves the same API on its own socket, so either engine can be the
+//! container source.
+//!
+//! Speaks just enough HTTP/1.1 for the Engine API: one request per
+//! connection, with a Content-Length or chunked body. Stats and logs stream on
+//! a connection of their own, read on a background thread, so a sample reads
+//! the latest figures instead of waiting for the daemon to measure a CPU delta.
+
+use crate::monitor::docker::ContainerInfo;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::Shutdown;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+pub const DEFAULT_SOCKET: &str = "/var/run/docker.sock";
+/// Podman's system socket; rootless Podman listens under `$XDG_RUNTIME_DIR`
+const PODMAN_SOCKET: &str = "/run/podman/podman.sock";
+/// Label Docker Compose (and podman-compose) put on a project's containers
+const COMPOSE_PROJECT_LABEL: &str = "com.docker.compose.project";
+
+/// Longest wait for a response before the request fails
+const TIMEOUT: Duration = Duration::from_secs(3);
+/// Seconds the daemon waits for a container to stop before killing it
+pub(super) const STOP_GRACE: u64 = 10;
+/// Stop and restart answer once the container is down, up to the grace period
+const ACTION_TIMEOUT: Duration = Duration::from_secs(STOP_GRACE + 20);
+/// Log lines kept per stream; older ones are dropped
+const MAX_LOG_LINES: usize = 5000;
+/// Lines of history requested when a log stream opens
+const LOG_TAIL: usize = 500;
+/// Longest log line kept whole; longer output is split so a stream without
+/// newlines cannot grow without bound
+const MAX_LOG_LINE_BYTES: usize = 16 * 1024;
+
+type Body = Box<dyn Read + Send>;
+
+/// Resource usage from one stats object
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ContainerStats {
+    pub cpu_pct: f32,
+    /// Memory in use, not counting reclaimable page cache
+    pub mem_used: u64,
+    pub mem_limit: u64,
+    pub mem_pct: f32,
+    /// Bytes received and sent over all networks since the container started
+    pub net_rx: u64,
+    pub net_tx: u64,
+    pub block_read: u64,
+    pub block_write: u64,
+    pub pids: u64,
+}
+
+impl ContainerStats {
+    /// Read a stats object the way `docker stats` does
+    fn parse(stats: &Value) -> Self {
+        let number = |pointer: &str| stats.pointer(pointer).and_then(Value::as_u64).unwrap_or(0);
+
+        let cpu_delta = number("/cpu_stats/cpu_usage/total_usage")
+            .saturating_sub(number("/precpu_stats/cpu_usage/total_usage"));
+        let system_delta = number("/cpu_stats/system_cpu_usage")
+            .saturating_sub(number("/precpu_stats/system_cpu_usage"));
+        let online_cpus = match number("/cpu_stats/online_cpus") {
+            0 => stats
+                .pointer("/cpu_stats/cpu_usage/percpu_usage")
+                .and_then(Value::as_array)
+                .map_or(1, Vec::len),
+            cpus => cpus as usize,
+        };
+        let cpu_pct = if system_delta > 0 {
+            (cpu_delta as f64 / system_delta as f64 * online_cpus as f64 * 100.0) as f32
+        } else {
+            0.0
+        };
+
+        // Inactive page cache: cgroup v2 names it inactive_file, v1 total_inactive_file
+        let cache = match number("/memory_stats/stats/inactive_file") {
+            0 => number("/memory_stats/stats/total_inactive_file"),
+            cache => cache,
+        };
+        let mem_used = number("/memory_stats/usage").saturating_sub(cache);
+        let mem_limit = number("/memory_stats/limit");
+        let mem_pct = if mem_limit > 0 {
+            (mem_used as f64 / mem_limit as f64 * 100.0) as f32
+        } else {
+            0.0
+        };
+
+        let (mut net_rx, mut net_tx) = (0, 0);
+        if let Some(networks) = stats["networks"].as_object() {
+            for network in networks.values() {
+                net_rx += network["rx_bytes"].as_u64().unwrap_or(0);
+                net_tx += network["tx_bytes"].as_u64().unwrap_or(0);
+            }
+        }
+
+        let (mut block_read, mut block_write) = (0, 0);
+        if let Some(entries) = stats
+            .pointer("/blkio_stats/io_service_bytes_recursive")
+            .and_then(Value::as_array)
+        {
+            for entry in entries {
+                let bytes = entry["value"].as_u64().unwrap_or(0);
+                match entry["op"].as_str() {
+                    Some(op) if op.eq_ignore_ascii_case("read") => block_read += bytes,
+                    Some(op) if op.eq_ignore_ascii_case("write") => block_write += bytes,
+                    _ => {}
+                }
+            }
+        }
+
+        Self {
+            cpu_pct,
+            mem_used,
+            mem_limit,
+            mem_pct,
+            net_rx,
+            net_tx,
+            block_read,
+            block_write,
+            pids: number("/pids_stats/current"),
+        }
+    }
+}
+
+/// A streamed response, read on a background thread into shared state
+pub struct Stream<T> {
+    /// The stream's connection, shut down to end the reader
+    connection: UnixStream,
+    shared: Arc<Mutex<T>>,
+    reader: JoinHandle<()>,
+}
+
+impl<T> Stream<T> {
+    /// Whether the stream has ended, e.g. because the container stopped
+    pub fn is_finished(&self) -> bool {
+        self.reader.is_finished()
+    }
+
+    pub fn with<R>(&self, read: impl FnOnce(&T) -> R) -> Option<R> {
+        self.shared.lock().ok().map(|shared| read(&shared))
+    }
+}
+
+impl<T> Drop for Stream<T> {
+    fn drop(&mut self) {
+        let _ = self.connection.shutdown(Shutdown::Both);
+    }
+}
+
+/// Stats for one container
+pub type StatsStream = Stream<Option<ContainerStats>>;
+
+impl StatsStream {
+    /// The most recent stats, once the daemon has sent any
+    pub fn latest(&self) -> Option<ContainerStats> {
+        self.with(Option::clone).flatten()
+    }
+}
+
+/// One container's output, following new lines as they are written
+pub type LogStream = Stream<LogBuffer>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogLine {
+    pub stderr: bool,
+    pub text: String,
+}
+
+/// The latest log lines of a stream
+#[derive(Debug, Default)]
+pub struct LogBuffer {
+    pub lines: VecDeque<LogLine>,
+    /// Lines dropped from the front, so `dropped + i` numbers line `i`
+    /// across the whole stream
+    pub dropped: usize,
+}
+
+impl LogBuffer {
+    pub fn push(&mut self, line: LogLine) {
+        if self.lines.len() == MAX_LOG_LINES {
+            self.lines.pop_front();
+            self.dropped += 1;
+        }
+        self.lines.push_back(line);
+    }
+
+    /// Number of lines received, counting dropped ones
+    pub fn end(&self) -> usize {
+        self.dropped + self.lines.len()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lifecycle {
+    Start,
+    Stop,
+    Restart,
+    Pause,
+    Unpause,
+    /// Remove, killing the container first if it runs
+    Remove,
+}
+
+/// A container engine serving the Engine API
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Engine {
+    Docker,
+    Podman,
+}
+
+impl Engine {
+    pub fn name(self) -> &'static str {
+        match self {
+            Engine::Docker => "Docker",
+            Engine::Podman => "Podman",
+        }
+    }
+
+    /// Where the engine listens, most likely first: a `unix://` address in
+    /// `DOCKER_HOST` or `CONTAINER_HOST` (Podman's equivalent), then the
+    /// system socket for Docker but the rootless one for Podman. `env` looks
+    /// up environment variables.
+    fn sockets(self, env: impl Fn(&str) -> Option<OsString>) -> Vec<PathBuf> {
+        let variable = match self {
+            Engine::Docker => "DOCKER_HOST",
+            Engine::Podman => "CONTAINER_HOST",
+        };
+        let from_env = env(variable)
+            .and_then(|host| host.into_string().ok())
+            .and_then(|host| host.strip_prefix("unix://").map(PathBuf::from));
+        let rootless = |path: &str| env("XDG_RUNTIME_DIR").map(|dir| PathBuf::from(dir).join(path));
+        let defaults = match self {
+            Engine::Docker => vec![Some(PathBuf::from(DEFAULT_SOCKET)), rootless("docker.sock")],
+            Engine::Podman => vec![
+                rootless("podman/podman.sock"),
+                Some(PathBuf::from(PODMAN_SOCKET)),
+            ],
+        };
+        from_env
+            .into_iter()
+            .chain(defaults.into_iter().flatten())
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub struct DockerClient {
+    engine: Engine,
+    socket: PathBuf,
+}
+
+impl DockerClient {
+    pub fn new(engine: Engine, socket: impl Into<PathBuf>) -> Self {
+        Self {
+            engine,
+            socket: socket.into(),
+        }
+    }
+
+    /// Connect to the first socket that exists, of `engine` or else of
+    /// Docker then Podman. With none, the engine's usual socket is used so
+    /// errors name it.
+    pub fn discover(engine: Option<Engine>) -> Self {
+        let engines = match engine {
+            Some(engine) => vec![engine],
+            None => vec![Engine::Docker, Engine::Podman],
+        };
+        let candidates: Vec<(Engine, PathBuf)> = engines
+            .iter()
+            .flat_map(|&engine| {
+                engine
+                    .sockets(|name| std::env::var_os(name))
+                    .into_iter()
+                    .map(move |path| (engine, path))
+            })
+            .collect();
+        let (engine, socket) = candidates
+            .iter()
+            .find(|(_, socket)| socket.exists())
+            .or(candidates.first())
+            .cloned()
+            .unwrap_or_else(|| (Engine::Docker, PathBuf::from(DEFAULT_SOCKET)));
+        Self::new(engine, socket)
+    }
+
+    pub fn engine(&self) -> Engine {
+        self.engine
+    }
+
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    /// Every container, running or not, with its inspect details. Stats
+    /// start out empty; they come from `stats_stream`.
+    pub fn containers(&self) -> io::Result<Vec<ContainerInfo>> {
+        let list = self.get_json("/containers/json?all=1")?;
+        let Value::Array(list) = list else {
+            return Err(invalid("container list is not an array"));
+        };
+
+        let mut containers = Vec::new();
+        for summary in &list {
+            let Some(id) = summary["Id"].as_str() else {
+                continue;
+            };
+            let details = match self.get_json(&format!("/containers/{id}/json")) {
+                Ok(details) => details,
+                // Removed between the list and the inspect
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            containers.push(parse_container(summary, &details));
+        }
+        Ok(containers)
+    }
+
+    /// Start streaming a container's stats
+    pub fn stats_stream(&self, id: &str) -> io::Result<StatsStream> {
+        self.stream(
+            &format!("/containers/{id}/stats?stream=1"),
+            |body, latest| {
+                for stats in serde_json::Deserializer::from_reader(body).into_iter::<Value>() {
+                    let Ok(stats) = stats else {
+                        break;
+                    };
+                    if let Ok(mut latest) = latest.lock() {
+                        *latest = Some(ContainerStats::parse(&stats));
+                    }
+                }
+            },
+        )
+    }
+
+    /// Tail a container's stdout and stderr, following new output.
+    /// Containers with a TTY send one raw stream; the rest multiplex both.
+    pub fn log_stream(&self, id: &str, tty: bool) -> io::Result<LogStream> {
+        let path = format!("/containers/{id}/logs?stdout=1&stderr=1&follow=1&tail={LOG_TAIL}");
+        self.stream(&path, move |body, buffer| {
+            // An ended or shut down stream is expected; the pane says so
+            let _ = read_logs(body, tty, buffer);
+        })
+    }
+
+    /// Run a lifecycle action. Stop and restart return once the container
+    /// has stopped, which can take the whole grace period.
+    pub fn lifecycle(&self, id: &str, action: Lifecycle) -> io::Result<()> {
+        let (method, path) = match action {
+            Lifecycle::Start => ("POST", format!("/containers/{id}/start")),
+            Lifecycle::Stop => ("POST", format!("/containers/{id}/stop?t={STOP_GRACE}")),
+            Lifecycle::Restart => ("POST", format!("/containers/{id}/restart?t={STOP_GRACE}")),
+            Lifecycle::Pause => ("POST", format!("/containers/{id}/pause")),
+            Lifecycle::Unpause => ("POST", format!("/containers/{id}/unpause")),
+            Lifecycle::Remove => ("DELETE", format!("/containers/{id}?force=1")),
+        };
+        read_response(self.send(method, &path, ACTION_TIMEOUT)?)?;
+        Ok(())
+    }
+
+    /// Send the request here and read the response on a thread. The daemon
+    /// may hold back the headers until it has something to send.
+    fn stream<T: Default + Send + 'static>(
+        &self,
+        path: &str,
+        read: impl FnOnce(Body, &Mutex<T>) + Send + 'static,
+    ) -> io::Result<Stream<T>> {
+        let stream = self.send("GET", path, TIMEOUT)?;
+        // Streams stay open as long as the container runs; only the connect
+        // and request are time-limited
+        stream.set_read_timeout(None)?;
+        let connection = stream.try_clone()?;
+
+        let shared = Arc::new(Mutex::new(T::default()));
+        let state = Arc::clone(&shared);
+        let reader = thread::spawn(move || {
+            if let Ok(body) = read_response(stream) {
+                read(body, &state);
+            }
+        });
+
+        Ok(Stream {
+            connection,
+            shared,
+            reader,
+        })
+    }
+
+    fn get_json(&self, path: &str) -> io::Result<Value> {
+        let body = read_response(self.send("GET", path, TIMEOUT)?)?;
+        Ok(serde_json::from_reader(body)?)
+    }
+
+    fn send(&self, method: &str, path: &str, timeout: Duration) -> io::Result<UnixStream> {
+        let mut stream = UnixStream::connect(&self.socket)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: docker\r\nConnection: close\r\n\r\n"
+        )?;
+        stream.flush()?;
+        Ok(stream)
+    }
+}
+
+/// Read the status line and headers, returning the body. Error statuses
+/// become errors carrying the daemon's message.
+fn read_response(stream: UnixStream) -> io::Result<Body> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let status: u16 = line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| invalid("malformed HTTP status line"))?;
+
+    let mut chunked = false;
+    let mut length = None;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("transfer-encoding") {
+                chunked = value.eq_ignore_ascii_case("chunked");
+            } else if name.eq_ignore_ascii_case("content-length") {
+                length = value.parse::<u64>().ok();
+            }
+        }
+    }
+
+    let mut body: Body = match (chunked, length) {
+        (true, _) => Box::new(Chunked::new(reader)),
+        (false, Some(length)) => Box::new(reader.take(length)),
+        (false, None) => Box::new(reader),
+    };
+    // 304: the container was already in the requested state
+    if (200..300).contains(&status) || status == 304 {
+        return Ok(body);
+    }
+
+    let mut text = String::new();
+    body.read_to_string(&mut text)?;
+    let message = serde_json::from_str::<Value>(&text)
+        .ok()
+        .and_then(|error| error["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("HTTP {status}"));
+    let kind = match status {
+        404 => io::ErrorKind::NotFound,
+        401 | 403 => io::ErrorKind::PermissionDenied,
+        _ => io::ErrorKind::Other,
+    };
+    Err(io::Error::new(kind, message))
+}
+
+/// A chunked transfer-encoded body
+struct Chunked<R> {
+    inner: R,
+    /// Bytes left in the current chunk
+    remaining: u64,
+    done: bool,
+}
+
+impl<R: BufRead> Chunked<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            remaining: 0,
+            done: false,
+        }
+    }
+}
+
+impl<R: BufRead> Read for Chunked<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.remaining == 0 {
+            if self.done {
+                return Ok(0);
+            }
+            let mut line = String::new();
+            if self.inner.read_line(&mut line)? == 0 {
+                return Ok(0);
+            }
+            // The blank line closing the previous chunk
+            let size = line.split(';').next().unwrap_or("").trim();
+            if size.is_empty() {
+                continue;
+            }
+            self.remaining =
+                u64::from_str_radix(size, 16).map_err(|_| invalid("malformed chunk size"))?;
+            self.done = self.remaining == 0;
+        }
+
+        let limit = buf.len().min(self.remaining as usize);
+        let read = self.inner.read(&mut buf[..limit])?;
+        if read == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        self.remaining -= read as u64;
+        Ok(read)
+    }
+}
+
+/// Split a log body into lines. Multiplexed frames carry an 8-byte header:
+/// the stream (1 stdout, 2 stderr), three zero bytes and a big-endian length;
+/// frames are read in chunks, whatever length the header claims.
+fn read_logs(mut body: impl Read, tty: bool, buffer: &Mutex<LogBuffer>) -> io::Result<()> {
+    // Output not yet ending in a newline, for stdout and stderr
+    let mut partial = [Vec::new(), Vec::new()];
+    let mut chunk = [0; 8192];
+    // Bytes of the current frame still to read, and whether it is stderr
+    let mut frame = (0, false);
+    loop {
+        let (stderr, data) = if tty {
+            let read = body.read(&mut chunk)?;
+            if read == 0 {
+                return Ok(());
+            }
+            (false, &chunk[..read])
+        } else {
+            if frame.0 == 0 {
+                let mut header = [0; 8];
+                match body.read_exact(&mut header) {
+                    Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                    result => result?,
+                }
+                let length = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+                frame = (length as usize, header[0] == 2);
+                continue;
+            }
+            let read = frame.0.min(chunk.len());
+            body.read_exact(&mut chunk[..read])?;
+            frame.0 -= read;
+            (frame.1, &chunk[..read])
+        };
+
+        let pending = &mut partial[usize::from(stderr)];
+        pending.extend_from_slice(data);
+        let complete: Vec<u8> = match pending.iter().rposition(|&byte| byte == b'\n') {
+            Some(last_newline) => {
+                let mut complete: Vec<u8> = pending.drain(..=last_newline).collect();
+                complete.pop();
+                complete
+            }
+            None if pending.len() >= MAX_LOG_LINE_BYTES => std::mem::take(pending),
+            None => continue,
+        };
+        let Ok(mut buffer) = buffer.lock() else {
+            return Ok(());
+        };
+        for line in complete.split(|&byte| byte == b'\n') {
+            buffer.push(LogLine {
+                stderr,
+                text: clean_log_line(&String::from_utf8_lossy(line)),
+            });
+        }
+    }
+}
+
+/// Drop carriage returns, colour codes and other control characters, which
+/// would upset the layout, and expand tabs
+fn clean_log_line(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            // CSI sequences end at the first letter
+            '\x1b' => {
+                if chars.next() == Some('[') {
+                    for c in chars.by_ref() {
+                        if c.is_ascii_alphabetic() {
+                            break;
+                        }
+                    }
+                }
+            }
+            '\t' => text.push_str("    "),
+            c if c.is_control() => {}
+            c => text.push(c),
+        }
+    }
+    text
+}
+
+fn parse_container(summary: &Value, details: &Value) -> ContainerInfo {
+    let text = |value: &Value| value.as_str().unwrap_or_default().to_string();
+    let name = summary["Names"][0]
+        .as_str()
+        .or_else(|| details["Name"].as_str())
+        .unwrap_or_default()
+        .trim_start_matches('/')
+        .to_string();
+    // Never-started containers carry the zero time, 0001-01-01
+    let started_at = details["State"]["StartedAt"]
+        .as_str()
+        .and_then(|time| chrono::DateTime::parse_from_rfc3339(time).ok())
+        .map(|time| time.timestamp())
+        .filter(|&time| time > 0);
+
+    ContainerInfo {
+        id: text(&summary["Id"]),
+        name,
+        image: text(&summary["Image"]),
+        project: summary["Labels"][COMPOSE_PROJECT_LABEL]
+            .as_str()
+            .map(str::to_string),
+        state: text(&summary["State"]),
+        status: text(&summary["Status"]),
+        health: details["State"]["Health"]["Status"]
+            .as_str()
+            .map(str::to_string),
+        restart_count: details["RestartCount"].as_u64().unwrap_or(0),
+        started_at,
+        tty: details["Config"]["Tty"].as_bool().unwrap_or(false),
+        stats: ContainerStats::default(),
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_logs, Chunked, ContainerStats, Engine, LogBuffer, LogLine};
+    use serde_json::json;
+    use std::ffi::OsString;
+    use std::io::Read;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    #[test]
+    fn stats_compute_usage_like_docker_stats() {
+        let stats = ContainerStats::parse(&json!({
+            "cpu_stats": {
+                "cpu_usage": { "total_usage": 3_000_000 },
+                "system_cpu_usage": 20_000_000,
+                "online_cpus": 4
+            },
+            "precpu_stats": {
+                "cpu_usage": { "total_usage": 1_000_000 },
+                "system_cpu_usage": 10_000_000
+            },
+            "memory_stats": {
+                "usage": 300,
+                "limit": 1000,
+                "stats": { "inactive_file": 100 }
+            },
+            "networks": {
+                "eth0": { "rx_bytes": 10, "tx_bytes": 20 },
+                "eth1": { "rx_bytes": 1, "tx_bytes": 2 }
+            },
+            "blkio_stats": {
+                "io_service_bytes_recursive": [
+                    { "major": 8, "minor": 0, "op": "read", "value": 4096 },
+                    { "major": 8, "minor": 0, "op": "write", "value": 512 },
+                    { "major": 8, "minor": 16, "op": "Read", "value": 4 }
+                ]
+            },
+            "pids_stats": { "current": 7 }
+        }));
+
+        assert_eq!(
+            stats,
+            ContainerStats {
+                cpu_pct: 80.0,
+                mem_used: 200,
+                mem_limit: 1000,
+                mem_pct: 20.0,
+                net_rx: 11,
+                net_tx: 22,
+                block_read: 4100,
+                block_write: 512,
+                pids: 7,
+            }
+        );
+        // The first object of a stream has no previous reading
+        assert_eq!(ContainerStats::parse(&json!({})), ContainerStats::default());
+
+        let mut body = String::new();
+        Chunked::new(&b"4\r\n{\"a\"\r\n3;ext=1\r\n:1}\r\n0\r\n\r\n"[..])
+            .read_to_string(&mut body)
+            .expect("chunked body");
+        assert_eq!(body, "{\"a\":1}");
+    }
+
+    #[test]
+    fn multiplexed_logs_split_into_lines_per_stream() {
+        let frame = |stream: u8, text: &str| {
+            let mut frame = vec![stream, 0, 0, 0];
+            frame.extend_from_slice(&(text.len() as u32).to_be_bytes());
+            frame.extend_from_slice(text.as_bytes());
+            frame
+        };
+        let body = [
+            frame(1, "GET / 200\nGET /a"),
+            frame(2, "\x1b[31mpanic\x1b[0m:\tboom\r\n"),
+            frame(1, "pi 404\n"),
+        ]
+        .concat();
+
+        let buffer = Mutex::new(LogBuffer::default());
+        read_logs(&body[..], false, &buffer).expect("complete frames");
+        let line = |stderr, text: &str| LogLine {
+            stderr,
+            text: text.to_string(),
+        };
+        assert_eq!(
+            Vec::from(buffer.into_inner().unwrap().lines),
+            vec![
+                line(false, "GET / 200"),
+                line(true, "panic:    boom"),
+                line(false, "GET /api 404"),
+            ]
+        );
+
+        let buffer = Mutex::new(LogBuffer::default());
+        read_logs(&b"raw tty\noutput\n"[..], true, &buffer).expect("raw stream");
+        assert_eq!(buffer.into_inner().unwrap().end(), 2);
+
+        // A header claiming 4 GiB is read in chunks until the body runs out
+        let mut body = vec![1, 0, 0, 0, 0xff, 0xff, 0xff, 0xff];
+        body.extend(std::iter::repeat_n(b'x', 40_000));
+        let buffer = Mutex::new(LogBuffer::default());
+        assert!(read_logs(&body[..], false, &buffer).is_err());
+        let lines = buffer.into_inner().unwrap().lines;
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.text.len() < 20_000));
+    }
+
+    #[test]
+    fn engines_prefer_their_host_variable_then_known_sockets() {
+        let env = |name: &str| -> Option<OsString> {
+            match name {
+                "CONTAINER_HOST" => Some("unix:///tmp/podman.sock".into()),
+                "DOCKER_HOST" => Some("tcp://10.0.0.1:2375".into()),
+                "XDG_RUNTIME_DIR" => Some("/run/user/1000".into()),
+                _ => None,
+            }
+        };
+        let paths = |paths: &[&str]| paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+        assert_eq!(
+            Engine::Podman.sockets(env),
+            paths(&[
+                "/tmp/podman.sock",
+                "/run/user/1000/podman/podman.sock",
+                "/run/podman/podman.sock"
+            ])
+        );
+        // Only unix:// hosts are sockets this client can use
+        assert_eq!(
+            Engine::Docker.sockets(env),
+            paths(&["/var/run/docker.sock", "/run/user/1000/docker.sock"])
+        );
+        assert_eq!(
+            Engine::Docker.sockets(|_| None),
+            paths(&["/var/run/docker.sock"])
+        );
+    }
+}
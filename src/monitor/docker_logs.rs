@@ -0,0 +1,269 @@
+//! Log pane: the selected container's stdout and stderr under the container
+//! list
+//!
+//! The pane follows new output until it is scrolled back, and a search filter
+//! narrows it to matching lines.
+
+use crate::colors::ColorState;
+use crate::monitor::docker::ContainerInfo;
+use crate::monitor::docker_api::{DockerClient, LogBuffer, LogLine, LogStream};
+use crate::monitor::layout::{draw_panel_box, muted_color_scheme, text_color_scheme, Rect};
+use crate::terminal::Terminal;
+use crate::unicode;
+use crossterm::event::KeyCode;
+use crossterm::style::Color;
+
+/// Scroll position and search filter over a log buffer
+#[derive(Debug, Default)]
+pub struct LogView {
+    /// Number (see `LogBuffer::dropped`) of the line just past the last one
+    /// shown; `None` follows new output
+    bottom: Option<usize>,
+    pub filter: String,
+    pub filter_editing: bool,
+}
+
+impl LogView {
+    pub fn following(&self) -> bool {
+        self.bottom.is_none()
+    }
+
+    /// Follow new output, or hold the view at the newest line received
+    pub fn set_following(&mut self, buffer: &LogBuffer, follow: bool) {
+        self.bottom = if follow { None } else { Some(buffer.end()) };
+    }
+
+    /// Scroll by `lines` matching lines; scrolling back to the end follows
+    /// again
+    pub fn scroll(&mut self, buffer: &LogBuffer, lines: isize) {
+        let matching = self.matching(buffer);
+        let shown = match self.bottom {
+            Some(bottom) => matching.iter().filter(|&&number| number < bottom).count(),
+            None => matching.len(),
+        };
+        let shown = if lines < 0 {
+            shown
+                .saturating_sub(lines.unsigned_abs())
+                .max(matching.len().min(1))
+        } else {
+            shown + lines as usize
+        };
+        self.bottom = match shown.checked_sub(1) {
+            _ if shown >= matching.len() && lines > 0 => None,
+            Some(last) => Some(matching[last] + 1),
+            None => Some(buffer.dropped),
+        };
+    }
+
+    pub fn edit_filter(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter => self.filter_editing = false,
+            KeyCode::Esc => {
+                self.filter_editing = false;
+                self.filter.clear();
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Char(c) if !c.is_control() => self.filter.push(c),
+            _ => {}
+        }
+    }
+
+    fn matches(&self, line: &LogLine) -> bool {
+        self.filter.is_empty()
+            || line
+                .text
+                .to_lowercase()
+                .contains(&self.filter.to_lowercase())
+    }
+
+    /// Numbers of the buffered lines that match the filter
+    fn matching(&self, buffer: &LogBuffer) -> Vec<usize> {
+        (buffer.dropped..)
+            .zip(&buffer.lines)
+            .filter(|(_, line)| self.matches(line))
+            .map(|(number, _)| number)
+            .collect()
+    }
+
+    /// The matching lines that fit in `height`, ending at the bottom
+    fn visible<'a>(&self, buffer: &'a LogBuffer, height: usize) -> Vec<&'a LogLine> {
+        let bottom = self.bottom.unwrap_or(usize::MAX);
+        let lines: Vec<&LogLine> = (buffer.dropped..)
+            .zip(&buffer.lines)
+            .take_while(|&(number, _)| number < bottom)
+            .filter(|(_, line)| self.matches(line))
+            .map(|(_, line)| line)
+            .collect();
+        lines[lines.len().saturating_sub(height)..].to_vec()
+    }
+}
+
+pub struct LogPane {
+    id: String,
+    name: String,
+    stream: Result<LogStream, String>,
+    pub view: LogView,
+}
+
+impl LogPane {
+    pub fn open(client: &DockerClient, container: &ContainerInfo) -> Self {
+        Self {
+            id: container.id.clone(),
+            name: container.name.clone(),
+            stream: client
+                .log_stream(&container.id, container.tty)
+                .map_err(|e| e.to_string()),
+            view: LogView::default(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn scroll_by(&mut self, lines: isize) {
+        if let Ok(stream) = &self.stream {
+            stream.with(|buffer| self.view.scroll(buffer, lines));
+        }
+    }
+
+    pub fn set_following(&mut self, follow: bool) {
+        if let Ok(stream) = &self.stream {
+            stream.with(|buffer| self.view.set_following(buffer, follow));
+        }
+    }
+
+    pub fn render(&self, term: &mut Terminal, area: &Rect, colors: &ColorState) {
+        let status = match &self.stream {
+            Ok(stream) if stream.is_finished() => "ended",
+            _ if self.view.following() => "following",
+            _ => "paused",
+        };
+        let title = format!(" LOGS {} · {status} ", self.name);
+        draw_panel_box(term, area, &title, muted_color_scheme(colors), false);
+        let (x, y, width) = (area.inner_x(), area.inner_y(), area.inner_width() as usize);
+        let mut height = area.inner_height() as usize;
+        if width < 20 || height < 2 {
+            return;
+        }
+
+        let stream = match &self.stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                let message = format!("Cannot read logs: {e}");
+                term.set_str(
+                    x,
+                    y,
+                    unicode::truncate_to_width(&message, width),
+                    Some(Color::Red),
+                    false,
+                );
+                return;
+            }
+        };
+
+        // The filter bar takes the pane's last line while typing or filtering
+        if self.view.filter_editing || !self.view.filter.is_empty() {
+            height -= 1;
+            let (matches, total) = stream
+                .with(|buffer| (self.view.matching(buffer).len(), buffer.lines.len()))
+                .unwrap_or_default();
+            let bar = format!(
+                "/{}{}  {matches} of {total}",
+                self.view.filter,
+                if self.view.filter_editing { "_" } else { "" },
+            );
+            term.set_str(
+                x,
+                y + height as i32,
+                unicode::truncate_to_width(&bar, width),
+                Some(text_color_scheme(colors)),
+                self.view.filter_editing,
+            );
+        }
+
+        stream.with(|buffer| {
+            let lines = self.view.visible(buffer, height);
+            if lines.is_empty() {
+                let empty = if buffer.lines.is_empty() {
+                    "No output yet"
+                } else {
+                    "No matching lines"
+                };
+                term.set_str(x, y, empty, Some(muted_color_scheme(colors)), false);
+            }
+            for (row, line) in lines.into_iter().enumerate() {
+                let color = if line.stderr {
+                    Color::Red
+                } else {
+                    text_color_scheme(colors)
+                };
+                term.set_str(
+                    x,
+                    y + row as i32,
+                    unicode::truncate_to_width(&line.text, width),
+                    Some(color),
+                    false,
+                );
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LogView;
+    use crate::monitor::docker_api::{LogBuffer, LogLine};
+
+    fn buffer(lines: &[&str]) -> LogBuffer {
+        let mut buffer = LogBuffer::default();
+        for text in lines {
+            buffer.push(LogLine {
+                stderr: false,
+                text: text.to_string(),
+            });
+        }
+        buffer
+    }
+
+    fn texts(view: &LogView, buffer: &LogBuffer, height: usize) -> Vec<String> {
+        view.visible(buffer, height)
+            .into_iter()
+            .map(|line| line.text.clone())
+            .collect()
+    }
+
+    #[test]
+    fn log_view_follows_scrolls_back_and_filters() {
+        let mut buffer = buffer(&["boot", "GET /", "error: disk", "GET /a", "Error: net"]);
+        let mut view = LogView::default();
+        assert_eq!(texts(&view, &buffer, 2), ["GET /a", "Error: net"]);
+
+        view.scroll(&buffer, -2);
+        assert!(!view.following());
+        assert_eq!(texts(&view, &buffer, 2), ["GET /", "error: disk"]);
+        view.scroll(&buffer, -10);
+        assert_eq!(texts(&view, &buffer, 2), ["boot"]);
+
+        // A held view ignores new output until it follows again
+        view.set_following(&buffer, false);
+        buffer.push(LogLine {
+            stderr: true,
+            text: "late error".to_string(),
+        });
+        assert_eq!(texts(&view, &buffer, 1), ["Error: net"]);
+        view.scroll(&buffer, 1);
+        assert!(view.following());
+        assert_eq!(texts(&view, &buffer, 1), ["late error"]);
+
+        view.filter = "ERROR".to_string();
+        assert_eq!(
+            texts(&view, &buffer, 5),
+            ["error: disk", "Error: net", "late error"]
+        );
+        view.scroll(&buffer, -1);
+        assert_eq!(texts(&view, &buffer, 5), ["error: disk", "Error: net"]);
+    }
+}
@@ -1,13 +1,16 @@
 use crate::colors::ColorState;
 use crate::help::HelpSpec;
+use crate::monitor::alert::Flash;
 use crate::monitor::layout::{
     cpu_gradient_color_scheme, draw_meter_btop_scheme, format_bytes, header_color_scheme,
     muted_color_scheme, temp_gradient_color_scheme, Rect,
 };
+use crate::monitor::output::{Collector, Snapshot};
 use crate::monitor::{command_output_with_timeout, MonitorAction, MonitorConfig, MonitorState};
 use crate::terminal::Terminal;
+use crate::unicode;
 use crossterm::style::Color;
-use crossterm::terminal::size;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io;
 use std::process::Command;
@@ -16,13 +19,14 @@ use std::time::Duration;
 const COLLECTOR_TIMEOUT: Duration = Duration::from_secs(3);
 const BACKEND_RETRY_INTERVAL: Duration = Duration::from_secs(5);
 
-struct GpuInfo {
-    name: String,
-    utilization: f32,
-    memory_used: u64,
-    memory_total: u64,
-    temperature: Option<u32>,
-    power_draw: Option<f32>,
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GpuInfo {
+    pub name: String,
+    pub utilization: f32,
+    pub memory_used: u64,
+    pub memory_total: u64,
+    pub temperature: Option<u32>,
+    pub power_draw: Option<f32>,
     power_limit: Option<f32>,
     fan_speed: Option<u32>,
     fan_max: Option<u32>,
@@ -30,7 +34,7 @@ struct GpuInfo {
 }
 
 impl GpuInfo {
-    fn memory_percent(&self) -> f32 {
+    pub fn memory_percent(&self) -> f32 {
         if self.memory_total > 0 {
             (self.memory_used as f32 / self.memory_total as f32) * 100.0
         } else {
@@ -65,6 +69,8 @@ pub struct GpuMonitor {
     amd_name: Option<String>,
     error_msg: Option<String>,
     next_backend_probe: std::time::Instant,
+    /// Rows flashing for firing alerts
+    pub flash: Flash,
 }
 
 impl GpuMonitor {
@@ -86,6 +92,7 @@ impl GpuMonitor {
                 amd_name: None,
                 error_msg: None,
                 next_backend_probe: std::time::Instant::now() + BACKEND_RETRY_INTERVAL,
+                flash: Flash::default(),
             };
         }
 
@@ -100,6 +107,7 @@ impl GpuMonitor {
                 amd_card_path: Some(card_path),
                 error_msg: None,
                 next_backend_probe: std::time::Instant::now() + BACKEND_RETRY_INTERVAL,
+                flash: Flash::default(),
             };
         }
 
@@ -111,6 +119,7 @@ impl GpuMonitor {
             amd_name: None,
             error_msg: Some("No GPU detected".to_string()),
             next_backend_probe: std::time::Instant::now() + BACKEND_RETRY_INTERVAL,
+            flash: Flash::default(),
         }
     }
 
@@ -172,6 +181,25 @@ impl GpuMonitor {
         "AMD GPU".to_string()
     }
 
+    /// A monitor that only shows recorded samples, skipping backend detection
+    pub fn replaying() -> Self {
+        Self {
+            gpus: Vec::new(),
+            backend: GpuBackend::None,
+            amd_card_path: None,
+            amd_hwmon_path: None,
+            amd_name: None,
+            error_msg: None,
+            next_backend_probe: std::time::Instant::now(),
+            flash: Flash::default(),
+        }
+    }
+
+    /// Show recorded GPUs in place of live readings
+    pub fn restore(&mut self, gpus: &[GpuInfo]) {
+        self.gpus = gpus.to_vec();
+    }
+
     pub fn update(&mut self) -> io::Result<()> {
         match self.backend {
             GpuBackend::Nvidia => self.update_nvidia(),
@@ -313,7 +341,6 @@ impl GpuMonitor {
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub fn render(&self, term: &mut Terminal, bx: &Rect, colors: &ColorState) {
         let x = bx.inner_x();
         let y = bx.inner_y();
@@ -366,12 +393,9 @@ impl GpuMonitor {
             let temp_len = temp_str.as_ref().map(|s| s.len()).unwrap_or(0);
             let max_name_len = w.saturating_sub(temp_len + 2); // +2 for spacing
 
-            let name_display = if gpu.name.chars().count() > max_name_len {
-                let truncated: String = gpu
-                    .name
-                    .chars()
-                    .take(max_name_len.saturating_sub(1))
-                    .collect();
+            let name_display = if unicode::str_width(&gpu.name) > max_name_len {
+                let truncated =
+                    unicode::truncate_to_width(&gpu.name, max_name_len.saturating_sub(1));
                 format!("{}…", truncated)
             } else {
                 gpu.name.clone()
@@ -396,6 +420,7 @@ impl GpuMonitor {
                     false,
                 );
             }
+            self.flash.mark(term, x, cy, w, &[&i.to_string()]);
             cy += 1;
 
             // GPU utilization
@@ -544,10 +569,22 @@ impl GpuMonitor {
     }
 }
 
+impl Collector for GpuMonitor {
+    fn sample(&mut self, _elapsed: f32) -> io::Result<()> {
+        self.update()
+    }
+
+    fn snapshot(&self) -> Snapshot<'_> {
+        Snapshot::Gpu { gpus: &self.gpus }
+    }
+}
+
 pub fn run(config: MonitorConfig) -> io::Result<()> {
     let mut term = Terminal::new(true)?;
     let mut state = MonitorState::new(config.time_step, 0.5);
     let mut monitor = GpuMonitor::new();
+    let mut sample_log = config.sample_log;
+    let mut alerts = config.alerts;
     const HELP: HelpSpec = HelpSpec::monitor("GPU MONITOR", &[]);
 
     loop {
@@ -559,7 +596,7 @@ pub fn run(config: MonitorConfig) -> io::Result<()> {
             }
         }
 
-        if let Ok((new_w, new_h)) = size() {
+        if let Ok((new_w, new_h)) = term.query_size() {
             let (cur_w, cur_h) = term.size();
             if new_w != cur_w || new_h != cur_h {
                 term.resize(new_w, new_h);
@@ -567,8 +604,9 @@ pub fn run(config: MonitorConfig) -> io::Result<()> {
             }
         }
 
-        if state.should_sample(action) {
-            state.record_sample(monitor.update());
+        if state.should_sample(action) && state.record_sample(monitor.update()) {
+            sample_log.save(&monitor, &mut state);
+            monitor.flash = alerts.check(&monitor, &mut state, &term);
         }
 
         term.clear();
@@ -586,7 +624,7 @@ pub fn run(config: MonitorConfig) -> io::Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::{GpuBackend, GpuMonitor, BACKEND_RETRY_INTERVAL};
+    use super::{Flash, GpuBackend, GpuMonitor, BACKEND_RETRY_INTERVAL};
 
     #[test]
     fn missing_gpu_backend_is_a_collection_error() {
@@ -598,6 +636,7 @@ mod tests {
             amd_name: None,
             error_msg: Some("No GPU detected".to_string()),
             next_backend_probe: std::time::Instant::now() + BACKEND_RETRY_INTERVAL,
+            flash: Flash::default(),
         };
 
         let error = monitor.update().expect_err("missing backend must fail");
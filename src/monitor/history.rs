@@ -0,0 +1,184 @@
+//! Metric history for the time-series monitor views
+//!
+//! Monitors overwrite their readings on every sample, so each one also pushes
+//! the metrics it graphs into a `HistoryStore`: a fixed window of samples per
+//! named series, oldest dropped first.
+
+use crate::help::HelpEntry;
+use std::collections::VecDeque;
+
+/// Samples kept per series unless `--history` says otherwise
+pub const DEFAULT_WINDOW: usize = 300;
+
+/// Help entry for the view toggle shared by the graphing monitors
+pub const VIEW_TOGGLE_HELP: HelpEntry = HelpEntry::new("g", "Meters/history view");
+
+/// Series returned for names that have no samples yet
+static EMPTY: History = History {
+    samples: VecDeque::new(),
+    capacity: 0,
+};
+
+/// Fixed-capacity ring buffer of samples, oldest first
+#[derive(Clone, Debug)]
+pub struct History {
+    samples: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Append a sample, dropping the oldest once the window is full
+    pub fn push(&mut self, value: f32) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(value);
+    }
+
+    /// Change the window, dropping the oldest samples if it shrinks
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.samples.len() > capacity {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Samples from oldest to newest
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = f32> + '_ {
+        self.samples.iter().copied()
+    }
+
+    /// Largest sample in the window, 0 when empty
+    pub fn max(&self) -> f32 {
+        self.iter().fold(0.0, f32::max)
+    }
+}
+
+/// Named histories sharing one window length
+#[derive(Clone, Debug)]
+pub struct HistoryStore {
+    window: usize,
+    series: Vec<(&'static str, History)>,
+}
+
+impl Default for HistoryStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_WINDOW)
+    }
+}
+
+impl HistoryStore {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            series: Vec::new(),
+        }
+    }
+
+    /// Resize every series to `window` samples
+    pub fn set_window(&mut self, window: usize) {
+        self.window = window;
+        for (_, history) in &mut self.series {
+            history.set_capacity(window);
+        }
+    }
+
+    /// Append a sample to the series `name`, creating it on first use
+    pub fn record(&mut self, name: &'static str, value: f32) {
+        if let Some((_, history)) = self.series.iter_mut().find(|(n, _)| *n == name) {
+            history.push(value);
+        } else {
+            let mut history = History::new(self.window);
+            history.push(value);
+            self.series.push((name, history));
+        }
+    }
+
+    /// The series `name`, empty if nothing was recorded under it
+    pub fn get(&self, name: &str) -> &History {
+        self.series
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map_or(&EMPTY, |(_, history)| history)
+    }
+}
+
+/// Whether a monitor shows its current readings or their history
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MonitorView {
+    #[default]
+    Meters,
+    History,
+}
+
+impl MonitorView {
+    pub fn toggle(self) -> Self {
+        match self {
+            MonitorView::Meters => MonitorView::History,
+            MonitorView::History => MonitorView::Meters,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MonitorView::Meters => "meters",
+            MonitorView::History => "history",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{History, HistoryStore};
+
+    #[test]
+    fn history_keeps_the_newest_samples_within_its_window() {
+        let mut history = History::new(3);
+        assert!(history.is_empty());
+        for value in [1.0, 5.0, 2.0, 3.0] {
+            history.push(value);
+        }
+        assert_eq!(history.iter().collect::<Vec<_>>(), [5.0, 2.0, 3.0]);
+        assert_eq!(history.max(), 5.0);
+
+        history.set_capacity(2);
+        assert_eq!(history.iter().collect::<Vec<_>>(), [2.0, 3.0]);
+        history.set_capacity(0);
+        history.push(4.0);
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn store_creates_series_on_first_record_and_shares_the_window() {
+        let mut store = HistoryStore::new(2);
+        assert_eq!(store.get("rx").len(), 0);
+        store.record("rx", 1.0);
+        store.record("rx", 2.0);
+        store.record("tx", 7.0);
+        store.record("rx", 3.0);
+        assert_eq!(store.get("rx").iter().collect::<Vec<_>>(), [2.0, 3.0]);
+        assert_eq!(store.get("tx").iter().collect::<Vec<_>>(), [7.0]);
+
+        store.set_window(1);
+        assert_eq!(store.get("rx").iter().collect::<Vec<_>>(), [3.0]);
+    }
+}
@@ -1,5 +1,8 @@
-use crate::colors::{scheme_color, ColorState};
+use crate::canvas::{Canvas, CanvasMode};
+use crate::colors::{gradient_color, scheme_color, scheme_ramp, ColorState};
+use crate::monitor::history::History;
 use crate::terminal::Terminal;
+use crate::unicode;
 use crossterm::style::Color;
 
 /// A bounding box for layout calculations
@@ -26,6 +29,30 @@ impl Rect {
     }
 }
 
+/// Draw a rounded border around `bx` with `title` inset in the top edge
+pub fn draw_panel_box(term: &mut Terminal, bx: &Rect, title: &str, color: Color, bold: bool) {
+    if bx.width < 2 || bx.height < 2 {
+        return;
+    }
+    let right = bx.x + bx.width as i32 - 1;
+    let bottom = bx.y + bx.height as i32 - 1;
+    for x in bx.x + 1..right {
+        term.set(x, bx.y, '─', Some(color), bold);
+        term.set(x, bottom, '─', Some(color), bold);
+    }
+    for y in bx.y + 1..bottom {
+        term.set(bx.x, y, '│', Some(color), bold);
+        term.set(right, y, '│', Some(color), bold);
+    }
+    term.set(bx.x, bx.y, '╭', Some(color), bold);
+    term.set(right, bx.y, '╮', Some(color), bold);
+    term.set(bx.x, bottom, '╰', Some(color), bold);
+    term.set(right, bottom, '╯', Some(color), bold);
+
+    let title = unicode::truncate_to_width(title, bx.width.saturating_sub(4) as usize);
+    term.set_str(bx.x + 2, bx.y, title, Some(color), bold);
+}
+
 /// Draw a btop-style meter with color scheme support
 pub fn draw_meter_btop_scheme(
     term: &mut Terminal,
@@ -156,6 +183,146 @@ pub fn draw_core_graphs_scheme(
     }
 }
 
+/// How a history graph is drawn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphStyle {
+    /// Consecutive samples joined by a line
+    Line,
+    /// The area under the samples filled
+    Area,
+}
+
+/// Draw `history` as a braille graph in a `width` x `height` cell box, one
+/// sample per dot column with the newest at the right edge. `max` reaches the
+/// top, and dots take the value gradient color of their height.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_history_graph(
+    term: &mut Terminal,
+    x: i32,
+    y: i32,
+    width: usize,
+    height: usize,
+    history: &History,
+    max: f32,
+    style: GraphStyle,
+    colors: &ColorState,
+) {
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    let mut canvas = Canvas::new(CanvasMode::Braille, width as u16, height as u16);
+    let (px_w, px_h) = canvas.size();
+    let bottom = px_h - 1;
+    let max = max.max(f32::EPSILON);
+    // Pixel values encode height (1 = bottom, 255 = top) for the flush colors
+    let level = |py: usize| 1 + ((bottom - py) * 254 / bottom.max(1)) as u8;
+    let top =
+        |value: f32| bottom - ((value / max).clamp(0.0, 1.0) * bottom as f32).round() as usize;
+
+    let mut previous: Option<(i32, usize)> = None;
+    for (i, value) in history.iter().rev().take(px_w).enumerate() {
+        let px = (px_w - 1 - i) as i32;
+        let py = top(value);
+        match style {
+            GraphStyle::Area => {
+                for row in py..px_h {
+                    canvas.set(px, row as i32, level(row));
+                }
+            }
+            GraphStyle::Line => match previous {
+                Some((prev_x, prev_y)) => {
+                    let value = level(py.min(prev_y));
+                    canvas.line(prev_x, prev_y as i32, px, py as i32, value);
+                }
+                None => canvas.set(px, py as i32, level(py)),
+            },
+        }
+        previous = Some((px, py));
+    }
+
+    canvas.flush_at(term, x, y, |value| {
+        let percent = (value - 1) as f32 / 254.0 * 100.0;
+        (cpu_gradient_color_scheme(percent, colors), false)
+    });
+}
+
+/// One labelled series in a history view
+pub struct HistoryRow<'a> {
+    pub label: &'a str,
+    /// Current reading, shown right-aligned on the label line
+    pub value: String,
+    pub history: &'a History,
+    /// Value that reaches the top of the graph
+    pub max: f32,
+}
+
+/// Draw a title line, then a label line and graph per row, sharing the
+/// remaining height evenly between the rows
+#[allow(clippy::too_many_arguments)]
+pub fn draw_history_view(
+    term: &mut Terminal,
+    x: i32,
+    y: i32,
+    width: usize,
+    height: usize,
+    title: &str,
+    rows: &[HistoryRow],
+    style: GraphStyle,
+    colors: &ColorState,
+) {
+    if rows.is_empty() || height < 3 {
+        return;
+    }
+
+    term.set_str(x, y, title, Some(text_color_scheme(colors)), true);
+    let samples = rows.iter().map(|row| row.history.len()).max().unwrap_or(0);
+    let window = if samples == 1 {
+        "1 sample".to_string()
+    } else {
+        format!("{samples} samples")
+    };
+    if width > window.len() + title.len() {
+        term.set_str(
+            x + (width - window.len()) as i32,
+            y,
+            &window,
+            Some(muted_color_scheme(colors)),
+            false,
+        );
+    }
+
+    let per_row = (height - 1) / rows.len();
+    if per_row < 2 {
+        return;
+    }
+    for (i, row) in rows.iter().enumerate() {
+        let cy = y + 1 + (i * per_row) as i32;
+        term.set_str(x, cy, row.label, Some(muted_color_scheme(colors)), false);
+        let latest = row.history.iter().next_back().unwrap_or(0.0);
+        let percent = (latest / row.max.max(f32::EPSILON) * 100.0).clamp(0.0, 100.0);
+        let value_w = unicode::str_width(&row.value);
+        term.set_str(
+            x + width.saturating_sub(value_w) as i32,
+            cy,
+            &row.value,
+            Some(cpu_gradient_color_scheme(percent, colors)),
+            false,
+        );
+        draw_history_graph(
+            term,
+            x,
+            cy + 1,
+            width,
+            per_row - 1,
+            row.history,
+            row.max,
+            style,
+            colors,
+        );
+    }
+}
+
 /// Format bytes with adaptive precision
 pub fn format_bytes(bytes: u64) -> String {
     const KB: u64 = 1024;
@@ -217,45 +384,43 @@ pub fn update_activity_scale(previous: f64, current: f64, elapsed_secs: f32, flo
     current.max((previous * decay).max(floor))
 }
 
-/// CPU mini graph gradient (btop TTY: bright green -> bright red based on VALUE)
-/// Maps percentage 0-100 to ANSI bright green (10) → bright yellow (11) → bright red (9)
+/// CPU gradient stops (btop style: green -> yellow -> red based on VALUE)
+const CPU_GRADIENT: [(u8, u8, u8); 3] = [(80, 220, 80), (240, 220, 60), (250, 60, 60)];
+
+/// Temperature gradient stops (btop style: blue -> cyan -> magenta based on VALUE)
+const TEMP_GRADIENT: [(u8, u8, u8); 3] = [(90, 120, 255), (60, 220, 230), (240, 90, 240)];
+
+/// Lowest scheme intensity used for value gradients; the bottom of the ramp is
+/// reserved for muted chrome so low values stay distinguishable from empty bars.
+const SCHEME_GRADIENT_FLOOR: f32 = 1.0 / 3.0;
+
+/// CPU mini graph gradient, sampled continuously across percentage 0-100
 pub fn cpu_gradient_color(percent: f32) -> Color {
-    if percent >= 80.0 {
-        Color::AnsiValue(9) // Bright red (ANSI 91)
-    } else if percent >= 50.0 {
-        Color::AnsiValue(11) // Bright yellow (ANSI 93)
-    } else {
-        Color::AnsiValue(10) // Bright green (ANSI 92)
-    }
+    gradient_color(&CPU_GRADIENT, percent / 100.0)
 }
 
-/// Temperature mini graph gradient (btop TTY: bright blue -> bright magenta based on VALUE)
-/// Maps temperature percentage to ANSI bright blue (12) → bright magenta (13)
+/// Temperature mini graph gradient, sampled continuously across percentage 0-100
 pub fn temp_gradient_color(percent: f32) -> Color {
-    if percent >= 70.0 {
-        Color::AnsiValue(13) // Bright magenta (ANSI 95)
-    } else if percent >= 40.0 {
-        Color::AnsiValue(14) // Bright cyan (ANSI 96) - mid point
-    } else {
-        Color::AnsiValue(12) // Bright blue (ANSI 94)
-    }
+    gradient_color(&TEMP_GRADIENT, percent / 100.0)
 }
 
 // ============ Scheme-aware color functions ============
 
+/// Map a 0-100 value onto the scheme ramp above the muted floor
+fn scheme_gradient(percent: f32, colors: &ColorState) -> Color {
+    let t = (percent / 100.0).clamp(0.0, 1.0);
+    scheme_ramp(
+        colors.scheme,
+        SCHEME_GRADIENT_FLOOR + t * (1.0 - SCHEME_GRADIENT_FLOOR),
+    )
+}
+
 /// Get CPU gradient color with scheme support
 pub fn cpu_gradient_color_scheme(percent: f32, colors: &ColorState) -> Color {
     if colors.is_mono() {
         cpu_gradient_color(percent)
     } else {
-        let intensity = if percent >= 80.0 {
-            3
-        } else if percent >= 50.0 {
-            2
-        } else {
-            1
-        };
-        scheme_color(colors.scheme, intensity, percent >= 80.0).0
+        scheme_gradient(percent, colors)
     }
 }
 
@@ -264,14 +429,7 @@ pub fn temp_gradient_color_scheme(percent: f32, colors: &ColorState) -> Color {
     if colors.is_mono() {
         temp_gradient_color(percent)
     } else {
-        let intensity = if percent >= 70.0 {
-            3
-        } else if percent >= 40.0 {
-            2
-        } else {
-            1
-        };
-        scheme_color(colors.scheme, intensity, percent >= 70.0).0
+        scheme_gradient(percent, colors)
     }
 }
 
@@ -304,7 +462,48 @@ pub fn header_color_scheme(colors: &ColorState) -> Color {
 
 #[cfg(test)]
 mod tests {
-    use super::{activity_percent, update_activity_scale};
+    use super::{
+        activity_percent, cpu_gradient_color_scheme, draw_history_graph, update_activity_scale,
+        GraphStyle,
+    };
+    use crate::colors::{scheme_color, ColorState};
+    use crate::monitor::history::History;
+    use crate::terminal::Terminal;
+
+    fn graph(history: &History, style: GraphStyle) -> Vec<String> {
+        let mut term = Terminal::offscreen(4, 2);
+        draw_history_graph(
+            &mut term,
+            1,
+            0,
+            3,
+            2,
+            history,
+            100.0,
+            style,
+            &ColorState::new(7),
+        );
+        term.back_buffer()
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.ch).collect())
+            .collect()
+    }
+
+    #[test]
+    fn history_graphs_put_the_newest_sample_on_the_right() {
+        let mut history = History::new(10);
+        for value in [100.0, 0.0, 50.0] {
+            history.push(value);
+        }
+        // 3 cells hold 6 dot columns; only the last three have samples
+        assert_eq!(graph(&history, GraphStyle::Area), ["  ⢸⢀", "  ⢸⣸"]);
+
+        history.push(100.0);
+        history.push(100.0);
+        history.push(0.0);
+        assert_eq!(graph(&history, GraphStyle::Area), [" ⡇⣸⡇", " ⣇⣿⣇"]);
+        assert_eq!(graph(&history, GraphStyle::Line), [" ⡇⡜⡇", " ⢸⠁⢸"]);
+    }
 
     #[test]
     fn activity_scale_keeps_low_rates_visible() {
@@ -320,4 +519,21 @@ mod tests {
         assert!((decayed - 4.0 * mib).abs() < 1.0);
         assert_eq!(update_activity_scale(mib, 0.0, 300.0, mib), mib);
     }
+
+    #[test]
+    fn scheme_gradients_vary_continuously_above_the_muted_floor() {
+        let colors = ColorState::new(1);
+        assert_eq!(
+            cpu_gradient_color_scheme(0.0, &colors),
+            scheme_color(1, 1, false).0
+        );
+        assert_eq!(
+            cpu_gradient_color_scheme(100.0, &colors),
+            scheme_color(1, 3, false).0
+        );
+        assert_ne!(
+            cpu_gradient_color_scheme(55.0, &colors),
+            cpu_gradient_color_scheme(65.0, &colors)
+        );
+    }
 }
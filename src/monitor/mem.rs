@@ -1,16 +1,20 @@
 use crate::colors::ColorState;
 use crate::help::HelpSpec;
+use crate::monitor::alert::Flash;
+use crate::monitor::history::{HistoryStore, MonitorView, VIEW_TOGGLE_HELP};
 use crate::monitor::layout::{
-    cpu_gradient_color_scheme, draw_meter_btop_scheme, format_bytes, muted_color_scheme,
-    text_color_scheme, Rect,
+    cpu_gradient_color_scheme, draw_history_view, draw_meter_btop_scheme, format_bytes,
+    muted_color_scheme, text_color_scheme, GraphStyle, HistoryRow, Rect,
 };
+use crate::monitor::output::{Collector, Snapshot};
 use crate::monitor::{MonitorAction, MonitorConfig, MonitorState};
 use crate::terminal::Terminal;
 use crossterm::style::Color;
-use crossterm::terminal::size;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io;
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct MemInfo {
     pub mem_total: u64,
     pub mem_available: u64,
@@ -57,6 +61,10 @@ impl MemInfo {
 
 pub struct MemMonitor {
     pub info: MemInfo,
+    pub history: HistoryStore,
+    pub view: MonitorView,
+    /// Rows flashing for firing alerts
+    pub flash: Flash,
 }
 
 impl MemMonitor {
@@ -71,6 +79,9 @@ impl MemMonitor {
                 swap_total: 0,
                 swap_free: 0,
             },
+            history: HistoryStore::default(),
+            view: MonitorView::default(),
+            flash: Flash::default(),
         }
     }
 
@@ -103,14 +114,25 @@ impl MemMonitor {
             }
         }
 
+        self.record_history();
         Ok(())
     }
 
+    /// Show a recorded sample in place of live readings
+    pub fn restore(&mut self, info: &MemInfo) {
+        self.info = info.clone();
+        self.record_history();
+    }
+
+    fn record_history(&mut self) {
+        self.history.record("used", self.info.mem_percent());
+        self.history.record("swap", self.info.swap_percent());
+    }
+
     pub fn render_fullscreen(&self, term: &mut Terminal, w: usize, h: usize, colors: &ColorState) {
         self.render_at(term, 0, 0, w, h, colors);
     }
 
-    #[allow(dead_code)]
     pub fn render(&self, term: &mut Terminal, bx: &Rect, colors: &ColorState) {
         let x = bx.inner_x();
         let y = bx.inner_y();
@@ -128,6 +150,34 @@ impl MemMonitor {
         h: usize,
         colors: &ColorState,
     ) {
+        if self.view == MonitorView::History {
+            let info = &self.info;
+            let mut rows = vec![HistoryRow {
+                label: "Used",
+                value: format!(
+                    "{} {:.0}%",
+                    format_bytes(info.mem_used()),
+                    info.mem_percent()
+                ),
+                history: self.history.get("used"),
+                max: 100.0,
+            }];
+            if info.swap_total > 0 {
+                rows.push(HistoryRow {
+                    label: "Swap",
+                    value: format!(
+                        "{} {:.0}%",
+                        format_bytes(info.swap_used()),
+                        info.swap_percent()
+                    ),
+                    history: self.history.get("swap"),
+                    max: 100.0,
+                });
+            }
+            draw_history_view(term, x, y, w, h, "Memory", &rows, GraphStyle::Area, colors);
+            return;
+        }
+
         if h < 5 || w < 30 {
             return;
         }
@@ -171,6 +221,7 @@ impl MemMonitor {
             colors,
             true,
         );
+        self.flash.mark(term, panel_x, cy, panel_w, &["percent"]);
         cy += 1;
 
         // Cached
@@ -254,6 +305,7 @@ impl MemMonitor {
                 colors,
                 true,
             );
+            self.flash.mark(term, panel_x, cy, panel_w, &["swap"]);
         }
     }
 
@@ -317,22 +369,37 @@ impl MemMonitor {
     }
 }
 
+impl Collector for MemMonitor {
+    fn sample(&mut self, _elapsed: f32) -> io::Result<()> {
+        self.update()
+    }
+
+    fn snapshot(&self) -> Snapshot<'_> {
+        Snapshot::Mem(&self.info)
+    }
+}
+
 pub fn run(config: MonitorConfig) -> io::Result<()> {
     let mut term = Terminal::new(true)?;
     let mut state = MonitorState::new(config.time_step, 0.5);
     let mut monitor = MemMonitor::new();
-    const HELP: HelpSpec = HelpSpec::monitor("MEMORY MONITOR", &[]);
+    let mut sample_log = config.sample_log;
+    let mut alerts = config.alerts;
+    monitor.history.set_window(config.history);
+    const HELP: HelpSpec = HelpSpec::monitor("MEMORY MONITOR", &[VIEW_TOGGLE_HELP]);
 
     loop {
         let mut action = MonitorAction::None;
         if let Ok(Some((code, mods))) = term.check_key() {
-            action = state.handle_key(code, mods);
-            if action == MonitorAction::Quit {
-                break;
+            if !state.handle_view_key(code, &mut monitor.view) {
+                action = state.handle_key(code, mods);
+                if action == MonitorAction::Quit {
+                    break;
+                }
             }
         }
 
-        if let Ok((new_w, new_h)) = size() {
+        if let Ok((new_w, new_h)) = term.query_size() {
             let (cur_w, cur_h) = term.size();
             if new_w != cur_w || new_h != cur_h {
                 term.resize(new_w, new_h);
@@ -340,8 +407,9 @@ pub fn run(config: MonitorConfig) -> io::Result<()> {
             }
         }
 
-        if state.should_sample(action) {
-            state.record_sample(monitor.update());
+        if state.should_sample(action) && state.record_sample(monitor.update()) {
+            sample_log.save(&monitor, &mut state);
+            monitor.flash = alerts.check(&monitor, &mut state, &term);
         }
 
         term.clear();
@@ -360,7 +428,10 @@ pub fn run(config: MonitorConfig) -> io::Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::MemInfo;
+    use super::{MemInfo, MemMonitor};
+    use crate::colors::ColorState;
+    use crate::monitor::history::MonitorView;
+    use crate::terminal::{assert_snapshot, run_headless, ScriptedInput, Terminal};
 
     #[test]
     fn cache_percentage_matches_displayed_cache_bytes() {
@@ -377,4 +448,37 @@ mod tests {
         assert!((info.cached_percent() - 30.0).abs() < 0.001);
         assert!((info.mem_percent() - 60.0).abs() < 0.001);
     }
+
+    #[test]
+    fn fixed_readings_match_golden_frames() {
+        let gib = 1024 * 1024 * 1024;
+        let info = MemInfo {
+            mem_total: 16 * gib,
+            mem_available: 6 * gib,
+            mem_free: 2 * gib,
+            buffers: gib / 2,
+            cached: 4 * gib,
+            swap_total: 8 * gib,
+            swap_free: 7 * gib,
+        };
+        let render = |view: MonitorView| {
+            let (result, frame) = run_headless(60, 12, [ScriptedInput::Idle(0)], || {
+                let mut term = Terminal::new(true)?;
+                let mut monitor = MemMonitor::new();
+                monitor.view = view;
+                for available in [10, 8, 6] {
+                    monitor.restore(&MemInfo {
+                        mem_available: available * gib,
+                        ..info.clone()
+                    });
+                }
+                monitor.render_fullscreen(&mut term, 60, 12, &ColorState::new(0));
+                term.present()
+            });
+            result.expect("headless memory frame");
+            frame.text()
+        };
+        assert_snapshot("mem_meters", &render(MonitorView::Meters));
+        assert_snapshot("mem_history", &render(MonitorView::History));
+    }
 }
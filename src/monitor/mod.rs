@@ -1,16 +1,33 @@
+pub mod alert;
+pub mod cgroups;
 pub mod cpu;
+pub mod dash;
+pub mod dialog;
 pub mod disk;
 pub mod diskio;
 pub mod docker;
+pub mod docker_action;
+pub mod docker_api;
+pub mod docker_logs;
 pub mod gpu;
+pub mod history;
 pub mod layout;
 pub mod mem;
 pub mod net;
+pub mod output;
+pub mod prometheus;
 pub mod ps;
+pub mod ps_action;
+pub mod ps_column;
+pub mod ps_inspect;
+pub mod replay;
+pub mod sensors;
+pub mod tree;
 
 use crate::colors::ColorState;
 use crate::help::{render_help_overlay, HelpSpec};
 use crate::terminal::Terminal;
+use crate::unicode;
 use crossterm::event::{KeyCode, KeyModifiers};
 use std::io::{self, Read};
 use std::process::{Command, Output, Stdio};
@@ -24,7 +41,7 @@ use std::os::unix::process::CommandExt;
 const MAX_COLLECTOR_OUTPUT_BYTES: usize = 1024 * 1024;
 const MAX_STATUS_ERROR_CHARS: usize = 256;
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum MonitorType {
     Cpu,
     Mem,
@@ -34,10 +51,17 @@ pub enum MonitorType {
     Gpu,
 }
 
-#[derive(Clone)]
 pub struct MonitorConfig {
     pub monitor_type: MonitorType,
     pub time_step: f32,
+    /// Samples kept for the history view
+    pub history: usize,
+    /// Write records to stdout instead of running the TUI
+    pub output: Option<output::OutputConfig>,
+    /// Where the TUI records its samples for `termart replay`
+    pub sample_log: replay::SampleLog,
+    /// Threshold rules checked on every sample
+    pub alerts: alert::Alerts,
     #[allow(dead_code)]
     pub debug: bool,
 }
@@ -53,6 +77,8 @@ pub struct MonitorState {
     last_attempt: Option<Instant>,
     sample_error: Option<String>,
     feedback: Option<(String, Instant)>,
+    /// Alert rules currently firing, by metric path
+    alerts: Vec<String>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -76,6 +102,7 @@ impl MonitorState {
             last_attempt: None,
             sample_error: None,
             feedback: None,
+            alerts: Vec::new(),
         }
     }
 
@@ -184,6 +211,20 @@ impl MonitorState {
         self.feedback = Some((message.into(), Instant::now()));
     }
 
+    pub fn set_alerts(&mut self, alerts: Vec<String>) {
+        self.alerts = alerts;
+    }
+
+    /// Handle the history view toggle (g). Returns true if key was handled.
+    pub fn handle_view_key(&mut self, code: KeyCode, view: &mut history::MonitorView) -> bool {
+        if code != KeyCode::Char('g') {
+            return false;
+        }
+        *view = view.toggle();
+        self.set_feedback(format!("View: {}", view.label()));
+        true
+    }
+
     pub fn render_help(&self, term: &mut Terminal, width: u16, height: u16, spec: &HelpSpec) {
         if !self.show_help || width == 0 || height == 0 {
             return;
@@ -194,7 +235,7 @@ impl MonitorState {
 
     fn help_text(&self, spec: &HelpSpec, width: usize) -> String {
         const RUNTIME_PREFIX: &str = "RUNTIME  ";
-        let runtime_width = width.saturating_sub(4 + RUNTIME_PREFIX.chars().count());
+        let runtime_width = width.saturating_sub(4 + unicode::str_width(RUNTIME_PREFIX));
         let rendered = spec.render();
         let mut lines = rendered.lines();
         let title = lines.next().unwrap_or(spec.title);
@@ -221,6 +262,9 @@ impl MonitorState {
             None => "awaiting sample".to_string(),
         };
         let mut fields = vec![mode.to_string()];
+        if !self.alerts.is_empty() {
+            fields.push(format!("ALERT {}", self.alerts.join(", ")));
+        }
         if let Some(error) = &self.sample_error {
             fields.push(error.clone());
             if self.paused {
@@ -237,7 +281,7 @@ impl MonitorState {
         }
 
         let status = format!(" {}", fields.join(" │ "));
-        status.chars().take(width).collect()
+        unicode::truncate_to_width(&status, width).to_string()
     }
 
     fn interval_feedback(&mut self) {
@@ -400,7 +444,140 @@ fn format_interval(seconds: f32) -> String {
     }
 }
 
+/// One of the system monitors that share `MonitorConfig`, for views that
+/// host any of them
+enum SystemMonitor {
+    Cpu(cpu::CpuMonitor),
+    Mem(mem::MemMonitor),
+    Disk(disk::DiskMonitor),
+    Io(diskio::IoMonitor),
+    Net(net::NetMonitor),
+    Gpu(gpu::GpuMonitor),
+}
+
+impl SystemMonitor {
+    fn new(kind: MonitorType) -> Self {
+        match kind {
+            MonitorType::Cpu => SystemMonitor::Cpu(cpu::CpuMonitor::new()),
+            MonitorType::Mem => SystemMonitor::Mem(mem::MemMonitor::new()),
+            MonitorType::Disk => SystemMonitor::Disk(disk::DiskMonitor::new()),
+            MonitorType::Io => SystemMonitor::Io(diskio::IoMonitor::new()),
+            MonitorType::Net => SystemMonitor::Net(net::NetMonitor::new()),
+            MonitorType::Gpu => SystemMonitor::Gpu(gpu::GpuMonitor::new()),
+        }
+    }
+
+    /// Take a sample; rate monitors need the seconds since their last one
+    fn update(&mut self, elapsed: f32) -> io::Result<()> {
+        match self {
+            SystemMonitor::Cpu(monitor) => monitor.update(),
+            SystemMonitor::Mem(monitor) => monitor.update(),
+            SystemMonitor::Disk(monitor) => monitor.update(),
+            SystemMonitor::Io(monitor) => monitor.update(elapsed),
+            SystemMonitor::Net(monitor) => monitor.update(elapsed),
+            SystemMonitor::Gpu(monitor) => monitor.update(),
+        }
+    }
+
+    fn set_history_window(&mut self, window: usize) {
+        match self {
+            SystemMonitor::Cpu(monitor) => monitor.history.set_window(window),
+            SystemMonitor::Mem(monitor) => monitor.history.set_window(window),
+            SystemMonitor::Io(monitor) => monitor.history.set_window(window),
+            SystemMonitor::Net(monitor) => monitor.history.set_window(window),
+            SystemMonitor::Disk(_) | SystemMonitor::Gpu(_) => {}
+        }
+    }
+
+    /// Current view; `None` for monitors without a history view
+    fn view(&self) -> Option<history::MonitorView> {
+        match self {
+            SystemMonitor::Cpu(monitor) => Some(monitor.view),
+            SystemMonitor::Mem(monitor) => Some(monitor.view),
+            SystemMonitor::Io(monitor) => Some(monitor.view),
+            SystemMonitor::Net(monitor) => Some(monitor.view),
+            SystemMonitor::Disk(_) | SystemMonitor::Gpu(_) => None,
+        }
+    }
+
+    /// Switch between meters and history; `None` for monitors without a
+    /// history view
+    fn toggle_view(&mut self) -> Option<history::MonitorView> {
+        let view = match self {
+            SystemMonitor::Cpu(monitor) => &mut monitor.view,
+            SystemMonitor::Mem(monitor) => &mut monitor.view,
+            SystemMonitor::Io(monitor) => &mut monitor.view,
+            SystemMonitor::Net(monitor) => &mut monitor.view,
+            SystemMonitor::Disk(_) | SystemMonitor::Gpu(_) => return None,
+        };
+        *view = view.toggle();
+        Some(*view)
+    }
+
+    fn set_flash(&mut self, flash: alert::Flash) {
+        match self {
+            SystemMonitor::Cpu(monitor) => monitor.flash = flash,
+            SystemMonitor::Mem(monitor) => monitor.flash = flash,
+            SystemMonitor::Disk(monitor) => monitor.flash = flash,
+            SystemMonitor::Io(monitor) => monitor.flash = flash,
+            SystemMonitor::Net(monitor) => monitor.flash = flash,
+            SystemMonitor::Gpu(monitor) => monitor.flash = flash,
+        }
+    }
+
+    fn render_fullscreen(&self, term: &mut Terminal, w: usize, h: usize, colors: &ColorState) {
+        match self {
+            SystemMonitor::Cpu(monitor) => monitor.render_fullscreen(term, w, h, colors),
+            SystemMonitor::Mem(monitor) => monitor.render_fullscreen(term, w, h, colors),
+            SystemMonitor::Disk(monitor) => monitor.render_fullscreen(term, w, h, colors),
+            SystemMonitor::Io(monitor) => monitor.render_fullscreen(term, w, h, colors),
+            SystemMonitor::Net(monitor) => monitor.render_fullscreen(term, w, h, colors),
+            SystemMonitor::Gpu(monitor) => monitor.render_fullscreen(term, w, h, colors),
+        }
+    }
+
+    fn render(&self, term: &mut Terminal, bx: &layout::Rect, colors: &ColorState) {
+        match self {
+            SystemMonitor::Cpu(monitor) => monitor.render(term, bx, colors),
+            SystemMonitor::Mem(monitor) => monitor.render(term, bx, colors),
+            SystemMonitor::Disk(monitor) => monitor.render(term, bx, colors),
+            SystemMonitor::Io(monitor) => monitor.render(term, bx, colors),
+            SystemMonitor::Net(monitor) => monitor.render(term, bx, colors),
+            SystemMonitor::Gpu(monitor) => monitor.render(term, bx, colors),
+        }
+    }
+}
+
+impl output::Collector for SystemMonitor {
+    fn sample(&mut self, elapsed: f32) -> io::Result<()> {
+        self.update(elapsed)
+    }
+
+    fn snapshot(&self) -> output::Snapshot<'_> {
+        match self {
+            SystemMonitor::Cpu(monitor) => monitor.snapshot(),
+            SystemMonitor::Mem(monitor) => monitor.snapshot(),
+            SystemMonitor::Disk(monitor) => monitor.snapshot(),
+            SystemMonitor::Io(monitor) => monitor.snapshot(),
+            SystemMonitor::Net(monitor) => monitor.snapshot(),
+            SystemMonitor::Gpu(monitor) => monitor.snapshot(),
+        }
+    }
+}
+
 pub fn run(config: MonitorConfig) -> std::io::Result<()> {
+    if let Some(output) = config.output {
+        let interval = config.time_step;
+        return match config.monitor_type {
+            MonitorType::Cpu => output::stream(&mut cpu::CpuMonitor::new(), output, interval),
+            MonitorType::Mem => output::stream(&mut mem::MemMonitor::new(), output, interval),
+            MonitorType::Disk => output::stream(&mut disk::DiskMonitor::new(), output, interval),
+            MonitorType::Io => output::stream(&mut diskio::IoMonitor::new(), output, interval),
+            MonitorType::Net => output::stream(&mut net::NetMonitor::new(), output, interval),
+            MonitorType::Gpu => output::stream(&mut gpu::GpuMonitor::new(), output, interval),
+        };
+    }
+
     match config.monitor_type {
         MonitorType::Cpu => cpu::run(config),
         MonitorType::Mem => mem::run(config),
@@ -415,9 +592,12 @@ pub fn run(config: MonitorConfig) -> std::io::Result<()> {
 mod tests {
     use super::{
         command_output_with_timeout, format_interval, read_bounded, truncate_message,
-        MonitorAction, MonitorState, MAX_COLLECTOR_OUTPUT_BYTES,
+        MonitorAction, MonitorConfig, MonitorState, MonitorType, MAX_COLLECTOR_OUTPUT_BYTES,
     };
     use crate::help::HelpSpec;
+    use crate::monitor::alert::Alerts;
+    use crate::monitor::replay::SampleLog;
+    use crate::terminal::{run_headless, Attrs, ScriptedInput};
     use crossterm::event::{KeyCode, KeyModifiers};
     use std::io::Cursor;
     use std::process::Command;
@@ -576,4 +756,79 @@ mod tests {
         assert_eq!(summary.chars().count(), 256);
         assert!(summary.ends_with('…'));
     }
+
+    #[test]
+    fn memory_monitor_renders_offscreen() {
+        let config = MonitorConfig {
+            monitor_type: MonitorType::Mem,
+            time_step: 1.0,
+            history: super::history::DEFAULT_WINDOW,
+            output: None,
+            sample_log: SampleLog::default(),
+            alerts: Alerts::default(),
+            debug: false,
+        };
+        let (result, frame) = run_headless(80, 24, [ScriptedInput::Idle(2)], || super::run(config));
+        result.expect("headless memory monitor");
+        assert_eq!(frame.presented, 2);
+        let text = frame.text();
+        assert!(text.contains("Memory"), "{text}");
+        assert!(text.contains("Available"), "{text}");
+    }
+
+    #[test]
+    fn g_switches_a_monitor_to_its_history_view() {
+        let config = MonitorConfig {
+            monitor_type: MonitorType::Mem,
+            time_step: 1.0,
+            history: 60,
+            output: None,
+            sample_log: SampleLog::default(),
+            alerts: Alerts::default(),
+            debug: false,
+        };
+        let script = [
+            ScriptedInput::Idle(1),
+            ScriptedInput::Key(KeyCode::Char('g'), KeyModifiers::NONE),
+            ScriptedInput::Idle(1),
+        ];
+        let (result, frame) = run_headless(80, 24, script, || super::run(config));
+        result.expect("headless memory monitor");
+        let text = frame.text();
+        assert!(text.contains("1 sample"), "{text}");
+        assert!(text.contains("Used"), "{text}");
+        assert!(!text.contains("Available"), "{text}");
+    }
+
+    #[test]
+    fn firing_alerts_flash_the_offending_row() {
+        let settings = crate::settings::AlertSettings {
+            rules: vec!["mem.percent >= 0".to_string()],
+            ..Default::default()
+        };
+        let config = MonitorConfig {
+            monitor_type: MonitorType::Mem,
+            time_step: 1.0,
+            history: 60,
+            output: None,
+            sample_log: SampleLog::default(),
+            alerts: Alerts::from_settings(&settings).expect("valid rule"),
+            debug: false,
+        };
+        let (result, frame) = run_headless(80, 24, [ScriptedInput::Idle(1)], || super::run(config));
+        result.expect("headless memory monitor");
+        let lines: Vec<String> = frame.text().lines().map(str::to_string).collect();
+        let used = lines
+            .iter()
+            .position(|line| line.contains("Used"))
+            .expect("used row");
+        let cached = lines
+            .iter()
+            .position(|line| line.contains("Cached"))
+            .expect("cached row");
+        assert!(frame.cells[used]
+            .iter()
+            .all(|cell| cell.attrs.contains(Attrs::REVERSE)));
+        assert!(!frame.cells[cached][0].attrs.contains(Attrs::REVERSE));
+    }
 }
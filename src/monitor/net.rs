@@ -1,33 +1,46 @@
 use crate::colors::ColorState;
 use crate::help::HelpSpec;
+use crate::monitor::alert::Flash;
+use crate::monitor::history::{HistoryStore, MonitorView, VIEW_TOGGLE_HELP};
 use crate::monitor::layout::{
-    activity_percent, cpu_gradient_color_scheme, draw_meter_btop_scheme, format_bytes, format_rate,
-    header_color_scheme, muted_color_scheme, text_color_scheme, update_activity_scale, Rect,
+    activity_percent, cpu_gradient_color_scheme, draw_history_view, draw_meter_btop_scheme,
+    format_bytes, format_rate, header_color_scheme, muted_color_scheme, text_color_scheme,
+    update_activity_scale, GraphStyle, HistoryRow, Rect,
 };
+use crate::monitor::output::{Collector, Snapshot};
 use crate::monitor::{MonitorAction, MonitorConfig, MonitorState};
 use crate::terminal::Terminal;
 use crossterm::style::Color;
-use crossterm::terminal::size;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io;
 
-#[derive(Clone)]
-struct InterfaceStats {
-    name: String,
-    rx_bytes: u64,
-    tx_bytes: u64,
-    rx_rate: f64,
-    tx_rate: f64,
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InterfaceStats {
+    pub name: String,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_rate: f64,
+    pub tx_rate: f64,
+    #[serde(skip)]
     prev_rx_bytes: u64,
+    #[serde(skip)]
     prev_tx_bytes: u64,
 }
 
+/// Smallest full-scale value of the history graphs, so idle noise stays flat
+const HISTORY_SCALE_FLOOR: f32 = 1024.0;
+
 pub struct NetMonitor {
     interfaces: Vec<InterfaceStats>,
     pub total_rx_rate: f64,
     pub total_tx_rate: f64,
     pub peak_rx_rate: f64,
     pub peak_tx_rate: f64,
+    pub history: HistoryStore,
+    pub view: MonitorView,
+    /// Rows flashing for firing alerts
+    pub flash: Flash,
 }
 
 impl NetMonitor {
@@ -38,6 +51,9 @@ impl NetMonitor {
             total_tx_rate: 0.0,
             peak_rx_rate: 1024.0 * 1024.0, // Start with 1MB/s as minimum scale
             peak_tx_rate: 1024.0 * 1024.0,
+            history: HistoryStore::default(),
+            view: MonitorView::default(),
+            flash: Flash::default(),
         }
     }
 
@@ -98,6 +114,16 @@ impl NetMonitor {
             new_interfaces.push(interface);
         }
 
+        self.apply(new_interfaces, interval);
+        Ok(())
+    }
+
+    /// Show recorded interface rates in place of live readings
+    pub fn restore(&mut self, interfaces: &[InterfaceStats], interval: f32) {
+        self.apply(interfaces.to_vec(), interval.max(f32::EPSILON));
+    }
+
+    fn apply(&mut self, new_interfaces: Vec<InterfaceStats>, interval: f32) {
         // Calculate totals
         self.total_rx_rate = 0.0;
         self.total_tx_rate = 0.0;
@@ -114,10 +140,10 @@ impl NetMonitor {
         self.peak_tx_rate =
             update_activity_scale(self.peak_tx_rate, self.total_tx_rate, interval, SCALE_FLOOR);
 
-        Ok(())
+        self.history.record("rx", self.total_rx_rate as f32);
+        self.history.record("tx", self.total_tx_rate as f32);
     }
 
-    #[allow(dead_code)]
     pub fn render(&self, term: &mut Terminal, bx: &Rect, colors: &ColorState) {
         let x = bx.inner_x();
         let y = bx.inner_y();
@@ -139,6 +165,24 @@ impl NetMonitor {
         h: usize,
         colors: &ColorState,
     ) {
+        if self.view == MonitorView::History {
+            let row = |label, name, rate| {
+                let history = self.history.get(name);
+                HistoryRow {
+                    label,
+                    value: format_rate(rate),
+                    history,
+                    max: history.max().max(HISTORY_SCALE_FLOOR),
+                }
+            };
+            let rows = [
+                row("Download", "rx", self.total_rx_rate),
+                row("Upload", "tx", self.total_tx_rate),
+            ];
+            draw_history_view(term, x, y, w, h, "Network", &rows, GraphStyle::Line, colors);
+            return;
+        }
+
         if h < 3 || w < 30 {
             return;
         }
@@ -182,6 +226,10 @@ impl NetMonitor {
             Some(muted_color_scheme(colors)),
             false,
         );
+        if !show_breakdown {
+            let names: Vec<&str> = self.interfaces.iter().map(|i| i.name.as_str()).collect();
+            self.flash.mark(term, start_x, cy, content_w, &names);
+        }
         cy += 1;
 
         // Download rate
@@ -227,6 +275,8 @@ impl NetMonitor {
                     Some(header_color_scheme(colors)),
                     false,
                 );
+                self.flash
+                    .mark(term, start_x, cy, content_w, &[&iface.name]);
                 cy += 1;
 
                 // Download for this interface
@@ -313,11 +363,30 @@ impl NetMonitor {
     }
 }
 
+impl Collector for NetMonitor {
+    fn sample(&mut self, elapsed: f32) -> io::Result<()> {
+        self.update(elapsed)
+    }
+
+    fn snapshot(&self) -> Snapshot<'_> {
+        Snapshot::Net {
+            interfaces: &self.interfaces,
+        }
+    }
+
+    fn needs_baseline(&self) -> bool {
+        true
+    }
+}
+
 pub fn run(config: MonitorConfig) -> io::Result<()> {
     let mut term = Terminal::new(true)?;
     let mut state = MonitorState::new(config.time_step, 1.0);
     let mut monitor = NetMonitor::new();
-    const HELP: HelpSpec = HelpSpec::monitor("NETWORK MONITOR", &[]);
+    let mut sample_log = config.sample_log;
+    let mut alerts = config.alerts;
+    monitor.history.set_window(config.history);
+    const HELP: HelpSpec = HelpSpec::monitor("NETWORK MONITOR", &[VIEW_TOGGLE_HELP]);
 
     state.record_sample(monitor.update(1.0));
     let mut last_sample = std::time::Instant::now();
@@ -326,13 +395,15 @@ pub fn run(config: MonitorConfig) -> io::Result<()> {
     loop {
         let mut action = MonitorAction::None;
         if let Ok(Some((code, mods))) = term.check_key() {
-            action = state.handle_key(code, mods);
-            if action == MonitorAction::Quit {
-                break;
+            if !state.handle_view_key(code, &mut monitor.view) {
+                action = state.handle_key(code, mods);
+                if action == MonitorAction::Quit {
+                    break;
+                }
             }
         }
 
-        if let Ok((new_w, new_h)) = size() {
+        if let Ok((new_w, new_h)) = term.query_size() {
             let (cur_w, cur_h) = term.size();
             if new_w != cur_w || new_h != cur_h {
                 term.resize(new_w, new_h);
@@ -343,6 +414,8 @@ pub fn run(config: MonitorConfig) -> io::Result<()> {
         if state.should_sample(action) {
             let elapsed = last_sample.elapsed().as_secs_f32().max(f32::EPSILON);
             if state.record_sample(monitor.update(elapsed)) {
+                sample_log.save(&monitor, &mut state);
+                monitor.flash = alerts.check(&monitor, &mut state, &term);
                 last_sample = std::time::Instant::now();
             }
         }
@@ -0,0 +1,336 @@
+//! Machine-readable monitor output
+//!
+//! With `--format`, a monitor skips the TUI and writes one record per sample
+//! to stdout: a JSON object per line, or CSV rows under a header. Every
+//! monitor describes its sample as a `Snapshot`; CSV flattens it, with one row
+//! per item for monitors that list things (filesystems, interfaces, processes).
+
+use crate::monitor::cgroups::CgroupInfo;
+use crate::monitor::cpu::CpuSnapshot;
+use crate::monitor::disk::DiskInfo;
+use crate::monitor::diskio::DiskStats;
+use crate::monitor::docker::ContainerInfo;
+use crate::monitor::gpu::GpuInfo;
+use crate::monitor::mem::MemInfo;
+use crate::monitor::net::InterfaceStats;
+use crate::monitor::ps::ProcessInfo;
+use crate::monitor::sensors::Sensor;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// One JSON object per line
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct OutputConfig {
+    pub format: OutputFormat,
+    /// Records to write before exiting; unlimited when `None`
+    pub records: Option<u64>,
+}
+
+/// One sample from any monitor, tagged with the monitor's name
+#[derive(Serialize)]
+#[serde(tag = "monitor", rename_all = "lowercase")]
+pub enum Snapshot<'a> {
+    Cpu(CpuSnapshot),
+    Mem(&'a MemInfo),
+    Disk { filesystems: &'a [DiskInfo] },
+    Io { disks: &'a [DiskStats] },
+    Net { interfaces: &'a [InterfaceStats] },
+    Gpu { gpus: &'a [GpuInfo] },
+    Ps { processes: &'a [ProcessInfo] },
+    Docker { containers: &'a [ContainerInfo] },
+    Cgroups { groups: &'a [CgroupInfo] },
+    Sensors { sensors: &'a [Sensor] },
+}
+
+/// A snapshot stamped with the time it was written
+#[derive(Serialize)]
+struct Record<'a> {
+    timestamp: String,
+    #[serde(flatten)]
+    snapshot: Snapshot<'a>,
+}
+
+impl<'a> Record<'a> {
+    fn now(snapshot: Snapshot<'a>) -> Self {
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            snapshot,
+        }
+    }
+}
+
+/// A monitor that can be sampled without its TUI
+pub trait Collector {
+    /// Take a sample; `elapsed` is the seconds since the previous one
+    fn sample(&mut self, elapsed: f32) -> io::Result<()>;
+
+    fn snapshot(&self) -> Snapshot<'_>;
+
+    /// Whether readings are deltas that only make sense after a first sample
+    fn needs_baseline(&self) -> bool {
+        false
+    }
+}
+
+/// Sample `collector` every `interval` seconds and write each record to
+/// stdout until `output.records` records are written or stdout closes.
+/// A failing first sample is returned; later failures are reported on stderr
+/// and retried at the next interval, as in the TUI.
+pub fn stream(
+    collector: &mut impl Collector,
+    output: OutputConfig,
+    interval: f32,
+) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut writer = RecordWriter::new(output.format, stdout.lock());
+    let interval = Duration::from_secs_f32(interval);
+
+    if collector.needs_baseline() {
+        collector.sample(interval.as_secs_f32())?;
+        thread::sleep(interval);
+    }
+
+    let mut last_sample = Instant::now() - interval;
+    let mut written = 0;
+    loop {
+        let elapsed = last_sample.elapsed().as_secs_f32().max(f32::EPSILON);
+        last_sample = Instant::now();
+        match collector.sample(elapsed) {
+            Ok(()) => {
+                match writer.write_snapshot(collector.snapshot()) {
+                    Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+                    result => result?,
+                }
+                written += 1;
+            }
+            Err(e) if written == 0 => return Err(e),
+            Err(e) => eprintln!("Error: {e}"),
+        }
+
+        if output.records.is_some_and(|records| written >= records) {
+            return Ok(());
+        }
+        thread::sleep(interval);
+    }
+}
+
+/// Writes timestamped snapshots in one of the output formats
+pub struct RecordWriter<W: Write> {
+    format: OutputFormat,
+    out: W,
+    /// Last CSV header written, repeated whenever the columns change
+    header: Vec<String>,
+}
+
+impl<W: Write> RecordWriter<W> {
+    pub fn new(format: OutputFormat, out: W) -> Self {
+        Self {
+            format,
+            out,
+            header: Vec::new(),
+        }
+    }
+
+    /// Stamp `snapshot` with the current time and write it
+    pub fn write_snapshot(&mut self, snapshot: Snapshot) -> io::Result<()> {
+        self.write(&Record::now(snapshot))
+    }
+
+    fn write(&mut self, record: &Record) -> io::Result<()> {
+        match self.format {
+            OutputFormat::Json => {
+                serde_json::to_writer(&mut self.out, record)?;
+                writeln!(self.out)?;
+            }
+            OutputFormat::Csv => {
+                // Round-trip through text so f32 fields keep their short form
+                // instead of widening to f64 digits
+                let value: Value = serde_json::from_slice(&serde_json::to_vec(record)?)?;
+                for row in csv_rows(&value) {
+                    let (header, fields): (Vec<String>, Vec<String>) = row.into_iter().unzip();
+                    if header != self.header {
+                        write_csv_line(&mut self.out, &header)?;
+                        self.header = header;
+                    }
+                    write_csv_line(&mut self.out, &fields)?;
+                }
+            }
+        }
+        self.out.flush()
+    }
+}
+
+/// Flatten a record into (column, value) rows. The first array of objects
+/// becomes one row per element, each repeating the record's other columns.
+fn csv_rows(record: &Value) -> Vec<Vec<(String, String)>> {
+    let Value::Object(fields) = record else {
+        return Vec::new();
+    };
+    let mut common = Vec::new();
+    let mut items = None;
+    for (key, value) in fields {
+        match value {
+            Value::Array(array)
+                if items.is_none() && array.first().is_some_and(Value::is_object) =>
+            {
+                items = Some(array);
+            }
+            // A list monitor with nothing in its list writes no rows
+            Value::Array(array) if array.is_empty() && items.is_none() => {
+                return Vec::new();
+            }
+            _ => flatten(key, value, &mut common),
+        }
+    }
+
+    match items {
+        None => vec![common],
+        Some(items) => items
+            .iter()
+            .map(|item| {
+                let mut row = common.clone();
+                flatten("", item, &mut row);
+                row
+            })
+            .collect(),
+    }
+}
+
+/// Nested objects and arrays become `parent_child` and `parent_0` columns
+fn flatten(name: &str, value: &Value, columns: &mut Vec<(String, String)>) {
+    let child = |key: &str| {
+        if name.is_empty() {
+            key.to_string()
+        } else {
+            format!("{name}_{key}")
+        }
+    };
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                flatten(&child(key), value, columns);
+            }
+        }
+        Value::Array(array) => {
+            for (index, value) in array.iter().enumerate() {
+                flatten(&child(&index.to_string()), value, columns);
+            }
+        }
+        Value::Null => columns.push((name.to_string(), String::new())),
+        Value::String(s) => columns.push((name.to_string(), s.clone())),
+        other => columns.push((name.to_string(), other.to_string())),
+    }
+}
+
+fn write_csv_line(out: &mut impl Write, fields: &[String]) -> io::Result<()> {
+    let line: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+    writeln!(out, "{}", line.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OutputFormat, Record, RecordWriter, Snapshot};
+    use crate::monitor::cpu::CpuSnapshot;
+    use crate::monitor::disk::DiskInfo;
+
+    fn written(format: OutputFormat, snapshots: Vec<Snapshot>) -> String {
+        let mut writer = RecordWriter::new(format, Vec::new());
+        for snapshot in snapshots {
+            let record = Record {
+                timestamp: "t".to_string(),
+                snapshot,
+            };
+            writer.write(&record).expect("write to memory");
+        }
+        String::from_utf8(writer.out).expect("utf-8 output")
+    }
+
+    fn cpu() -> CpuSnapshot {
+        CpuSnapshot {
+            model: None,
+            total: 12.5,
+            iowait: 0.0,
+            cores: vec![10.0, 15.0],
+            core_temps: vec![Some(45), None],
+            freq_ghz: None,
+            temperature: Some(48),
+            load: Some([0.5, 0.25, 0.0]),
+            uptime_secs: Some(90.5),
+        }
+    }
+
+    #[test]
+    fn json_writes_one_tagged_object_per_line() {
+        let out = written(OutputFormat::Json, vec![Snapshot::Cpu(cpu())]);
+        assert_eq!(
+            out,
+            "{\"timestamp\":\"t\",\"monitor\":\"cpu\",\"model\":null,\"total\":12.5,\
+             \"iowait\":0.0,\"cores\":[10.0,15.0],\"core_temps\":[45,null],\"freq_ghz\":null,\
+             \"temperature\":48,\"load\":[0.5,0.25,0.0],\"uptime_secs\":90.5}\n"
+        );
+    }
+
+    #[test]
+    fn csv_flattens_arrays_and_writes_a_row_per_listed_item() {
+        let out = written(OutputFormat::Csv, vec![Snapshot::Cpu(cpu())]);
+        assert_eq!(
+            out,
+            "timestamp,monitor,model,total,iowait,cores_0,cores_1,core_temps_0,core_temps_1,\
+             freq_ghz,temperature,load_0,load_1,load_2,uptime_secs\n\
+             t,cpu,,12.5,0.0,10.0,15.0,45,,,48,0.5,0.25,0.0,90.5\n"
+        );
+
+        let disks = [
+            DiskInfo {
+                mount_point: "/".to_string(),
+                total: 100,
+                used: 40,
+                available: 60,
+            },
+            DiskInfo {
+                mount_point: "/mnt/a,b".to_string(),
+                total: 10,
+                used: 1,
+                available: 9,
+            },
+        ];
+        let out = written(
+            OutputFormat::Csv,
+            vec![
+                Snapshot::Disk {
+                    filesystems: &disks,
+                },
+                Snapshot::Disk { filesystems: &[] },
+                Snapshot::Disk {
+                    filesystems: &disks[..1],
+                },
+            ],
+        );
+        assert_eq!(
+            out,
+            "timestamp,monitor,mount_point,total,used,available\n\
+             t,disk,/,100,40,60\n\
+             t,disk,\"/mnt/a,b\",10,1,9\n\
+             t,disk,/,100,40,60\n"
+        );
+    }
+}
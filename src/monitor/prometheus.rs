@@ -0,0 +1,598 @@
+//! Prometheus exporter
+//!
+//! `termart export` samples the monitor collectors on a background thread and
+//! serves the latest readings at `/metrics` in the Prometheus text format from
+//! a minimal std-only HTTP server. Collectors that fail (no GPU, no Docker
+//! daemon) are reported through `termart_collector_up` instead of stopping
+//! the exporter.
+
+use crate::monitor::cpu::CpuMonitor;
+use crate::monitor::disk::DiskMonitor;
+use crate::monitor::diskio::IoMonitor;
+use crate::monitor::docker::DockerMonitor;
+use crate::monitor::gpu::GpuMonitor;
+use crate::monitor::mem::MemMonitor;
+use crate::monitor::net::NetMonitor;
+use crate::monitor::output::{Collector, Snapshot};
+use std::fmt::Write as _;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Content type of the text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+/// Largest request head read before answering
+const MAX_REQUEST_BYTES: usize = 8192;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+/// Delay between the baseline and first published sample of rate collectors
+const BASELINE_DELAY: Duration = Duration::from_millis(250);
+
+pub struct ExportConfig {
+    pub listen: String,
+    pub time_step: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MetricKind {
+    Gauge,
+    Counter,
+}
+
+impl MetricKind {
+    fn name(self) -> &'static str {
+        match self {
+            MetricKind::Gauge => "gauge",
+            MetricKind::Counter => "counter",
+        }
+    }
+}
+
+struct Family {
+    name: &'static str,
+    help: &'static str,
+    kind: MetricKind,
+    samples: Vec<(String, f64)>,
+}
+
+/// Metric families in the order they were first written
+#[derive(Default)]
+struct Metrics {
+    families: Vec<Family>,
+}
+
+impl Metrics {
+    fn add(
+        &mut self,
+        kind: MetricKind,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        let index = match self.families.iter().position(|f| f.name == name) {
+            Some(index) => index,
+            None => {
+                self.families.push(Family {
+                    name,
+                    help,
+                    kind,
+                    samples: Vec::new(),
+                });
+                self.families.len() - 1
+            }
+        };
+        self.families[index]
+            .samples
+            .push((format_labels(labels), value));
+    }
+
+    fn gauge(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        self.add(MetricKind::Gauge, name, help, labels, value);
+    }
+
+    fn counter(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        self.add(MetricKind::Counter, name, help, labels, value);
+    }
+
+    fn add_snapshot(&mut self, snapshot: &Snapshot) {
+        match snapshot {
+            Snapshot::Cpu(cpu) => {
+                self.gauge(
+                    "termart_cpu_usage_percent",
+                    "CPU busy time.",
+                    &[],
+                    widen(cpu.total),
+                );
+                self.gauge(
+                    "termart_cpu_iowait_percent",
+                    "CPU time waiting on I/O.",
+                    &[],
+                    widen(cpu.iowait),
+                );
+                for (core, usage) in cpu.cores.iter().enumerate() {
+                    self.gauge(
+                        "termart_cpu_core_usage_percent",
+                        "Busy time per logical core.",
+                        &[("core", &core.to_string())],
+                        widen(*usage),
+                    );
+                }
+                if let Some(ghz) = cpu.freq_ghz {
+                    self.gauge(
+                        "termart_cpu_frequency_hertz",
+                        "Current CPU clock.",
+                        &[],
+                        widen(ghz) * 1e9,
+                    );
+                }
+                if let Some(celsius) = cpu.temperature {
+                    self.gauge(
+                        "termart_cpu_temperature_celsius",
+                        "CPU package temperature.",
+                        &[],
+                        celsius as f64,
+                    );
+                }
+                if let Some(load) = cpu.load {
+                    for (window, value) in ["1m", "5m", "15m"].iter().zip(load) {
+                        self.gauge(
+                            "termart_load_average",
+                            "System load average.",
+                            &[("window", window)],
+                            widen(value),
+                        );
+                    }
+                }
+            }
+            Snapshot::Mem(mem) => {
+                let gauges = [
+                    (
+                        "termart_memory_total_bytes",
+                        "Physical memory.",
+                        mem.mem_total,
+                    ),
+                    (
+                        "termart_memory_used_bytes",
+                        "Memory in use.",
+                        mem.mem_used(),
+                    ),
+                    (
+                        "termart_memory_available_bytes",
+                        "Memory available without swapping.",
+                        mem.mem_available,
+                    ),
+                    ("termart_memory_cached_bytes", "Page cache.", mem.cached),
+                    (
+                        "termart_memory_buffers_bytes",
+                        "Block device buffers.",
+                        mem.buffers,
+                    ),
+                    ("termart_swap_total_bytes", "Swap space.", mem.swap_total),
+                    ("termart_swap_used_bytes", "Swap in use.", mem.swap_used()),
+                ];
+                for (name, help, value) in gauges {
+                    self.gauge(name, help, &[], value as f64);
+                }
+            }
+            Snapshot::Disk { filesystems } => {
+                for fs in filesystems.iter() {
+                    let labels = [("mountpoint", fs.mount_point.as_str())];
+                    self.gauge(
+                        "termart_filesystem_size_bytes",
+                        "Filesystem size.",
+                        &labels,
+                        fs.total as f64,
+                    );
+                    self.gauge(
+                        "termart_filesystem_used_bytes",
+                        "Filesystem space in use.",
+                        &labels,
+                        fs.used as f64,
+                    );
+                    self.gauge(
+                        "termart_filesystem_avail_bytes",
+                        "Filesystem space available to unprivileged users.",
+                        &labels,
+                        fs.available as f64,
+                    );
+                }
+            }
+            Snapshot::Io { disks } => {
+                for disk in disks.iter() {
+                    let labels = [("device", disk.name.as_str())];
+                    self.counter(
+                        "termart_disk_read_bytes_total",
+                        "Bytes read from the device.",
+                        &labels,
+                        disk.read_bytes as f64,
+                    );
+                    self.counter(
+                        "termart_disk_written_bytes_total",
+                        "Bytes written to the device.",
+                        &labels,
+                        disk.write_bytes as f64,
+                    );
+                    self.gauge(
+                        "termart_disk_read_bytes_per_second",
+                        "Read rate over the last sample interval.",
+                        &labels,
+                        disk.read_rate,
+                    );
+                    self.gauge(
+                        "termart_disk_written_bytes_per_second",
+                        "Write rate over the last sample interval.",
+                        &labels,
+                        disk.write_rate,
+                    );
+                }
+            }
+            Snapshot::Net { interfaces } => {
+                for iface in interfaces.iter() {
+                    let labels = [("interface", iface.name.as_str())];
+                    self.counter(
+                        "termart_network_receive_bytes_total",
+                        "Bytes received on the interface.",
+                        &labels,
+                        iface.rx_bytes as f64,
+                    );
+                    self.counter(
+                        "termart_network_transmit_bytes_total",
+                        "Bytes sent on the interface.",
+                        &labels,
+                        iface.tx_bytes as f64,
+                    );
+                    self.gauge(
+                        "termart_network_receive_bytes_per_second",
+                        "Receive rate over the last sample interval.",
+                        &labels,
+                        iface.rx_rate,
+                    );
+                    self.gauge(
+                        "termart_network_transmit_bytes_per_second",
+                        "Transmit rate over the last sample interval.",
+                        &labels,
+                        iface.tx_rate,
+                    );
+                }
+            }
+            Snapshot::Gpu { gpus } => {
+                for (index, gpu) in gpus.iter().enumerate() {
+                    let index = index.to_string();
+                    let labels = [("gpu", index.as_str()), ("name", gpu.name.as_str())];
+                    self.gauge(
+                        "termart_gpu_utilization_percent",
+                        "GPU busy time.",
+                        &labels,
+                        widen(gpu.utilization),
+                    );
+                    self.gauge(
+                        "termart_gpu_memory_used_bytes",
+                        "GPU memory in use.",
+                        &labels,
+                        gpu.memory_used as f64,
+                    );
+                    self.gauge(
+                        "termart_gpu_memory_total_bytes",
+                        "GPU memory.",
+                        &labels,
+                        gpu.memory_total as f64,
+                    );
+                    if let Some(celsius) = gpu.temperature {
+                        self.gauge(
+                            "termart_gpu_temperature_celsius",
+                            "GPU temperature.",
+                            &labels,
+                            celsius as f64,
+                        );
+                    }
+                    if let Some(watts) = gpu.power_draw {
+                        self.gauge(
+                            "termart_gpu_power_watts",
+                            "GPU power draw.",
+                            &labels,
+                            widen(watts),
+                        );
+                    }
+                }
+            }
+            Snapshot::Docker { containers } => {
+                for container in containers.iter().filter(|c| c.has_stats()) {
+                    let labels = [("name", container.name.as_str())];
+                    self.gauge(
+                        "termart_container_cpu_percent",
+                        "Container CPU usage as reported by the Engine stats API.",
+                        &labels,
+                        widen(container.stats.cpu_pct),
+                    );
+                    self.gauge(
+                        "termart_container_memory_percent",
+                        "Container memory usage against its limit.",
+                        &labels,
+                        widen(container.stats.mem_pct),
+                    );
+                }
+            }
+            Snapshot::Ps { .. } | Snapshot::Cgroups { .. } | Snapshot::Sensors { .. } => {}
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::new();
+        for family in &self.families {
+            let _ = writeln!(out, "# HELP {} {}", family.name, family.help);
+            let _ = writeln!(out, "# TYPE {} {}", family.name, family.kind.name());
+            for (labels, value) in &family.samples {
+                let _ = writeln!(out, "{}{} {}", family.name, labels, format_value(*value));
+            }
+        }
+        out
+    }
+}
+
+/// An f32 reading as the decimal it prints as, so 1.98 is not exported as
+/// 1.9801980257034302
+fn widen(value: f32) -> f64 {
+    value.to_string().parse().unwrap_or(value as f64)
+}
+
+fn format_labels(labels: &[(&str, &str)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| {
+            let escaped = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{name}=\"{escaped}\"")
+        })
+        .collect();
+    format!("{{{}}}", pairs.join(","))
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// A collector and the bookkeeping the exporter keeps for it
+struct Source {
+    name: &'static str,
+    collector: Box<dyn Collector + Send>,
+    last_sample: Option<Instant>,
+}
+
+impl Source {
+    fn new(name: &'static str, collector: impl Collector + Send + 'static) -> Self {
+        Self {
+            name,
+            collector: Box::new(collector),
+            last_sample: None,
+        }
+    }
+
+    fn sample(&mut self, interval: f32) -> bool {
+        let elapsed = self
+            .last_sample
+            .map_or(interval, |last| last.elapsed().as_secs_f32())
+            .max(f32::EPSILON);
+        let ok = self.collector.sample(elapsed).is_ok();
+        self.last_sample = Some(Instant::now());
+        ok
+    }
+}
+
+fn sources() -> Vec<Source> {
+    vec![
+        Source::new("cpu", CpuMonitor::new()),
+        Source::new("mem", MemMonitor::new()),
+        Source::new("disk", DiskMonitor::new()),
+        Source::new("io", IoMonitor::new()),
+        Source::new("net", NetMonitor::new()),
+        Source::new("gpu", GpuMonitor::new()),
+        Source::new("docker", DockerMonitor::new()),
+    ]
+}
+
+/// Sample every source and render the exposition text
+fn scrape(sources: &mut [Source], interval: f32) -> String {
+    let mut metrics = Metrics::default();
+    let mut snapshots = Vec::new();
+    for source in sources.iter_mut() {
+        let up = source.sample(interval);
+        metrics.gauge(
+            "termart_collector_up",
+            "Whether the collector's last sample succeeded.",
+            &[("collector", source.name)],
+            if up { 1.0 } else { 0.0 },
+        );
+        if up {
+            snapshots.push(source);
+        }
+    }
+    for source in snapshots {
+        metrics.add_snapshot(&source.collector.snapshot());
+    }
+    metrics.render()
+}
+
+/// Answer one HTTP request with the current metrics
+fn handle_connection(mut stream: TcpStream, metrics: &Mutex<String>) -> io::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_BYTES {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+
+    let head = String::from_utf8_lossy(&request);
+    let mut parts = head.lines().next().unwrap_or("").split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = target.split('?').next().unwrap_or("");
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET" | "HEAD", "/metrics") => {
+            let body = metrics.lock().map(|m| m.clone()).unwrap_or_default();
+            ("200 OK", CONTENT_TYPE, body)
+        }
+        ("GET" | "HEAD", "/") => (
+            "200 OK",
+            "text/plain; charset=utf-8",
+            "termart exporter\nMetrics are served at /metrics\n".to_string(),
+        ),
+        ("GET" | "HEAD", _) => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "Not found\n".to_string(),
+        ),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            "Method not allowed\n".to_string(),
+        ),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    if method != "HEAD" {
+        stream.write_all(body.as_bytes())?;
+    }
+    stream.flush()
+}
+
+pub fn run(config: ExportConfig) -> io::Result<()> {
+    let listener = TcpListener::bind(&config.listen)?;
+    let interval = config.time_step;
+
+    let mut sources = sources();
+    for source in sources.iter_mut() {
+        if source.collector.needs_baseline() {
+            source.sample(interval);
+        }
+    }
+    thread::sleep(BASELINE_DELAY);
+    let metrics = Arc::new(Mutex::new(scrape(&mut sources, interval)));
+
+    let latest = Arc::clone(&metrics);
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_secs_f32(interval));
+        let text = scrape(&mut sources, interval);
+        if let Ok(mut metrics) = latest.lock() {
+            *metrics = text;
+        }
+    });
+
+    eprintln!(
+        "Serving metrics on http://{}/metrics",
+        listener.local_addr()?
+    );
+    // A client that disconnects or stalls only loses its own response
+    for stream in listener.incoming().flatten() {
+        let _ = handle_connection(stream, &metrics);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{handle_connection, Metrics};
+    use crate::monitor::disk::DiskInfo;
+    use crate::monitor::output::Snapshot;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::Mutex;
+    use std::thread;
+
+    #[test]
+    fn families_group_samples_and_escape_labels() {
+        let filesystems = [
+            DiskInfo {
+                mount_point: "/".to_string(),
+                total: 100,
+                used: 40,
+                available: 60,
+            },
+            DiskInfo {
+                mount_point: "/mnt/\"odd\"".to_string(),
+                total: 10,
+                used: 1,
+                available: 9,
+            },
+        ];
+        let mut metrics = Metrics::default();
+        metrics.gauge("termart_collector_up", "Up.", &[("collector", "disk")], 1.0);
+        metrics.add_snapshot(&Snapshot::Disk {
+            filesystems: &filesystems,
+        });
+        let text = metrics.render();
+
+        assert!(text.starts_with(
+            "# HELP termart_collector_up Up.\n\
+             # TYPE termart_collector_up gauge\n\
+             termart_collector_up{collector=\"disk\"} 1\n\
+             # HELP termart_filesystem_size_bytes Filesystem size.\n\
+             # TYPE termart_filesystem_size_bytes gauge\n\
+             termart_filesystem_size_bytes{mountpoint=\"/\"} 100\n\
+             termart_filesystem_size_bytes{mountpoint=\"/mnt/\\\"odd\\\"\"} 10\n"
+        ));
+        assert_eq!(text.matches("# TYPE").count(), 4);
+    }
+
+    fn request(listener: &TcpListener, request: &str) -> String {
+        let metrics = Mutex::new("termart_up 1\n".to_string());
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        client.write_all(request.as_bytes()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        let server = thread::spawn(move || handle_connection(stream, &metrics));
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        server.join().unwrap().expect("response written");
+        response
+    }
+
+    #[test]
+    fn server_answers_metrics_and_rejects_other_requests() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind localhost");
+
+        let response = request(&listener, "GET /metrics HTTP/1.1\r\nHost: x\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+        assert!(response.contains("Content-Length: 13\r\n"));
+        assert!(response.ends_with("\r\n\r\ntermart_up 1\n"));
+
+        let response = request(&listener, "GET /nope HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 404 Not Found"), "{response}");
+        let response = request(&listener, "POST /metrics HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 405"), "{response}");
+    }
+}
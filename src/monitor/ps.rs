@@ -1,72 +1,210 @@
 //! Process list monitor - shows top processes by CPU/memory usage
 
 use crate::colors::ColorState;
-use crate::help::{render_help_overlay, HelpEntry, HelpSpec};
-use crate::monitor::layout::{cpu_gradient_color_scheme, muted_color_scheme, text_color_scheme};
+use crate::help::{HelpEntry, HelpSpec};
+use crate::monitor::dialog::{Dialog, Outcome};
+use crate::monitor::layout::{
+    cpu_gradient_color_scheme, muted_color_scheme, text_color_scheme, Rect,
+};
+use crate::monitor::output::{self, Collector, OutputConfig, Snapshot};
+use crate::monitor::ps_action::{ProcessAction, Signal};
+use crate::monitor::ps_column::{Column, PsColumns};
+use crate::monitor::ps_inspect::Inspector;
+use crate::monitor::tree;
 use crate::monitor::{MonitorAction, MonitorState};
-use crate::terminal::Terminal;
+use crate::terminal::{Attrs, Terminal};
+use crate::unicode;
 use crossterm::event::KeyCode;
-use crossterm::terminal::size;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{self, Read};
+use std::os::unix::fs::MetadataExt;
 
 const MAX_CMDLINE_BYTES: u64 = 4096;
 const MAX_PROCESS_NAME_CHARS: usize = 128;
 
-#[derive(Clone)]
-struct ProcessInfo {
-    pid: u32,
-    name: String,
-    cpu_pct: f32,
-    mem_pct: f32,
+#[derive(Clone, Serialize)]
+pub struct ProcessInfo {
+    pub(super) pid: u32,
+    pub(super) ppid: u32,
+    pub(super) name: String,
+    pub(super) cpu_pct: f32,
+    pub(super) mem_pct: f32,
+    #[serde(skip)]
     cpu_ticks: u64, // Raw ticks for delta calculation
-    is_kernel: bool,
+    pub(super) is_kernel: bool,
+    pub(super) cmdline: String, // Arguments joined by spaces; empty for kernel threads
+    pub(super) user: String,
+    pub(super) state: char,
+    pub(super) threads: u32,
+    pub(super) rss_bytes: u64,
+    pub(super) vsz_bytes: u64,
+    pub(super) start_time: u64,         // Unix time the process started
+    pub(super) cpu_time: f64,           // User + system CPU seconds since start
+    pub(super) read_bytes: Option<u64>, // From /proc/<pid>/io; None when unreadable
+    pub(super) write_bytes: Option<u64>,
 }
 
 pub struct PsMonitor {
     processes: Vec<ProcessInfo>,
     prev_ticks: HashMap<u32, (u64, f64)>, // PID -> (cpu_ticks, uptime)
     mem_total: u64,
-    sort_by_mem: bool,
+    /// Shown columns and the current sort column
+    layout: PsColumns,
+    sort_descending: bool,
     show_kernel: bool,
     clock_ticks: f64,
     page_size: u64,
+    /// Boot time as Unix seconds, for process start times
+    boot_time: u64,
+    users: HashMap<u32, String>,
     selected_pid: Option<u32>,
     detail_open: bool,
+    /// Follows the selected process while the details pane is open
+    inspector: Option<Inspector>,
+    /// Show processes under their parents instead of as a flat list
+    tree_mode: bool,
+    /// Tree nodes whose children are hidden
+    collapsed: HashSet<u32>,
+    /// Case-insensitive text matched against name, command line, user and PID
+    filter: String,
+    /// Keys go to the filter bar
+    filter_editing: bool,
+}
+
+/// One line of the process list
+struct ProcessRow {
+    /// Index into `processes`
+    index: usize,
+    /// Tree branches drawn before the name; empty in the flat list
+    prefix: String,
+    /// The process' own usage, or its subtree's in tree mode
+    cpu_pct: f32,
+    mem_pct: f32,
+    /// `Some(collapsed)` for tree nodes with children
+    expandable: Option<bool>,
 }
 
 impl PsMonitor {
     pub fn new(show_kernel: bool) -> Self {
+        let layout = PsColumns::default();
         Self {
             processes: Vec::new(),
             prev_ticks: HashMap::new(),
             mem_total: get_mem_total().unwrap_or(1),
-            sort_by_mem: false,
+            sort_descending: layout.sort.descending_by_default(),
+            layout,
             show_kernel,
             clock_ticks: sysconf_value(libc::_SC_CLK_TCK).unwrap_or(100) as f64,
             page_size: sysconf_value(libc::_SC_PAGESIZE).unwrap_or(4096),
+            boot_time: get_boot_time().unwrap_or(0),
+            users: read_users(),
             selected_pid: None,
             detail_open: false,
+            inspector: None,
+            tree_mode: false,
+            collapsed: HashSet::new(),
+            filter: String::new(),
+            filter_editing: false,
         }
     }
 
-    pub fn toggle_sort(&mut self) {
-        self.sort_by_mem = !self.sort_by_mem;
-        // Re-sort immediately
-        if self.sort_by_mem {
-            self.processes.sort_by(|a, b| {
-                b.mem_pct
-                    .partial_cmp(&a.mem_pct)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
+    pub fn set_columns(&mut self, layout: PsColumns) {
+        self.sort_descending = layout.sort.descending_by_default();
+        self.layout = layout;
+        self.sort_processes();
+    }
+
+    pub fn toggle_tree(&mut self) {
+        self.tree_mode = !self.tree_mode;
+        self.reconcile_selection();
+    }
+
+    /// Hide or show the selected node's children; false if it has none
+    pub fn set_collapsed(&mut self, collapsed: bool) -> bool {
+        let Some(pid) = self.selected_pid else {
+            return false;
+        };
+        if !self.tree_mode || !self.processes.iter().any(|p| p.ppid == pid && p.pid != pid) {
+            return false;
+        }
+        if collapsed {
+            self.collapsed.insert(pid)
         } else {
-            self.processes.sort_by(|a, b| {
-                b.cpu_pct
-                    .partial_cmp(&a.cpu_pct)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
+            self.collapsed.remove(&pid)
+        }
+    }
+
+    /// Sort by the next shown column, in that column's natural direction
+    pub fn cycle_sort(&mut self) {
+        let next = self
+            .layout
+            .columns
+            .iter()
+            .position(|&column| column == self.layout.sort)
+            .map_or(0, |index| (index + 1) % self.layout.columns.len());
+        self.layout.sort = self.layout.columns[next];
+        self.sort_descending = self.layout.sort.descending_by_default();
+        self.sort_processes();
+    }
+
+    pub fn reverse_sort(&mut self) {
+        self.sort_descending = !self.sort_descending;
+        self.sort_processes();
+    }
+
+    pub fn sort_label(&self) -> String {
+        format!(
+            "{}{}",
+            self.layout.sort.header(false),
+            if self.sort_descending { '↓' } else { '↑' }
+        )
+    }
+
+    /// Sort by the sort column, breaking ties by PID
+    fn sort_processes(&mut self) {
+        let (sort, descending) = (self.layout.sort, self.sort_descending);
+        self.processes
+            .sort_by(|a, b| sort_order(sort, descending, a, b).then(a.pid.cmp(&b.pid)));
+    }
+
+    /// Type into the filter bar; `Enter` keeps the filter and `Esc` clears it
+    pub fn edit_filter(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter => self.filter_editing = false,
+            KeyCode::Esc => {
+                self.filter_editing = false;
+                self.filter.clear();
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Char(c) if !c.is_control() => self.filter.push(c),
+            _ => return,
         }
+        self.reconcile_selection();
+    }
+
+    pub fn start_filter(&mut self) {
+        self.filter_editing = true;
+    }
+
+    pub fn clear_filter(&mut self) {
+        self.filter.clear();
+        self.reconcile_selection();
+    }
+
+    fn matches_filter(&self, process: &ProcessInfo) -> bool {
+        if self.filter.is_empty() {
+            return true;
+        }
+        let needle = self.filter.to_lowercase();
+        [&process.name, &process.cmdline, &process.user]
+            .iter()
+            .any(|text| text.to_lowercase().contains(&needle))
+            || process.pid.to_string().contains(&needle)
     }
 
     pub fn update(&mut self) -> io::Result<()> {
@@ -93,23 +231,12 @@ impl PsMonitor {
             }
         }
 
-        // Sort by CPU% or MEM%
-        if self.sort_by_mem {
-            new_processes.sort_by(|a, b| {
-                b.mem_pct
-                    .partial_cmp(&a.mem_pct)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
-        } else {
-            new_processes.sort_by(|a, b| {
-                b.cpu_pct
-                    .partial_cmp(&a.cpu_pct)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
-        }
-
         self.processes = new_processes;
         self.prev_ticks = new_ticks;
+        self.sort_processes();
+        let pids: HashSet<u32> = self.processes.iter().map(|p| p.pid).collect();
+        self.collapsed.retain(|pid| pids.contains(pid));
+        self.sync_inspector(true);
         self.reconcile_selection();
         Ok(())
     }
@@ -126,65 +253,210 @@ impl PsMonitor {
         if self.selected_process().is_some() {
             self.detail_open = !self.detail_open;
         }
+        self.sync_inspector(false);
     }
 
     pub fn close_details(&mut self) {
         self.detail_open = false;
+        self.sync_inspector(false);
     }
 
-    pub fn selection_label(&self) -> Option<String> {
-        self.selected_process()
-            .map(|process| format!("{} ({})", process.name, process.pid))
+    pub fn scroll_inspector(&mut self, lines: isize) {
+        if let Some(inspector) = &mut self.inspector {
+            inspector.scroll_by(lines);
+        }
     }
 
-    pub fn detail_text(&self) -> Option<String> {
-        let process = self.selected_process()?;
-        Some(format!(
-            "PROCESS DETAILS\n───────────────────────\nPID       {}\nCommand   {}\nCPU       {:.1}%\nMemory    {:.1}%\nType      {}",
-            process.pid,
-            process.name,
-            process.cpu_pct,
-            process.mem_pct,
-            if process.is_kernel {
-                "kernel thread"
-            } else {
-                "user process"
+    /// Point the inspector at the selected process while details are open.
+    /// `sampled` re-reads it and adds a sparkline sample.
+    fn sync_inspector(&mut self, sampled: bool) {
+        let selected = self
+            .selected_pid
+            .filter(|_| self.detail_open)
+            .and_then(|pid| self.processes.iter().find(|process| process.pid == pid));
+        match (selected, &mut self.inspector) {
+            (None, _) => self.inspector = None,
+            (Some(process), Some(inspector)) if inspector.pid() == process.pid => {
+                if sampled {
+                    inspector.refresh(process);
+                }
             }
-        ))
+            (Some(process), _) => self.inspector = Some(Inspector::new(process)),
+        }
+    }
+
+    /// The dialog a process action key opens for the selected process
+    pub fn action_dialog(&self, key: char) -> Option<Dialog<ProcessAction>> {
+        let process = self.selected_process()?;
+        let (pid, name) = (process.pid, command_name(&process.name));
+        let signal = |signal| ProcessAction::signal_dialog(pid, name, signal);
+        Some(match key {
+            'x' => signal(Signal::Term),
+            'X' => signal(Signal::Kill),
+            'z' => signal(Signal::Stop),
+            'c' => signal(Signal::Cont),
+            'n' => ProcessAction::renice_dialog(pid, name),
+            'a' => ProcessAction::affinity_dialog(pid, name),
+            _ => return None,
+        })
+    }
+
+    pub fn selection_label(&self) -> Option<String> {
+        self.selected_process()
+            .map(|process| format!("{} ({})", process.name, process.pid))
     }
 
     fn move_selection(&mut self, direction: i32) {
-        if self.processes.is_empty() {
+        let rows = self.rows();
+        if rows.is_empty() {
             self.selected_pid = None;
             self.detail_open = false;
             return;
         }
 
-        let current = self
-            .selected_pid
-            .and_then(|pid| self.processes.iter().position(|process| process.pid == pid))
-            .unwrap_or(0);
+        let current = self.selected_row(&rows).unwrap_or(0);
         let next = if direction < 0 {
-            current
-                .checked_sub(1)
-                .unwrap_or(self.processes.len().saturating_sub(1))
+            current.checked_sub(1).unwrap_or(rows.len() - 1)
         } else {
-            (current + 1) % self.processes.len()
+            (current + 1) % rows.len()
         };
-        self.selected_pid = Some(self.processes[next].pid);
+        self.selected_pid = Some(self.processes[rows[next].index].pid);
+        self.sync_inspector(false);
     }
 
+    /// Keep the selection on a listed row; a process that exited or is now
+    /// hidden in a collapsed subtree hands it to the first row
     fn reconcile_selection(&mut self) {
-        if self.processes.is_empty() {
+        let rows = self.rows();
+        if rows.is_empty() {
             self.selected_pid = None;
             self.detail_open = false;
-        } else if !self
-            .selected_pid
-            .is_some_and(|pid| self.processes.iter().any(|process| process.pid == pid))
-        {
-            self.selected_pid = Some(self.processes[0].pid);
+        } else if self.selected_row(&rows).is_none() {
+            self.selected_pid = Some(self.processes[rows[0].index].pid);
             self.detail_open = false;
         }
+        self.sync_inspector(false);
+    }
+
+    fn selected_row(&self, rows: &[ProcessRow]) -> Option<usize> {
+        let pid = self.selected_pid?;
+        rows.iter()
+            .position(|row| self.processes[row.index].pid == pid)
+    }
+
+    /// The listed rows in display order
+    fn rows(&self) -> Vec<ProcessRow> {
+        if self.tree_mode {
+            return self.tree_rows();
+        }
+        self.processes
+            .iter()
+            .enumerate()
+            .filter(|(_, process)| self.matches_filter(process))
+            .map(|(index, process)| ProcessRow {
+                index,
+                prefix: String::new(),
+                cpu_pct: process.cpu_pct,
+                mem_pct: process.mem_pct,
+                expandable: None,
+            })
+            .collect()
+    }
+
+    /// Processes under their parents, siblings ordered by subtree usage.
+    /// Processes whose parent isn't listed (PID 1, kthreadd, or children of
+    /// hidden kernel threads) are roots.
+    fn tree_rows(&self) -> Vec<ProcessRow> {
+        let by_pid: HashMap<u32, usize> = self
+            .processes
+            .iter()
+            .enumerate()
+            .map(|(index, process)| (process.pid, index))
+            .collect();
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); self.processes.len()];
+        let mut parents = vec![None; self.processes.len()];
+        let mut roots = Vec::new();
+        for (index, process) in self.processes.iter().enumerate() {
+            match by_pid.get(&process.ppid) {
+                Some(&parent) if process.ppid != process.pid => {
+                    children[parent].push(index);
+                    parents[index] = Some(parent);
+                }
+                _ => roots.push(index),
+            }
+        }
+
+        // A filter keeps matching processes and the ancestors that lead to them
+        let mut shown = vec![self.filter.is_empty(); self.processes.len()];
+        for (index, process) in self.processes.iter().enumerate() {
+            if !shown[index] && self.matches_filter(process) {
+                let mut node = Some(index);
+                while let Some(current) = node.filter(|&current| !shown[current]) {
+                    shown[current] = true;
+                    node = parents[current];
+                }
+            }
+        }
+
+        let mut totals = vec![(0.0, 0.0); self.processes.len()];
+        for &root in &roots {
+            self.subtree_usage(root, &children, &mut totals);
+        }
+        // Siblings sort by subtree usage for CPU and memory, else by their own
+        // values, as processes order in the flat list
+        let order = |a: &usize, b: &usize| {
+            let ((a_cpu, a_mem), (b_cpu, b_mem)) = (totals[*a], totals[*b]);
+            let ordering = match self.layout.sort {
+                Column::Cpu => a_cpu.total_cmp(&b_cpu),
+                Column::Mem => a_mem.total_cmp(&b_mem),
+                _ => return a.cmp(b),
+            };
+            if self.sort_descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+            .then(a.cmp(b))
+        };
+        roots.retain(|&root| shown[root]);
+        roots.sort_by(order);
+        for siblings in &mut children {
+            siblings.retain(|&child| shown[child]);
+            siblings.sort_by(order);
+        }
+
+        let collapsed = |index: usize| self.collapsed.contains(&self.processes[index].pid);
+        tree::rows(&roots, &children, collapsed)
+            .into_iter()
+            .map(|row| {
+                let (cpu_pct, mem_pct) = totals[row.index];
+                ProcessRow {
+                    index: row.index,
+                    prefix: row.prefix,
+                    cpu_pct,
+                    mem_pct,
+                    expandable: row.expandable,
+                }
+            })
+            .collect()
+    }
+
+    /// Fill `totals` with the summed usage of `index` and its descendants
+    fn subtree_usage(
+        &self,
+        index: usize,
+        children: &[Vec<usize>],
+        totals: &mut [(f32, f32)],
+    ) -> (f32, f32) {
+        let process = &self.processes[index];
+        let mut total = (process.cpu_pct, process.mem_pct);
+        for &child in &children[index] {
+            let (cpu, mem) = self.subtree_usage(child, children, totals);
+            total.0 += cpu;
+            total.1 += mem;
+        }
+        totals[index] = total;
+        total
     }
 
     fn selected_process(&self) -> Option<&ProcessInfo> {
@@ -209,8 +481,13 @@ impl PsMonitor {
             return None;
         }
 
+        let state = fields[0].chars().next()?; // Field 3
+        let ppid: u32 = fields[1].parse().ok()?; // Field 4
         let utime: u64 = fields[11].parse().ok()?; // Field 14 in original (0-indexed after comm: 11)
         let stime: u64 = fields[12].parse().ok()?; // Field 15
+        let threads: u32 = fields[17].parse().ok()?; // Field 20
+        let start_ticks: u64 = fields[19].parse().ok()?; // Field 22 (ticks after boot)
+        let vsz_bytes: u64 = fields[20].parse().ok()?; // Field 23 (bytes)
         let rss_pages: u64 = fields[21].parse().ok()?; // Field 24 (RSS in pages)
 
         let cpu_ticks = utime + stime;
@@ -233,7 +510,7 @@ impl PsMonitor {
         let mem_pct = (rss_bytes as f64 / self.mem_total as f64 * 100.0) as f32;
 
         // Get better command name from cmdline if available
-        let cmdline_name = get_cmdline(pid).unwrap_or_default();
+        let (cmdline_name, cmdline) = get_cmdline(pid).unwrap_or_default();
         let is_kernel = cmdline_name.is_empty();
         let display_name = if cmdline_name.is_empty() {
             format!("[{}]", name) // Kernel thread
@@ -241,13 +518,32 @@ impl PsMonitor {
             cmdline_name
         };
 
+        let uid = fs::metadata(format!("/proc/{}", pid)).ok()?.uid();
+        let user = self
+            .users
+            .get(&uid)
+            .cloned()
+            .unwrap_or_else(|| uid.to_string());
+        let (read_bytes, write_bytes) = get_io_bytes(pid).unzip();
+
         Some(ProcessInfo {
             pid,
+            ppid,
             name: display_name,
             cpu_pct,
             mem_pct,
             cpu_ticks,
             is_kernel,
+            cmdline,
+            user,
+            state,
+            threads,
+            rss_bytes,
+            vsz_bytes,
+            start_time: self.boot_time + (start_ticks as f64 / self.clock_ticks) as u64,
+            cpu_time: cpu_ticks as f64 / self.clock_ticks,
+            read_bytes,
+            write_bytes,
         })
     }
 
@@ -263,26 +559,42 @@ impl PsMonitor {
             return;
         }
 
+        // The inspector takes the lower half; the list fills what is left
+        let inspected = self.inspector.as_ref().zip(self.selected_process());
+        let pane_height = match inspected {
+            Some(_) => (h / 2).max(10).min(h.saturating_sub(4)),
+            None => 0,
+        };
+        if let Some((inspector, process)) = inspected {
+            let area = Rect {
+                x: 0,
+                y: (h - pane_height) as i32,
+                width: w as u16,
+                height: pane_height as u16,
+            };
+            inspector.render(term, &area, process, colors);
+        }
+        let h = h - pane_height;
+
         let header_y = 0;
         let mut y = 1;
 
-        // Header: PID, CPU%, MEM%, PROCESS
-        let sort_indicator = if self.sort_by_mem { "MEM%" } else { "CPU%" };
-        let header = format!(" {:>7}  {:>6}  {:>6}  {}", "PID", "CPU%", "MEM%", "PROCESS");
-        let header_truncated: String = header.chars().take(w).collect();
+        // Header from the configured columns. Tree mode shows subtree totals.
+        let header = self.layout.header(self.tree_mode);
         term.set_str(
             0,
             header_y,
-            &header_truncated,
+            unicode::truncate_to_width(&header, w),
             Some(text_color_scheme(colors)),
             true,
         );
 
         // Sort indicator at top right
-        let sort_hint = format!("[m]Sort:{}", sort_indicator);
-        if w > sort_hint.len() + 2 {
+        let sort_hint = format!("[s]Sort:{}", self.sort_label());
+        let hint_width = unicode::str_width(&sort_hint);
+        if w > hint_width + 2 {
             term.set_str(
-                (w - sort_hint.len()) as i32,
+                (w - hint_width) as i32,
                 header_y,
                 &sort_hint,
                 Some(muted_color_scheme(colors)),
@@ -290,46 +602,83 @@ impl PsMonitor {
             );
         }
 
-        // Process rows
-        let available_rows = h.saturating_sub(1);
-        let show_count = self.processes.len().min(max_procs).min(available_rows);
-        let selected_index = self
-            .selected_pid
-            .and_then(|pid| self.processes.iter().position(|process| process.pid == pid))
-            .unwrap_or(0);
-        let start = selected_index.saturating_add(1).saturating_sub(show_count);
-
-        for proc in self.processes.iter().skip(start).take(show_count) {
-            let selected = self.selected_pid == Some(proc.pid);
-            // Format the row: PID, CPU%, MEM%, PROCESS
-            let row = format!(
-                "{}{:>7}  {:>5.1}%  {:>5.1}%  {}",
-                if selected { '>' } else { ' ' },
-                proc.pid,
-                proc.cpu_pct,
-                proc.mem_pct,
-                proc.name
+        // The filter bar takes the bottom line while typing or filtering
+        let filter_bar = self.filter_editing || !self.filter.is_empty();
+        if filter_bar {
+            let matches = self
+                .processes
+                .iter()
+                .filter(|process| self.matches_filter(process))
+                .count();
+            let bar = format!(
+                "/{}{}  {} of {}",
+                self.filter,
+                if self.filter_editing { "_" } else { "" },
+                matches,
+                self.processes.len()
+            );
+            term.set_str(
+                0,
+                h as i32 - 1,
+                unicode::truncate_to_width(&bar, w),
+                Some(text_color_scheme(colors)),
+                self.filter_editing,
             );
+        }
 
-            // Truncate to terminal width
-            let row_truncated: String = row.chars().take(w).collect();
+        // Process rows; the tree is never cut short by the process limit
+        let rows = self.rows();
+        let available_rows = h.saturating_sub(1 + usize::from(filter_bar));
+        let listed = if self.tree_mode {
+            rows.len()
+        } else {
+            rows.len().min(max_procs)
+        };
+        let show_count = listed.min(available_rows);
+        let selected_index = self.selected_row(&rows).unwrap_or(0);
+        let start = selected_index.saturating_add(1).saturating_sub(show_count);
 
-            // Color based on CPU usage
-            let row_color = if selected {
-                text_color_scheme(colors)
-            } else {
-                cpu_gradient_color_scheme(proc.cpu_pct.min(100.0), colors)
+        for row in rows.iter().skip(start).take(show_count) {
+            let proc = &self.processes[row.index];
+            let selected = self.selected_pid == Some(proc.pid);
+            let marker = match row.expandable {
+                Some(true) => "▸ ",
+                Some(false) => "▾ ",
+                None => "",
             };
-            term.set_str(0, y, &row_truncated, Some(row_color), selected);
+            let tree = format!("{}{marker}", row.prefix);
+            let line = self
+                .layout
+                .row(proc, row.cpu_pct, row.mem_pct, &tree, selected);
+
+            // Color based on CPU usage; the selection is a reverse-video bar
+            let row_color = cpu_gradient_color_scheme(row.cpu_pct.min(100.0), colors);
+            let line = unicode::truncate_to_width(&line, w);
+            if selected {
+                let bar = format!("{line}{}", " ".repeat(w - unicode::str_width(line)));
+                term.set_str_with_attrs(0, y, &bar, Some(row_color), None, Attrs::REVERSE);
+            } else {
+                term.set_str(0, y, line, Some(row_color), false);
+            }
 
             y += 1;
-            if y >= h as i32 {
+            if y > available_rows as i32 {
                 break;
             }
         }
     }
 }
 
+/// Order two processes by `column`
+fn sort_order(column: Column, descending: bool, a: &ProcessInfo, b: &ProcessInfo) -> Ordering {
+    let ordering = column.compare(a, b);
+    if descending {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
 fn sysconf_value(name: libc::c_int) -> Option<u64> {
     let value = unsafe { libc::sysconf(name) };
     (value > 0).then_some(value as u64)
@@ -344,7 +693,7 @@ fn get_uptime_secs() -> io::Result<f64> {
         .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Failed to parse uptime"))
 }
 
-fn get_mem_total() -> Option<u64> {
+pub(super) fn get_mem_total() -> Option<u64> {
     let content = fs::read_to_string("/proc/meminfo").ok()?;
     for line in content.lines() {
         if line.starts_with("MemTotal:") {
@@ -358,7 +707,43 @@ fn get_mem_total() -> Option<u64> {
     None
 }
 
-fn get_cmdline(pid: u32) -> Option<String> {
+fn get_boot_time() -> Option<u64> {
+    let content = fs::read_to_string("/proc/stat").ok()?;
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|value| value.trim().parse().ok())
+}
+
+/// Map UIDs to user names from /etc/passwd
+fn read_users() -> HashMap<u32, String> {
+    let content = fs::read_to_string("/etc/passwd").unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse().ok()?;
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
+/// Bytes read from and written to storage; other users' processes are
+/// unreadable without root
+fn get_io_bytes(pid: u32) -> Option<(u64, u64)> {
+    let content = fs::read_to_string(format!("/proc/{}/io", pid)).ok()?;
+    let field = |name: &str| {
+        content
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|value| value.trim().parse().ok())
+    };
+    Some((field("read_bytes:")?, field("write_bytes:")?))
+}
+
+/// The program name for display and the whole command line
+fn get_cmdline(pid: u32) -> Option<(String, String)> {
     let cmdline_path = format!("/proc/{}/cmdline", pid);
     let file = fs::File::open(&cmdline_path).ok()?;
     let mut content = Vec::new();
@@ -378,14 +763,31 @@ fn get_cmdline(pid: u32) -> Option<String> {
 
     // Extract just the program name (last component of path)
     let program = first_arg.rsplit('/').next().unwrap_or(first_arg.as_ref());
-    Some(truncate_process_name(program))
+    let cmdline = String::from_utf8_lossy(&content)
+        .split('\0')
+        .filter(|arg| !arg.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some((truncate_process_name(program), cmdline))
+}
+
+/// The program name from a command line, for dialogs; kernel thread names
+/// (`[kworker/0:1]`) are kept whole
+fn command_name(name: &str) -> &str {
+    if name.starts_with('[') {
+        return name;
+    }
+    let program = name.split_whitespace().next().unwrap_or(name);
+    program.rsplit('/').next().unwrap_or(program)
 }
 
+/// Bound a name to `MAX_PROCESS_NAME_CHARS` display columns
 fn truncate_process_name(name: &str) -> String {
-    if name.chars().count() <= MAX_PROCESS_NAME_CHARS {
+    if unicode::str_width(name) <= MAX_PROCESS_NAME_CHARS {
         name.to_string()
     } else {
-        let mut truncated: String = name.chars().take(MAX_PROCESS_NAME_CHARS - 1).collect();
+        let mut truncated =
+            unicode::truncate_to_width(name, MAX_PROCESS_NAME_CHARS - 1).to_string();
         truncated.push('…');
         truncated
     }
@@ -395,30 +797,91 @@ pub struct PsConfig {
     pub time_step: f32,
     pub max_procs: usize,
     pub show_kernel: bool,
+    pub columns: PsColumns,
+    pub output: Option<OutputConfig>,
+}
+
+/// The first `limit` processes in the current sort order
+struct TopProcesses {
+    monitor: PsMonitor,
+    limit: usize,
+}
+
+impl Collector for TopProcesses {
+    fn sample(&mut self, _elapsed: f32) -> io::Result<()> {
+        self.monitor.update()
+    }
+
+    fn snapshot(&self) -> Snapshot<'_> {
+        let processes = &self.monitor.processes;
+        Snapshot::Ps {
+            processes: &processes[..processes.len().min(self.limit)],
+        }
+    }
+
+    fn needs_baseline(&self) -> bool {
+        true
+    }
 }
 
 pub fn run(config: PsConfig) -> io::Result<()> {
+    let mut monitor = PsMonitor::new(config.show_kernel);
+    monitor.set_columns(config.columns);
+    if let Some(output) = config.output {
+        let mut top = TopProcesses {
+            monitor,
+            limit: config.max_procs,
+        };
+        return output::stream(&mut top, output, config.time_step);
+    }
+
     let mut term = Terminal::new(true)?;
     let mut state = MonitorState::new(config.time_step, 0.5);
-    let mut monitor = PsMonitor::new(config.show_kernel);
     const HELP: HelpSpec = HelpSpec::monitor(
         "PROCESS LIST",
         &[
             HelpEntry::new("↑/↓ or j/k", "Select process"),
-            HelpEntry::new("Enter", "Toggle details"),
-            HelpEntry::new("m/s", "Cycle sort"),
+            HelpEntry::new("Enter", "Toggle inspector"),
+            HelpEntry::new("PgUp/PgDn", "Scroll inspector"),
+            HelpEntry::new("m/s", "Cycle sort column"),
+            HelpEntry::new("S", "Reverse sort"),
+            HelpEntry::new("/", "Filter by name, command, user or PID"),
+            HelpEntry::new("t", "Tree view"),
+            HelpEntry::new("←/→ or h/l", "Collapse/expand tree node"),
+            HelpEntry::new("x/X", "Send SIGTERM/SIGKILL"),
+            HelpEntry::new("z/c", "Send SIGSTOP/SIGCONT"),
+            HelpEntry::new("n", "Change nice value"),
+            HelpEntry::new("a", "Set CPU affinity"),
         ],
     );
 
     state.record_sample(monitor.update());
     std::thread::sleep(std::time::Duration::from_millis(100));
+    let mut dialog: Option<Dialog<ProcessAction>> = None;
 
     loop {
         let mut action = MonitorAction::None;
         if let Ok(Some((code, mods))) = term.check_key() {
-            if monitor.detail_open && code == KeyCode::Esc {
+            if let Some(open) = dialog.take() {
+                match open.handle_key(code) {
+                    Outcome::Open(open) => dialog = Some(open),
+                    Outcome::Closed => {}
+                    Outcome::Confirmed(process_action) => match process_action.apply() {
+                        Ok(message) => {
+                            state.set_feedback(message);
+                            action = MonitorAction::SampleNow;
+                        }
+                        Err(message) => dialog = Some(Dialog::message("ERROR", message)),
+                    },
+                }
+            } else if monitor.filter_editing {
+                monitor.edit_filter(code);
+            } else if monitor.detail_open && code == KeyCode::Esc {
                 monitor.close_details();
-                state.set_feedback("Details closed");
+                state.set_feedback("Inspector closed");
+            } else if !monitor.filter.is_empty() && code == KeyCode::Esc {
+                monitor.clear_filter();
+                state.set_feedback("Filter cleared");
             } else {
                 match code {
                     KeyCode::Up | KeyCode::Char('k') => {
@@ -436,14 +899,38 @@ pub fn run(config: PsConfig) -> io::Result<()> {
                     KeyCode::Enter => {
                         monitor.toggle_details();
                     }
-                    KeyCode::Char('m') | KeyCode::Char('s') => {
-                        monitor.toggle_sort();
-                        state.set_feedback(if monitor.sort_by_mem {
-                            "Sort: memory"
+                    KeyCode::PageUp => monitor.scroll_inspector(-10),
+                    KeyCode::PageDown => monitor.scroll_inspector(10),
+                    KeyCode::Char('t') => {
+                        monitor.toggle_tree();
+                        state.set_feedback(if monitor.tree_mode {
+                            "View: tree"
                         } else {
-                            "Sort: CPU"
+                            "View: list"
                         });
                     }
+                    KeyCode::Left | KeyCode::Char('h') if monitor.tree_mode => {
+                        if monitor.set_collapsed(true) {
+                            state.set_feedback("Collapsed");
+                        }
+                    }
+                    KeyCode::Right | KeyCode::Char('l') if monitor.tree_mode => {
+                        if monitor.set_collapsed(false) {
+                            state.set_feedback("Expanded");
+                        }
+                    }
+                    KeyCode::Char(key @ ('x' | 'X' | 'z' | 'c' | 'n' | 'a')) => {
+                        dialog = monitor.action_dialog(key);
+                    }
+                    KeyCode::Char('m') | KeyCode::Char('s') => {
+                        monitor.cycle_sort();
+                        state.set_feedback(format!("Sort: {}", monitor.sort_label()));
+                    }
+                    KeyCode::Char('S') => {
+                        monitor.reverse_sort();
+                        state.set_feedback(format!("Sort: {}", monitor.sort_label()));
+                    }
+                    KeyCode::Char('/') => monitor.start_filter(),
                     _ => action = state.handle_key(code, mods),
                 }
             }
@@ -452,7 +939,7 @@ pub fn run(config: PsConfig) -> io::Result<()> {
             }
         }
 
-        if let Ok((new_w, new_h)) = size() {
+        if let Ok((new_w, new_h)) = term.query_size() {
             let (cur_w, cur_h) = term.size();
             if new_w != cur_w || new_h != cur_h {
                 term.resize(new_w, new_h);
@@ -475,10 +962,8 @@ pub fn run(config: PsConfig) -> io::Result<()> {
             config.max_procs,
         );
 
-        if monitor.detail_open {
-            if let Some(details) = monitor.detail_text() {
-                render_help_overlay(&mut term, w, h, &details);
-            }
+        if let Some(dialog) = &dialog {
+            dialog.render(&mut term, w, h);
         }
 
         state.render_help(&mut term, w, h, &HELP);
@@ -493,18 +978,52 @@ pub fn run(config: PsConfig) -> io::Result<()> {
 #[cfg(test)]
 mod tests {
     use super::{truncate_process_name, ProcessInfo, PsMonitor, MAX_PROCESS_NAME_CHARS};
+    use crate::monitor::ps_column::{Column, PsColumns};
+    use crossterm::event::KeyCode;
 
     fn process(pid: u32, cpu_pct: f32, mem_pct: f32) -> ProcessInfo {
         ProcessInfo {
             pid,
+            ppid: 0,
             name: format!("process-{pid}"),
             cpu_pct,
             mem_pct,
             cpu_ticks: 0,
             is_kernel: false,
+            cmdline: format!("/usr/bin/process-{pid} --flag"),
+            user: "root".to_string(),
+            state: 'S',
+            threads: 1,
+            rss_bytes: 0,
+            vsz_bytes: 0,
+            start_time: 0,
+            cpu_time: 0.0,
+            read_bytes: None,
+            write_bytes: None,
         }
     }
 
+    fn child(pid: u32, ppid: u32, cpu_pct: f32, mem_pct: f32) -> ProcessInfo {
+        ProcessInfo {
+            ppid,
+            ..process(pid, cpu_pct, mem_pct)
+        }
+    }
+
+    fn tree_lines(monitor: &PsMonitor) -> Vec<String> {
+        monitor
+            .rows()
+            .iter()
+            .map(|row| {
+                let process = &monitor.processes[row.index];
+                format!(
+                    "{}{} {:.0}/{:.0}",
+                    row.prefix, process.pid, row.cpu_pct, row.mem_pct
+                )
+            })
+            .collect()
+    }
+
     #[test]
     fn process_selection_wraps_and_survives_sorting() {
         let mut monitor = PsMonitor::new(false);
@@ -523,7 +1042,7 @@ mod tests {
         monitor.select_next();
         assert_eq!(monitor.selected_pid, Some(20));
 
-        monitor.toggle_sort();
+        monitor.cycle_sort();
         assert_eq!(monitor.selected_pid, Some(20));
         assert_eq!(monitor.processes[0].pid, 20);
     }
@@ -542,7 +1061,8 @@ mod tests {
 
         assert_eq!(monitor.selected_pid, Some(10));
         assert!(!monitor.detail_open);
-        assert!(monitor.detail_text().is_some());
+        assert!(monitor.inspector.is_none());
+        assert!(monitor.selection_label().is_some());
     }
 
     #[test]
@@ -552,5 +1072,174 @@ mod tests {
 
         assert_eq!(truncated.chars().count(), MAX_PROCESS_NAME_CHARS);
         assert!(truncated.ends_with('…'));
+
+        let wide = truncate_process_name(&"漢".repeat(MAX_PROCESS_NAME_CHARS));
+        assert_eq!(crate::unicode::str_width(&wide), MAX_PROCESS_NAME_CHARS - 1);
+    }
+
+    #[test]
+    fn tree_mode_nests_children_and_rolls_up_subtree_usage() {
+        let mut monitor = PsMonitor::new(false);
+        monitor.processes = vec![
+            child(1, 0, 1.0, 1.0),
+            child(10, 1, 5.0, 2.0),
+            child(11, 10, 20.0, 1.0),
+            child(12, 10, 2.0, 4.0),
+            child(20, 1, 30.0, 1.0),
+            // Parent not listed (e.g. a hidden kernel thread): its own root
+            child(30, 2, 3.0, 0.0),
+        ];
+        monitor.toggle_tree();
+
+        assert_eq!(
+            tree_lines(&monitor),
+            vec![
+                "1 58/9",
+                "├─ 20 30/1",
+                "└─ 10 27/7",
+                "   ├─ 11 20/1",
+                "   └─ 12 2/4",
+                "30 3/0",
+            ]
+        );
+
+        monitor.cycle_sort();
+        assert_eq!(tree_lines(&monitor)[1], "├─ 10 27/7");
+    }
+
+    #[test]
+    fn collapsed_tree_nodes_hide_their_children_from_navigation() {
+        let mut monitor = PsMonitor::new(false);
+        monitor.processes = vec![
+            child(1, 0, 1.0, 1.0),
+            child(10, 1, 5.0, 2.0),
+            child(11, 10, 20.0, 1.0),
+            child(20, 1, 3.0, 1.0),
+        ];
+        monitor.toggle_tree();
+        monitor.select_next();
+        monitor.select_next();
+        assert_eq!(monitor.selected_pid, Some(11));
+        assert!(!monitor.set_collapsed(true), "leaves cannot collapse");
+
+        monitor.select_previous();
+        assert!(monitor.set_collapsed(true));
+        assert_eq!(
+            tree_lines(&monitor),
+            vec!["1 29/5", "├─ 10 25/3", "└─ 20 3/1"]
+        );
+        assert_eq!(monitor.rows()[1].expandable, Some(true));
+        monitor.select_next();
+        assert_eq!(monitor.selected_pid, Some(20));
+
+        // A selection inside a collapsed subtree falls back to the first row
+        monitor.selected_pid = Some(11);
+        monitor.reconcile_selection();
+        assert_eq!(monitor.selected_pid, Some(1));
+
+        monitor.selected_pid = Some(10);
+        assert!(monitor.set_collapsed(false));
+        assert_eq!(monitor.rows().len(), 4);
+    }
+
+    #[test]
+    fn sorting_follows_the_chosen_column_and_direction() {
+        let mut monitor = PsMonitor::new(false);
+        monitor.processes = vec![
+            ProcessInfo {
+                user: "bob".to_string(),
+                threads: 8,
+                ..process(10, 1.0, 1.0)
+            },
+            ProcessInfo {
+                user: "alice".to_string(),
+                threads: 2,
+                ..process(20, 5.0, 1.0)
+            },
+            ProcessInfo {
+                user: "alice".to_string(),
+                threads: 4,
+                ..process(30, 3.0, 1.0)
+            },
+        ];
+        monitor.set_columns(PsColumns {
+            columns: vec![Column::Pid, Column::User, Column::Threads, Column::Command],
+            sort: Column::Threads,
+        });
+        let pids =
+            |monitor: &PsMonitor| -> Vec<u32> { monitor.processes.iter().map(|p| p.pid).collect() };
+        assert_eq!(pids(&monitor), vec![10, 30, 20]);
+        assert_eq!(monitor.sort_label(), "THR↓");
+
+        monitor.cycle_sort();
+        assert_eq!(monitor.sort_label(), "PROCESS↑");
+        monitor.cycle_sort();
+        assert_eq!(monitor.sort_label(), "PID↑");
+        monitor.cycle_sort();
+        // Ties keep PID order
+        assert_eq!(pids(&monitor), vec![20, 30, 10]);
+        monitor.reverse_sort();
+        assert_eq!(pids(&monitor), vec![10, 20, 30]);
+        assert_eq!(monitor.sort_label(), "USER↓");
+
+        assert_eq!(
+            monitor.layout.header(false),
+            "     PID  USER       THR  PROCESS"
+        );
+        assert_eq!(
+            monitor
+                .layout
+                .row(&monitor.processes[0], 1.0, 1.0, "", true),
+            ">     10  bob          8  process-10"
+        );
+    }
+
+    #[test]
+    fn filter_matches_name_cmdline_user_and_pid() {
+        let mut monitor = PsMonitor::new(false);
+        monitor.processes = vec![
+            child(1, 0, 1.0, 1.0),
+            ProcessInfo {
+                name: "sshd".to_string(),
+                cmdline: "sshd: alice@pts/0".to_string(),
+                ..child(10, 1, 5.0, 2.0)
+            },
+            ProcessInfo {
+                user: "alice".to_string(),
+                ..child(11, 10, 20.0, 1.0)
+            },
+            child(20, 1, 3.0, 1.0),
+        ];
+        monitor.reconcile_selection();
+        let listed = |monitor: &PsMonitor| -> Vec<u32> {
+            monitor
+                .rows()
+                .iter()
+                .map(|row| monitor.processes[row.index].pid)
+                .collect()
+        };
+
+        monitor.start_filter();
+        for c in "ALICE".chars() {
+            monitor.edit_filter(KeyCode::Char(c));
+        }
+        assert_eq!(listed(&monitor), vec![10, 11]);
+        assert_eq!(monitor.selected_pid, Some(10));
+
+        // The tree keeps the ancestors of each match
+        monitor.toggle_tree();
+        assert_eq!(listed(&monitor), vec![1, 10, 11]);
+
+        monitor.edit_filter(KeyCode::Enter);
+        assert!(!monitor.filter_editing);
+        monitor.filter = "--flag".to_string();
+        monitor.toggle_tree();
+        assert_eq!(listed(&monitor), vec![1, 11, 20]);
+        monitor.filter = "2".to_string();
+        assert_eq!(listed(&monitor), vec![20]);
+
+        monitor.start_filter();
+        monitor.edit_filter(KeyCode::Esc);
+        assert_eq!(listed(&monitor).len(), 4);
     }
 }
@@ -0,0 +1,332 @@
+//! Process actions for the process list: signals, nice and CPU affinity
+
+use crate::monitor::dialog::Dialog;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::mem;
+
+const NICE_MIN: i32 = -20;
+const NICE_MAX: i32 = 19;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Signal {
+    Term,
+    Kill,
+    Stop,
+    Cont,
+}
+
+impl Signal {
+    fn number(self) -> libc::c_int {
+        match self {
+            Signal::Term => libc::SIGTERM,
+            Signal::Kill => libc::SIGKILL,
+            Signal::Stop => libc::SIGSTOP,
+            Signal::Cont => libc::SIGCONT,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Signal::Term => "SIGTERM",
+            Signal::Kill => "SIGKILL",
+            Signal::Stop => "SIGSTOP",
+            Signal::Cont => "SIGCONT",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionKind {
+    Signal(Signal),
+    Renice(i32),
+    /// Allowed CPUs, sorted and deduplicated
+    Affinity(Vec<usize>),
+}
+
+/// An action on one process, shown as the confirmation question
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProcessAction {
+    pub pid: u32,
+    pub name: String,
+    pub kind: ActionKind,
+}
+
+impl fmt::Display for ProcessAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let target = format!("{} ({})", self.name, self.pid);
+        match &self.kind {
+            ActionKind::Signal(signal) => write!(f, "Send {} to {target}?", signal.name()),
+            ActionKind::Renice(nice) => write!(f, "Set nice of {target} to {nice}?"),
+            ActionKind::Affinity(cpus) => {
+                write!(f, "Run {target} on CPUs {}?", format_cpu_list(cpus))
+            }
+        }
+    }
+}
+
+impl ProcessAction {
+    /// Confirm sending `signal`
+    pub fn signal_dialog(pid: u32, name: &str, signal: Signal) -> Dialog<ProcessAction> {
+        Dialog::confirm(
+            "SIGNAL",
+            ProcessAction {
+                pid,
+                name: name.to_string(),
+                kind: ActionKind::Signal(signal),
+            },
+        )
+    }
+
+    /// Ask for a nice value, starting from the current one
+    pub fn renice_dialog(pid: u32, name: &str) -> Dialog<ProcessAction> {
+        let current = read_nice(pid)
+            .map(|nice| nice.to_string())
+            .unwrap_or_default();
+        let name = name.to_string();
+        Dialog::input(
+            "RENICE",
+            format!("Nice value ({NICE_MIN} to {NICE_MAX}) for {name} ({pid}):"),
+            current,
+            move |text| {
+                parse_nice(text).map(|nice| ProcessAction {
+                    pid,
+                    name: name.clone(),
+                    kind: ActionKind::Renice(nice),
+                })
+            },
+        )
+    }
+
+    /// Ask for a CPU list such as `0-3,6`, starting from the current one
+    pub fn affinity_dialog(pid: u32, name: &str) -> Dialog<ProcessAction> {
+        let current = read_affinity(pid)
+            .map(|cpus| format_cpu_list(&cpus))
+            .unwrap_or_default();
+        let name = name.to_string();
+        Dialog::input(
+            "CPU AFFINITY",
+            format!("CPUs for {name} ({pid}), e.g. 0-3,6:"),
+            current,
+            move |text| {
+                parse_cpu_list(text).map(|cpus| ProcessAction {
+                    pid,
+                    name: name.clone(),
+                    kind: ActionKind::Affinity(cpus),
+                })
+            },
+        )
+    }
+
+    /// Run the action, returning feedback on success or a message to show
+    pub fn apply(&self) -> Result<String, String> {
+        let pid = self.pid as libc::pid_t;
+        let result = match &self.kind {
+            ActionKind::Signal(signal) => {
+                // SAFETY: kill only reads its arguments
+                check(unsafe { libc::kill(pid, signal.number()) })
+            }
+            ActionKind::Renice(nice) => {
+                // SAFETY: setpriority only reads its arguments
+                check(unsafe { libc::setpriority(libc::PRIO_PROCESS, self.pid, *nice) })
+            }
+            ActionKind::Affinity(cpus) => {
+                // SAFETY: cpu_set_t is plain data and every CPU index was
+                // checked against CPU_SETSIZE when the list was parsed
+                unsafe {
+                    let mut set: libc::cpu_set_t = mem::zeroed();
+                    for &cpu in cpus {
+                        libc::CPU_SET(cpu, &mut set);
+                    }
+                    check(libc::sched_setaffinity(
+                        pid,
+                        mem::size_of::<libc::cpu_set_t>(),
+                        &set,
+                    ))
+                }
+            }
+        };
+        result
+            .map(|()| self.done())
+            .map_err(|e| self.describe_error(&e))
+    }
+
+    fn target(&self) -> String {
+        format!("{} ({})", self.name, self.pid)
+    }
+
+    fn done(&self) -> String {
+        match &self.kind {
+            ActionKind::Signal(signal) => format!("Sent {} to {}", signal.name(), self.target()),
+            ActionKind::Renice(nice) => format!("Nice {nice}: {}", self.target()),
+            ActionKind::Affinity(cpus) => {
+                format!("CPUs {}: {}", format_cpu_list(cpus), self.target())
+            }
+        }
+    }
+
+    fn describe_error(&self, error: &io::Error) -> String {
+        let what = match &self.kind {
+            ActionKind::Signal(signal) => format!("send {} to {}", signal.name(), self.target()),
+            ActionKind::Renice(nice) => format!("set nice of {} to {nice}", self.target()),
+            ActionKind::Affinity(cpus) => {
+                format!("run {} on CPUs {}", self.target(), format_cpu_list(cpus))
+            }
+        };
+        match error.raw_os_error() {
+            Some(libc::EPERM | libc::EACCES) => {
+                let hint = match &self.kind {
+                    ActionKind::Signal(_) => "Only the process owner or root can signal it.",
+                    ActionKind::Renice(_) => {
+                        "Lowering nice, or renicing another user's process,\nneeds root (CAP_SYS_NICE)."
+                    }
+                    ActionKind::Affinity(_) => {
+                        "Another user's process needs root (CAP_SYS_NICE)."
+                    }
+                };
+                format!("Permission denied: cannot {what}.\n{hint}")
+            }
+            Some(libc::ESRCH) => format!("{} has exited.", self.target()),
+            Some(libc::EINVAL) if matches!(self.kind, ActionKind::Affinity(_)) => {
+                format!("Cannot {what}:\nnone of those CPUs are online.")
+            }
+            _ => format!("Cannot {what}:\n{error}"),
+        }
+    }
+}
+
+fn check(ret: libc::c_int) -> io::Result<()> {
+    if ret == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+fn parse_nice(text: &str) -> Result<i32, String> {
+    match text.parse() {
+        Ok(nice) if (NICE_MIN..=NICE_MAX).contains(&nice) => Ok(nice),
+        _ => Err(format!(
+            "Nice must be a number from {NICE_MIN} to {NICE_MAX}"
+        )),
+    }
+}
+
+/// Parse a kernel-style CPU list (`0-3,6`)
+fn parse_cpu_list(text: &str) -> Result<Vec<usize>, String> {
+    let invalid = || format!("Invalid CPU list '{text}'; use e.g. 0-3,6");
+    let mut cpus = Vec::new();
+    for part in text.split(',').map(str::trim) {
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (first.trim(), last.trim()),
+            None => (part, part),
+        };
+        let first: usize = first.parse().map_err(|_| invalid())?;
+        let last: usize = last.parse().map_err(|_| invalid())?;
+        if first > last {
+            return Err(invalid());
+        }
+        if last >= libc::CPU_SETSIZE as usize {
+            return Err(format!("CPU {last} is out of range"));
+        }
+        cpus.extend(first..=last);
+    }
+    cpus.sort_unstable();
+    cpus.dedup();
+    Ok(cpus)
+}
+
+/// Format sorted CPUs as a kernel-style list, joining runs into ranges
+fn format_cpu_list(cpus: &[usize]) -> String {
+    let mut parts = Vec::new();
+    let mut index = 0;
+    while index < cpus.len() {
+        let start = cpus[index];
+        let mut end = start;
+        while index + 1 < cpus.len() && cpus[index + 1] == end + 1 {
+            index += 1;
+            end += 1;
+        }
+        parts.push(if start == end {
+            start.to_string()
+        } else {
+            format!("{start}-{end}")
+        });
+        index += 1;
+    }
+    parts.join(",")
+}
+
+fn read_nice(pid: u32) -> Option<i32> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    let rest = &stat[stat.rfind(')')? + 2..];
+    // Field 19; fields after the command start at field 3
+    rest.split_whitespace().nth(16)?.parse().ok()
+}
+
+fn read_affinity(pid: u32) -> Option<Vec<usize>> {
+    // SAFETY: cpu_set_t is plain data, filled in by the kernel
+    unsafe {
+        let mut set: libc::cpu_set_t = mem::zeroed();
+        if libc::sched_getaffinity(
+            pid as libc::pid_t,
+            mem::size_of::<libc::cpu_set_t>(),
+            &mut set,
+        ) != 0
+        {
+            return None;
+        }
+        Some(
+            (0..libc::CPU_SETSIZE as usize)
+                .filter(|&cpu| libc::CPU_ISSET(cpu, &set))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        format_cpu_list, parse_cpu_list, parse_nice, read_nice, ActionKind, ProcessAction, Signal,
+    };
+
+    #[test]
+    fn cpu_lists_parse_and_format_as_ranges() {
+        assert_eq!(parse_cpu_list("3, 0-1,1,6-7"), Ok(vec![0, 1, 3, 6, 7]));
+        assert_eq!(format_cpu_list(&[0, 1, 3, 6, 7]), "0-1,3,6-7");
+        assert_eq!(format_cpu_list(&[2]), "2");
+        assert!(parse_cpu_list("3-1").is_err());
+        assert!(parse_cpu_list("a").is_err());
+        assert!(parse_cpu_list("0-99999").is_err());
+
+        assert_eq!(parse_nice("-20"), Ok(-20));
+        assert!(parse_nice("20").is_err());
+    }
+
+    #[test]
+    fn actions_report_results_and_missing_processes() {
+        let own = ProcessAction {
+            pid: std::process::id(),
+            name: "termart".to_string(),
+            kind: ActionKind::Signal(Signal::Cont),
+        };
+        assert_eq!(
+            own.to_string(),
+            format!("Send SIGCONT to termart ({})?", own.pid)
+        );
+        assert_eq!(
+            own.apply(),
+            Ok(format!("Sent SIGCONT to termart ({})", own.pid))
+        );
+        assert!(read_nice(own.pid).is_some());
+
+        // Above the kernel's PID_MAX_LIMIT, so never a live process
+        let gone = ProcessAction {
+            pid: 5_000_000,
+            name: "gone".to_string(),
+            kind: ActionKind::Renice(5),
+        };
+        assert_eq!(gone.apply(), Err("gone (5000000) has exited.".to_string()));
+    }
+}
@@ -0,0 +1,313 @@
+//! Columns of the process list, configured under `[ps]` in config.toml
+
+use crate::monitor::layout::format_bytes;
+use crate::monitor::ps::ProcessInfo;
+use crate::settings::PsSettings;
+use crate::unicode;
+use chrono::{Datelike, Local, TimeZone};
+use std::cmp::Ordering;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    Pid,
+    User,
+    State,
+    Threads,
+    Cpu,
+    Mem,
+    Rss,
+    Vsz,
+    Start,
+    Read,
+    Write,
+    Time,
+    Command,
+}
+
+const COLUMNS: [(&str, Column); 13] = [
+    ("pid", Column::Pid),
+    ("user", Column::User),
+    ("state", Column::State),
+    ("threads", Column::Threads),
+    ("cpu", Column::Cpu),
+    ("mem", Column::Mem),
+    ("rss", Column::Rss),
+    ("vsz", Column::Vsz),
+    ("start", Column::Start),
+    ("read", Column::Read),
+    ("write", Column::Write),
+    ("time", Column::Time),
+    ("command", Column::Command),
+];
+
+impl Column {
+    fn parse(name: &str) -> Result<Self, String> {
+        COLUMNS
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(name.trim()))
+            .map(|&(_, column)| column)
+            .ok_or_else(|| {
+                let names: Vec<&str> = COLUMNS.iter().map(|(name, _)| *name).collect();
+                format!(
+                    "unknown column '{name}' (expected one of {})",
+                    names.join(", ")
+                )
+            })
+    }
+
+    fn name(self) -> &'static str {
+        COLUMNS
+            .iter()
+            .find(|&&(_, column)| column == self)
+            .map_or("", |(name, _)| name)
+    }
+
+    pub fn header(self, tree: bool) -> &'static str {
+        match self {
+            Column::Pid => "PID",
+            Column::User => "USER",
+            Column::State => "S",
+            Column::Threads => "THR",
+            Column::Cpu if tree => "ΣCPU%",
+            Column::Cpu => "CPU%",
+            Column::Mem if tree => "ΣMEM%",
+            Column::Mem => "MEM%",
+            Column::Rss => "RSS",
+            Column::Vsz => "VSZ",
+            Column::Start => "START",
+            Column::Read => "READ",
+            Column::Write => "WRITE",
+            Column::Time => "TIME",
+            Column::Command if tree => "PROCESS TREE",
+            Column::Command => "PROCESS",
+        }
+    }
+
+    /// Display width; the command takes whatever is left
+    fn width(self) -> usize {
+        match self {
+            Column::Pid => 7,
+            Column::User => 8,
+            Column::State => 1,
+            Column::Threads => 4,
+            Column::Cpu | Column::Mem => 6,
+            Column::Rss | Column::Vsz | Column::Read | Column::Write => 9,
+            Column::Start => 5,
+            Column::Time => 9,
+            Column::Command => 0,
+        }
+    }
+
+    fn left_aligned(self) -> bool {
+        matches!(self, Column::User | Column::State | Column::Command)
+    }
+
+    /// Numbers sort biggest first; names and PIDs sort in ascending order
+    pub fn descending_by_default(self) -> bool {
+        !matches!(
+            self,
+            Column::Pid | Column::User | Column::State | Column::Command
+        )
+    }
+
+    /// Ascending order of two processes by this column
+    pub fn compare(self, a: &ProcessInfo, b: &ProcessInfo) -> Ordering {
+        match self {
+            Column::Pid => a.pid.cmp(&b.pid),
+            Column::User => a.user.cmp(&b.user),
+            Column::State => a.state.cmp(&b.state),
+            Column::Threads => a.threads.cmp(&b.threads),
+            Column::Cpu => a.cpu_pct.total_cmp(&b.cpu_pct),
+            Column::Mem => a.mem_pct.total_cmp(&b.mem_pct),
+            Column::Rss => a.rss_bytes.cmp(&b.rss_bytes),
+            Column::Vsz => a.vsz_bytes.cmp(&b.vsz_bytes),
+            Column::Start => a.start_time.cmp(&b.start_time),
+            Column::Read => a.read_bytes.cmp(&b.read_bytes),
+            Column::Write => a.write_bytes.cmp(&b.write_bytes),
+            Column::Time => a.cpu_time.total_cmp(&b.cpu_time),
+            Column::Command => a.name.cmp(&b.name),
+        }
+    }
+
+    /// The cell text, before alignment. CPU and memory come from the row,
+    /// which holds subtree totals in tree mode.
+    fn cell(self, process: &ProcessInfo, cpu_pct: f32, mem_pct: f32) -> String {
+        let bytes = |value: Option<u64>| value.map_or_else(|| "-".to_string(), format_bytes);
+        match self {
+            Column::Pid => process.pid.to_string(),
+            Column::User => unicode::truncate_to_width(&process.user, self.width()).to_string(),
+            Column::State => process.state.to_string(),
+            Column::Threads => process.threads.to_string(),
+            Column::Cpu => format!("{cpu_pct:.1}%"),
+            Column::Mem => format!("{mem_pct:.1}%"),
+            Column::Rss => format_bytes(process.rss_bytes),
+            Column::Vsz => format_bytes(process.vsz_bytes),
+            Column::Start => format_start_time(process.start_time),
+            Column::Read => bytes(process.read_bytes),
+            Column::Write => bytes(process.write_bytes),
+            Column::Time => format_cpu_time(process.cpu_time),
+            Column::Command => process.name.clone(),
+        }
+    }
+}
+
+/// The process list's columns and initial sort
+#[derive(Clone, Debug, PartialEq)]
+pub struct PsColumns {
+    /// Shown columns, ending with the command
+    pub columns: Vec<Column>,
+    pub sort: Column,
+}
+
+impl Default for PsColumns {
+    fn default() -> Self {
+        Self {
+            columns: vec![Column::Pid, Column::Cpu, Column::Mem, Column::Command],
+            sort: Column::Cpu,
+        }
+    }
+}
+
+impl PsColumns {
+    /// Validate `[ps]`; the command column is added at the end if missing
+    pub fn from_settings(settings: &PsSettings) -> Result<Self, String> {
+        let mut layout = Self::default();
+        if !settings.columns.is_empty() {
+            layout.columns.clear();
+            for name in &settings.columns {
+                let column = Column::parse(name)?;
+                if layout.columns.contains(&column) {
+                    return Err(format!("column '{}' is listed twice", column.name()));
+                }
+                if layout.columns.last() == Some(&Column::Command) {
+                    return Err("'command' must be the last column".to_string());
+                }
+                layout.columns.push(column);
+            }
+            if layout.columns.last() != Some(&Column::Command) {
+                layout.columns.push(Column::Command);
+            }
+        }
+        if let Some(sort) = &settings.sort {
+            layout.sort = Column::parse(sort)?;
+        }
+        Ok(layout)
+    }
+
+    /// The header line, indented past the selection marker
+    pub fn header(&self, tree: bool) -> String {
+        let cells = self.columns.iter().map(|&column| {
+            let header = column.header(tree);
+            align(column, header.to_string())
+        });
+        format!(" {}", cells.collect::<Vec<_>>().join("  "))
+    }
+
+    /// One process row. `tree` is the branch prefix drawn before the command.
+    pub fn row(
+        &self,
+        process: &ProcessInfo,
+        cpu_pct: f32,
+        mem_pct: f32,
+        tree: &str,
+        selected: bool,
+    ) -> String {
+        let cells = self.columns.iter().map(|&column| {
+            let cell = column.cell(process, cpu_pct, mem_pct);
+            if column == Column::Command {
+                format!("{tree}{cell}")
+            } else {
+                align(column, cell)
+            }
+        });
+        format!(
+            "{}{}",
+            if selected { '>' } else { ' ' },
+            cells.collect::<Vec<_>>().join("  ")
+        )
+    }
+}
+
+fn align(column: Column, text: String) -> String {
+    let padding = " ".repeat(column.width().saturating_sub(unicode::str_width(&text)));
+    if column == Column::Command {
+        text
+    } else if column.left_aligned() {
+        text + &padding
+    } else {
+        padding + &text
+    }
+}
+
+/// `HH:MM` for processes started today, otherwise `MonDD`, or the year when
+/// it was a previous year
+fn format_start_time(start_time: u64) -> String {
+    let Some(started) = Local.timestamp_opt(start_time as i64, 0).single() else {
+        return "-".to_string();
+    };
+    let now = Local::now();
+    if started.date_naive() == now.date_naive() {
+        started.format("%H:%M").to_string()
+    } else if started.year() == now.year() {
+        started.format("%b%d").to_string()
+    } else {
+        started.year().to_string()
+    }
+}
+
+/// Accumulated CPU time as `M:SS`, or `H:MM:SS` from an hour on
+fn format_cpu_time(seconds: f64) -> String {
+    let total = seconds as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_cpu_time, Column, PsColumns};
+    use crate::settings::PsSettings;
+
+    fn settings(columns: &[&str], sort: Option<&str>) -> PsSettings {
+        PsSettings {
+            columns: columns.iter().map(|name| name.to_string()).collect(),
+            sort: sort.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn column_settings_parse_and_keep_the_command_last() {
+        assert_eq!(
+            PsColumns::from_settings(&PsSettings::default()),
+            Ok(PsColumns::default())
+        );
+        assert_eq!(
+            PsColumns::from_settings(&settings(&["pid", "User", "rss"], Some("time"))),
+            Ok(PsColumns {
+                columns: vec![Column::Pid, Column::User, Column::Rss, Column::Command],
+                sort: Column::Time,
+            })
+        );
+
+        let error = |columns: &[&str], sort| {
+            PsColumns::from_settings(&settings(columns, sort)).unwrap_err()
+        };
+        assert!(error(&["pid", "nice"], None).starts_with("unknown column 'nice'"));
+        assert_eq!(error(&["pid", "pid"], None), "column 'pid' is listed twice");
+        assert_eq!(
+            error(&["command", "pid"], None),
+            "'command' must be the last column"
+        );
+        assert!(error(&[], Some("size")).starts_with("unknown column 'size'"));
+    }
+
+    #[test]
+    fn cpu_time_reads_like_ps() {
+        assert_eq!(format_cpu_time(59.9), "0:59");
+        assert_eq!(format_cpu_time(754.0), "12:34");
+        assert_eq!(format_cpu_time(3_723.0), "1:02:03");
+    }
+}
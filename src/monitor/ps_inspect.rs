@@ -0,0 +1,427 @@
+//! Process inspector: the pane `Enter` opens under the process list
+//!
+//! Everything is read from /proc for the selected process on each sample.
+//! Sections another user's process keeps private (fds, environ) say so
+//! instead of disappearing.
+
+use crate::colors::ColorState;
+use crate::monitor::history::History;
+use crate::monitor::layout::{
+    draw_history_graph, draw_panel_box, format_bytes, header_color_scheme, muted_color_scheme,
+    text_color_scheme, GraphStyle, Rect,
+};
+use crate::monitor::ps::ProcessInfo;
+use crate::net_geo::{self, Protocol, Socket, TcpState};
+use crate::terminal::Terminal;
+use crate::unicode;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+
+/// Samples kept for the sparklines
+const SPARKLINE_SAMPLES: usize = 120;
+/// File descriptors listed before the rest are counted
+const MAX_FDS: usize = 256;
+
+struct Section {
+    title: String,
+    lines: Vec<String>,
+}
+
+pub struct Inspector {
+    pid: u32,
+    cpu: History,
+    rss: History,
+    sections: Vec<Section>,
+    /// First section line shown
+    scroll: usize,
+}
+
+impl Inspector {
+    pub fn new(process: &ProcessInfo) -> Self {
+        let mut inspector = Self {
+            pid: process.pid,
+            cpu: History::new(SPARKLINE_SAMPLES),
+            rss: History::new(SPARKLINE_SAMPLES),
+            sections: Vec::new(),
+            scroll: 0,
+        };
+        inspector.refresh(process);
+        inspector
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Re-read /proc and record the process' latest usage
+    pub fn refresh(&mut self, process: &ProcessInfo) {
+        self.cpu.push(process.cpu_pct);
+        self.rss.push(process.rss_bytes as f32);
+        self.sections = read_sections(process.pid);
+    }
+
+    pub fn scroll_by(&mut self, lines: isize) {
+        let total: usize = self.sections.iter().map(|s| s.lines.len() + 1).sum();
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(lines)
+            .min(total.saturating_sub(1));
+    }
+
+    pub fn render(
+        &self,
+        term: &mut Terminal,
+        area: &Rect,
+        process: &ProcessInfo,
+        colors: &ColorState,
+    ) {
+        let title = format!(" PID {} {} ", process.pid, process.name);
+        draw_panel_box(term, area, &title, muted_color_scheme(colors), false);
+        let (x, width) = (area.inner_x(), area.inner_width() as usize);
+        let height = area.inner_height() as usize;
+        if width < 20 || height < 3 {
+            return;
+        }
+
+        // Sparklines: label, graph, then the latest reading
+        const LABEL: usize = 5;
+        const VALUE: usize = 10;
+        let graph_width = width - LABEL - VALUE;
+        let sparklines = [
+            (
+                "CPU",
+                &self.cpu,
+                self.cpu.max().max(100.0),
+                format!("{:.1}%", process.cpu_pct),
+            ),
+            (
+                "RSS",
+                &self.rss,
+                self.rss.max(),
+                format_bytes(process.rss_bytes),
+            ),
+        ];
+        for (row, (label, history, max, value)) in sparklines.into_iter().enumerate() {
+            let y = area.inner_y() + row as i32;
+            term.set_str(x, y, label, Some(text_color_scheme(colors)), true);
+            draw_history_graph(
+                term,
+                x + LABEL as i32,
+                y,
+                graph_width,
+                1,
+                history,
+                max,
+                GraphStyle::Area,
+                colors,
+            );
+            let value = format!("{value:>VALUE$}");
+            term.set_str(
+                x + (LABEL + graph_width) as i32,
+                y,
+                &value,
+                Some(text_color_scheme(colors)),
+                false,
+            );
+        }
+
+        let summary = format!(
+            "{} │ {} │ {} threads │ {}",
+            process.user,
+            state_name(process.state),
+            process.threads,
+            if process.is_kernel {
+                "kernel thread"
+            } else {
+                process.cmdline.as_str()
+            }
+        );
+        term.set_str(
+            x,
+            area.inner_y() + 2,
+            unicode::truncate_to_width(&summary, width),
+            Some(muted_color_scheme(colors)),
+            false,
+        );
+
+        let lines = self.sections.iter().flat_map(|section| {
+            std::iter::once((section.title.as_str(), true))
+                .chain(section.lines.iter().map(|line| (line.as_str(), false)))
+        });
+        for (row, (line, heading)) in lines.skip(self.scroll).take(height - 3).enumerate() {
+            let color = if heading {
+                header_color_scheme(colors)
+            } else {
+                text_color_scheme(colors)
+            };
+            term.set_str(
+                x,
+                area.inner_y() + 3 + row as i32,
+                unicode::truncate_to_width(line, width),
+                Some(color),
+                heading,
+            );
+        }
+    }
+}
+
+fn state_name(state: char) -> String {
+    let name = match state {
+        'R' => "running",
+        'S' => "sleeping",
+        'D' => "disk sleep",
+        'Z' => "zombie",
+        'T' => "stopped",
+        't' => "tracing stop",
+        'I' => "idle",
+        'X' => "dead",
+        _ => return state.to_string(),
+    };
+    format!("{state} ({name})")
+}
+
+fn read_sections(pid: u32) -> Vec<Section> {
+    let proc_file =
+        |name: &str| fs::read_to_string(format!("/proc/{pid}/{name}")).map_err(|e| e.to_string());
+    let section = |title: &str, lines: Result<Vec<String>, String>| Section {
+        title: title.to_string(),
+        lines: lines.unwrap_or_else(|e| vec![format!("not readable: {e}")]),
+    };
+
+    let fds = read_fds(pid).map_err(|e| e.to_string());
+    let sockets = net_geo::read_sockets();
+    let connections = fds.as_ref().map_err(String::clone).map(|fds| {
+        fds.iter()
+            .filter_map(|(fd, target)| {
+                let socket = sockets.get(&socket_inode(target)?)?;
+                Some(format!("{fd:>4}  {}", describe_socket(socket)))
+            })
+            .collect::<Vec<_>>()
+    });
+    let fd_lines = fds.as_ref().map_err(String::clone).map(|fds| {
+        let mut lines: Vec<String> = fds
+            .iter()
+            .take(MAX_FDS)
+            .map(
+                |(fd, target)| match socket_inode(target).and_then(|inode| sockets.get(&inode)) {
+                    Some(socket) => format!("{fd:>4}  {target} ({})", describe_socket(socket)),
+                    None => format!("{fd:>4}  {target}"),
+                },
+            )
+            .collect();
+        if fds.len() > MAX_FDS {
+            lines.push(format!("… {} more", fds.len() - MAX_FDS));
+        }
+        lines
+    });
+    let fd_title = match &fds {
+        Ok(fds) => format!("FILE DESCRIPTORS ({})", fds.len()),
+        Err(_) => "FILE DESCRIPTORS".to_string(),
+    };
+
+    vec![
+        section(
+            "MEMORY",
+            proc_file("smaps_rollup").map(|text| memory_summary(&text)),
+        ),
+        section("CGROUP", proc_file("cgroup").map(|text| cgroups(&text))),
+        section(
+            "CONNECTIONS",
+            connections.map(|lines| {
+                if lines.is_empty() {
+                    vec!["none".to_string()]
+                } else {
+                    lines
+                }
+            }),
+        ),
+        section(&fd_title, fd_lines),
+        section("LIMITS", proc_file("limits").map(|text| limits(&text))),
+        section(
+            "ENVIRONMENT",
+            fs::read(format!("/proc/{pid}/environ"))
+                .map(|bytes| environment(&bytes))
+                .map_err(|e| e.to_string()),
+        ),
+    ]
+}
+
+/// Open descriptors and their link targets, by descriptor number
+fn read_fds(pid: u32) -> io::Result<Vec<(u32, String)>> {
+    let mut fds: Vec<(u32, String)> = fs::read_dir(format!("/proc/{pid}/fd"))?
+        .flatten()
+        .filter_map(|entry| {
+            let fd = entry.file_name().to_str()?.parse().ok()?;
+            let target = fs::read_link(entry.path()).ok()?;
+            Some((fd, target.to_string_lossy().into_owned()))
+        })
+        .collect();
+    fds.sort_unstable_by_key(|(fd, _)| *fd);
+    Ok(fds)
+}
+
+fn socket_inode(target: &str) -> Option<u64> {
+    target
+        .strip_prefix("socket:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+fn describe_socket(socket: &Socket) -> String {
+    let protocol = socket.protocol.label();
+    if socket.remote.ip().is_unspecified() {
+        let state = match socket.protocol {
+            Protocol::Tcp => socket.state.label(),
+            Protocol::Udp => "",
+        };
+        format!("{protocol} {} {state}", socket.local)
+            .trim_end()
+            .to_string()
+    } else {
+        let state = match (socket.protocol, socket.state) {
+            (Protocol::Udp, _) | (_, TcpState::Unknown) => "",
+            (_, state) => state.label(),
+        };
+        format!("{protocol} {} → {} {state}", socket.local, socket.remote)
+            .trim_end()
+            .to_string()
+    }
+}
+
+/// Summarise smaps_rollup, whose sizes are in kB
+fn memory_summary(text: &str) -> Vec<String> {
+    let fields: HashMap<&str, u64> = text
+        .lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let kb = rest.split_whitespace().next()?.parse::<u64>().ok()?;
+            Some((key, kb * 1024))
+        })
+        .collect();
+    let field = |key: &str| fields.get(key).copied().unwrap_or(0);
+    let size = |key: &str| format_bytes(field(key));
+    vec![
+        format!(
+            "RSS {}   PSS {}   Swap {}",
+            size("Rss"),
+            size("Pss"),
+            size("Swap")
+        ),
+        format!(
+            "Private {}   Shared {}   Anonymous {}",
+            format_bytes(field("Private_Clean") + field("Private_Dirty")),
+            format_bytes(field("Shared_Clean") + field("Shared_Dirty")),
+            size("Anonymous")
+        ),
+    ]
+}
+
+/// cgroup v2 paths as-is; v1 hierarchies prefixed with their controllers
+fn cgroups(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, ':');
+            let (_, controllers, path) = (parts.next()?, parts.next()?, parts.next()?);
+            Some(if controllers.is_empty() {
+                path.to_string()
+            } else {
+                format!("{controllers}: {path}")
+            })
+        })
+        .collect()
+}
+
+/// Rows of /proc/<pid>/limits as `name  soft / hard units`
+fn limits(text: &str) -> Vec<String> {
+    // The table is fixed-width: name, soft and hard limits, then units
+    let column = |line: &str, start: usize, end: usize| {
+        line.get(start..end.min(line.len()))
+            .unwrap_or("")
+            .trim()
+            .to_string()
+    };
+    text.lines()
+        .skip(1)
+        .map(|line| {
+            let (name, soft, hard) = (
+                column(line, 0, 26),
+                column(line, 26, 47),
+                column(line, 47, 68),
+            );
+            let units = column(line, 68, line.len());
+            format!("{name:<26}{soft:>12} / {hard:<12}{units}")
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+fn environment(bytes: &[u8]) -> Vec<String> {
+    let mut vars: Vec<String> = bytes
+        .split(|&byte| byte == 0)
+        .filter(|var| !var.is_empty())
+        .map(|var| String::from_utf8_lossy(var).into_owned())
+        .collect();
+    vars.sort();
+    if vars.is_empty() {
+        vars.push("empty".to_string());
+    }
+    vars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cgroups, limits, memory_summary, read_sections, socket_inode};
+
+    #[test]
+    fn proc_files_summarise_into_inspector_lines() {
+        let smaps = "55a4c0000000-7ffd00000000 ---p 00000000 00:00 0    [rollup]\n\
+                     Rss:                2048 kB\n\
+                     Pss:                1024 kB\n\
+                     Shared_Clean:        512 kB\n\
+                     Private_Dirty:      1536 kB\n\
+                     Anonymous:          1536 kB\n\
+                     Swap:                  0 kB\n";
+        assert_eq!(
+            memory_summary(smaps),
+            [
+                "RSS 2.0MiB   PSS 1.0MiB   Swap 0B",
+                "Private 1.5MiB   Shared 512.0KiB   Anonymous 1.5MiB"
+            ]
+        );
+
+        assert_eq!(
+            cgroups("0::/user.slice/user-1000.slice\n4:cpu,cpuacct:/\n"),
+            ["/user.slice/user-1000.slice", "cpu,cpuacct: /"]
+        );
+
+        let table = "Limit                     Soft Limit           Hard Limit           Units     \n\
+                     Max open files            1024                 524288               files     \n\
+                     Max realtime timeout      unlimited            unlimited            us        \n";
+        assert_eq!(
+            limits(table),
+            [
+                "Max open files                    1024 / 524288      files",
+                "Max realtime timeout         unlimited / unlimited   us"
+            ]
+        );
+
+        assert_eq!(socket_inode("socket:[48213]"), Some(48213));
+        assert_eq!(socket_inode("/dev/null"), None);
+    }
+
+    #[test]
+    fn inspecting_this_process_reads_its_descriptors_and_environment() {
+        let sections = read_sections(std::process::id());
+        let titles: Vec<&str> = sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles[..3], ["MEMORY", "CGROUP", "CONNECTIONS"]);
+        assert!(titles[3].starts_with("FILE DESCRIPTORS ("));
+        assert_eq!(titles[4..], ["LIMITS", "ENVIRONMENT"]);
+        assert!(sections[4]
+            .lines
+            .iter()
+            .any(|l| l.starts_with("Max open files")));
+    }
+}
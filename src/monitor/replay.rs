@@ -0,0 +1,481 @@
+//! Recorded monitor sessions
+//!
+//! `--record-samples` appends every sample a monitor takes to a file, one
+//! JSON record per line in the same shape `--format json` writes. `termart
+//! replay` reads such a file back into the monitor's TUI, stepping through the
+//! samples at their recorded pace with pause, speed and seek controls.
+
+use crate::help::{HelpEntry, HelpSpec};
+use crate::monitor::cpu::CpuSnapshot;
+use crate::monitor::disk::DiskInfo;
+use crate::monitor::diskio::DiskStats;
+use crate::monitor::gpu::{GpuInfo, GpuMonitor};
+use crate::monitor::history::VIEW_TOGGLE_HELP;
+use crate::monitor::layout::{header_color_scheme, muted_color_scheme};
+use crate::monitor::mem::MemInfo;
+use crate::monitor::net::InterfaceStats;
+use crate::monitor::output::{Collector, OutputFormat, RecordWriter};
+use crate::monitor::{MonitorAction, MonitorState, MonitorType, SystemMonitor};
+use crate::terminal::Terminal;
+use chrono::{DateTime, FixedOffset};
+use crossterm::event::KeyCode;
+use serde::Deserialize;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+/// Samples skipped by the arrow keys
+const SEEK_STEP: usize = 10;
+/// Samples skipped by Page Up/Down
+const SEEK_PAGE: usize = 60;
+/// Fastest replay step, in seconds
+const MIN_STEP: f32 = 0.05;
+
+/// Where a monitor appends its samples; disabled unless opened
+#[derive(Default)]
+pub struct SampleLog {
+    writer: Option<RecordWriter<BufWriter<File>>>,
+}
+
+impl SampleLog {
+    /// Append to `path`, creating it if needed
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            writer: Some(RecordWriter::new(OutputFormat::Json, BufWriter::new(file))),
+        })
+    }
+
+    /// Record the monitor's current sample. A failed write stops recording
+    /// and says so in the status line rather than ending the session.
+    pub fn save(&mut self, collector: &impl Collector, state: &mut MonitorState) {
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        if let Err(e) = writer.write_snapshot(collector.snapshot()) {
+            state.set_feedback(format!("Recording stopped: {e}"));
+            self.writer = None;
+        }
+    }
+}
+
+/// A sample as read back from a recording
+#[derive(Deserialize)]
+#[serde(tag = "monitor", rename_all = "lowercase")]
+enum Sample {
+    Cpu(CpuSnapshot),
+    Mem(MemInfo),
+    Disk { filesystems: Vec<DiskInfo> },
+    Io { disks: Vec<DiskStats> },
+    Net { interfaces: Vec<InterfaceStats> },
+    Gpu { gpus: Vec<GpuInfo> },
+}
+
+impl Sample {
+    fn kind(&self) -> MonitorType {
+        match self {
+            Sample::Cpu(_) => MonitorType::Cpu,
+            Sample::Mem(_) => MonitorType::Mem,
+            Sample::Disk { .. } => MonitorType::Disk,
+            Sample::Io { .. } => MonitorType::Io,
+            Sample::Net { .. } => MonitorType::Net,
+            Sample::Gpu { .. } => MonitorType::Gpu,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RecordedLine {
+    timestamp: String,
+    #[serde(flatten)]
+    sample: Sample,
+}
+
+struct RecordedSample {
+    time: DateTime<FixedOffset>,
+    sample: Sample,
+}
+
+/// Every sample of one monitor's recording, in file order
+struct Recording {
+    kind: MonitorType,
+    samples: Vec<RecordedSample>,
+}
+
+impl Recording {
+    fn load(path: &Path) -> io::Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    fn parse(text: &str) -> io::Result<Self> {
+        let invalid = |line: usize, message: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {line}: {message}"),
+            )
+        };
+
+        let mut samples: Vec<RecordedSample> = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: RecordedLine =
+                serde_json::from_str(line).map_err(|e| invalid(index + 1, e.to_string()))?;
+            let time = DateTime::parse_from_rfc3339(&record.timestamp)
+                .map_err(|e| invalid(index + 1, format!("bad timestamp: {e}")))?;
+            if let Some(first) = samples.first() {
+                if first.sample.kind() != record.sample.kind() {
+                    return Err(invalid(
+                        index + 1,
+                        format!(
+                            "{} sample in a {} recording",
+                            monitor_name(record.sample.kind()),
+                            monitor_name(first.sample.kind())
+                        ),
+                    ));
+                }
+            }
+            samples.push(RecordedSample {
+                time,
+                sample: record.sample,
+            });
+        }
+
+        let Some(first) = samples.first() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "recording has no samples",
+            ));
+        };
+        Ok(Self {
+            kind: first.sample.kind(),
+            samples,
+        })
+    }
+
+    /// Seconds between sample `index` and the one before it
+    fn gap(&self, index: usize) -> f32 {
+        if index == 0 {
+            return 0.0;
+        }
+        let delta = self.samples[index].time - self.samples[index - 1].time;
+        delta.num_milliseconds().max(0) as f32 / 1000.0
+    }
+
+    /// Median time between samples, the pace of a replay at 1x
+    fn interval(&self) -> f32 {
+        let mut gaps: Vec<f32> = (1..self.samples.len()).map(|i| self.gap(i)).collect();
+        if gaps.is_empty() {
+            return 1.0;
+        }
+        gaps.sort_by(f32::total_cmp);
+        gaps[gaps.len() / 2].max(MIN_STEP)
+    }
+}
+
+fn monitor_name(kind: MonitorType) -> &'static str {
+    match kind {
+        MonitorType::Cpu => "cpu",
+        MonitorType::Mem => "mem",
+        MonitorType::Disk => "disk",
+        MonitorType::Io => "io",
+        MonitorType::Net => "net",
+        MonitorType::Gpu => "gpu",
+    }
+}
+
+pub struct ReplayConfig {
+    pub path: PathBuf,
+    /// Playback rate relative to the recorded pace
+    pub speed: f32,
+    /// Samples kept for the history view
+    pub history: usize,
+}
+
+/// A monitor showing one position in a recording
+struct Player {
+    recording: Recording,
+    monitor: SystemMonitor,
+    history: usize,
+    position: usize,
+}
+
+impl Player {
+    fn new(recording: Recording, history: usize) -> Self {
+        let monitor = Self::empty_monitor(recording.kind, history);
+        let mut player = Self {
+            recording,
+            monitor,
+            history,
+            position: 0,
+        };
+        player.show(0);
+        player
+    }
+
+    fn empty_monitor(kind: MonitorType, history: usize) -> SystemMonitor {
+        let mut monitor = match kind {
+            // Don't probe for a live GPU backend just to show recorded ones
+            MonitorType::Gpu => SystemMonitor::Gpu(GpuMonitor::replaying()),
+            kind => SystemMonitor::new(kind),
+        };
+        monitor.set_history_window(history);
+        monitor
+    }
+
+    fn last(&self) -> usize {
+        self.recording.samples.len() - 1
+    }
+
+    /// Feed sample `index` to the monitor
+    fn show(&mut self, index: usize) {
+        let interval = self.recording.gap(index);
+        match (&mut self.monitor, &self.recording.samples[index].sample) {
+            (SystemMonitor::Cpu(monitor), Sample::Cpu(snapshot)) => monitor.restore(snapshot),
+            (SystemMonitor::Mem(monitor), Sample::Mem(info)) => monitor.restore(info),
+            (SystemMonitor::Disk(monitor), Sample::Disk { filesystems }) => {
+                monitor.disks = filesystems.clone();
+            }
+            (SystemMonitor::Io(monitor), Sample::Io { disks }) => monitor.restore(disks, interval),
+            (SystemMonitor::Net(monitor), Sample::Net { interfaces }) => {
+                monitor.restore(interfaces, interval);
+            }
+            (SystemMonitor::Gpu(monitor), Sample::Gpu { gpus }) => monitor.restore(gpus),
+            _ => {}
+        }
+        self.position = index;
+    }
+
+    /// Step to the next sample; false at the end of the recording
+    fn advance(&mut self) -> bool {
+        if self.position >= self.last() {
+            return false;
+        }
+        self.show(self.position + 1);
+        true
+    }
+
+    /// Jump to sample `target`, rebuilding the monitor from the samples its
+    /// history window covers so graphs and rate scales match a straight replay
+    fn seek(&mut self, target: usize) {
+        let target = target.min(self.last());
+        if target == self.position {
+            return;
+        }
+        let view = self.monitor.view();
+        self.monitor = Self::empty_monitor(self.recording.kind, self.history);
+        if self.monitor.view() != view {
+            self.monitor.toggle_view();
+        }
+        for index in target.saturating_sub(self.history.max(1) - 1)..=target {
+            self.show(index);
+        }
+    }
+
+    fn status(&self, rate: f32, paused: bool) -> String {
+        let time = self.recording.samples[self.position].time;
+        let mut fields = vec![
+            format!("REPLAY {}", time.format("%Y-%m-%d %H:%M:%S")),
+            format!("{}/{}", self.position + 1, self.recording.samples.len()),
+            format!("×{rate:.2}"),
+        ];
+        if paused {
+            fields.push("PAUSED".to_string());
+        }
+        format!(" {}", fields.join(" │ "))
+    }
+}
+
+pub fn run(config: ReplayConfig) -> io::Result<()> {
+    let recording = Recording::load(&config.path)?;
+    replay(recording, config.speed, config.history)
+}
+
+fn replay(recording: Recording, speed: f32, history: usize) -> io::Result<()> {
+    let mut term = Terminal::new(true)?;
+    let pace = recording.interval();
+    let mut state = MonitorState::new(pace / speed.max(f32::EPSILON), MIN_STEP);
+    let mut player = Player::new(recording, history);
+    state.mark_sampled();
+    const HELP: HelpSpec = HelpSpec::monitor(
+        "REPLAY",
+        &[
+            HelpEntry::new("←/→", "Seek 10 samples"),
+            HelpEntry::new("PgUp/PgDn", "Seek 60 samples"),
+            HelpEntry::new("Home/End", "First/last sample"),
+            VIEW_TOGGLE_HELP,
+        ],
+    );
+
+    loop {
+        let mut action = MonitorAction::None;
+        if let Ok(Some((code, mods))) = term.check_key() {
+            let position = player.position;
+            match code {
+                KeyCode::Char('g') => match player.monitor.toggle_view() {
+                    Some(view) => state.set_feedback(format!("View: {}", view.label())),
+                    None => state.set_feedback("No history view"),
+                },
+                KeyCode::Left => player.seek(position.saturating_sub(SEEK_STEP)),
+                KeyCode::Right => player.seek(position + SEEK_STEP),
+                KeyCode::PageUp => player.seek(position.saturating_sub(SEEK_PAGE)),
+                KeyCode::PageDown => player.seek(position + SEEK_PAGE),
+                KeyCode::Home => player.seek(0),
+                KeyCode::End => player.seek(usize::MAX),
+                _ => {
+                    action = state.handle_key(code, mods);
+                    if action == MonitorAction::Quit {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if let Ok((new_w, new_h)) = term.query_size() {
+            let (cur_w, cur_h) = term.size();
+            if new_w != cur_w || new_h != cur_h {
+                term.resize(new_w, new_h);
+                term.clear_screen()?;
+            }
+        }
+
+        if state.should_sample(action) {
+            if player.advance() {
+                state.mark_sampled();
+            } else {
+                state.paused = true;
+                state.set_feedback("End of recording");
+            }
+        }
+
+        term.clear();
+
+        let (w, h) = term.size();
+        let body = h.saturating_sub(1);
+        player
+            .monitor
+            .render_fullscreen(&mut term, w as usize, body as usize, &state.colors);
+        let status: String = player
+            .status(pace / state.speed, state.paused)
+            .chars()
+            .take(w as usize)
+            .collect();
+        term.set_str(
+            0,
+            body as i32,
+            &status,
+            Some(muted_color_scheme(&state.colors)),
+            false,
+        );
+        term.set_str(
+            1,
+            body as i32,
+            "REPLAY",
+            Some(header_color_scheme(&state.colors)),
+            true,
+        );
+        state.render_help(&mut term, w, h, &HELP);
+
+        term.present()?;
+        term.sleep(state.poll_delay());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replay, Player, Recording, SampleLog};
+    use crate::monitor::mem::MemMonitor;
+    use crate::monitor::{MonitorState, MonitorType, SystemMonitor};
+    use crate::terminal::{run_headless, ScriptedInput};
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    fn mem_line(second: u32, used_kb: u64) -> String {
+        format!(
+            "{{\"timestamp\":\"2026-01-02T03:04:{second:02}+00:00\",\"monitor\":\"mem\",\
+             \"mem_total\":1000,\"mem_available\":{free},\"mem_free\":{free},\"buffers\":0,\
+             \"cached\":0,\"swap_total\":0,\"swap_free\":0}}",
+            free = 1000 - used_kb
+        )
+    }
+
+    fn mem_recording(samples: u32) -> Recording {
+        let text: Vec<String> = (0..samples)
+            .map(|i| mem_line(i * 2, 100 + i as u64))
+            .collect();
+        Recording::parse(&text.join("\n")).expect("valid recording")
+    }
+
+    #[test]
+    fn recordings_parse_with_their_pace_and_reject_mixed_or_empty_files() {
+        let recording = mem_recording(4);
+        assert_eq!(recording.kind, MonitorType::Mem);
+        assert_eq!(recording.samples.len(), 4);
+        assert_eq!(recording.interval(), 2.0);
+
+        let mixed = format!(
+            "{}\n\n{{\"timestamp\":\"2026-01-02T03:04:09+00:00\",\"monitor\":\"disk\",\"filesystems\":[]}}",
+            mem_line(0, 1)
+        );
+        let error = Recording::parse(&mixed).err().expect("mixed monitors");
+        assert_eq!(error.to_string(), "line 3: disk sample in a mem recording");
+
+        let error = Recording::parse("\n").err().expect("empty recording");
+        assert_eq!(error.to_string(), "recording has no samples");
+        let error = Recording::parse("{\"monitor\":\"mem\"}")
+            .err()
+            .expect("missing fields");
+        assert!(error.to_string().starts_with("line 1: "), "{error}");
+    }
+
+    #[test]
+    fn seeking_rebuilds_history_from_the_samples_before_the_target() {
+        let mut player = Player::new(mem_recording(20), 5);
+        assert!(player.advance());
+        player.seek(15);
+        assert_eq!(player.position, 15);
+        let SystemMonitor::Mem(monitor) = &player.monitor else {
+            panic!("mem recording replays in the memory monitor");
+        };
+        assert_eq!(monitor.history.get("used").len(), 5);
+        assert_eq!(monitor.info.mem_total, 1000);
+
+        player.seek(usize::MAX);
+        assert_eq!(player.position, 19);
+        assert!(!player.advance());
+    }
+
+    #[test]
+    fn saved_samples_replay_in_the_tui() {
+        let path = std::env::temp_dir().join(format!(
+            "termart-replay-{}-{}",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        let mut log = SampleLog::open(&path).expect("sample log opens");
+        let mut monitor = MemMonitor::new();
+        let mut state = MonitorState::new(1.0, 0.5);
+        for _ in 0..2 {
+            monitor.update().expect("read /proc/meminfo");
+            log.save(&monitor, &mut state);
+        }
+        drop(log);
+
+        let recording = Recording::load(&path).expect("saved samples load");
+        std::fs::remove_file(&path).expect("test file should be removed");
+        assert_eq!(recording.samples.len(), 2);
+
+        let input = [
+            ScriptedInput::Key(KeyCode::Char(' '), KeyModifiers::NONE),
+            ScriptedInput::Idle(1),
+        ];
+        let (result, frame) = run_headless(100, 20, input, || replay(recording, 1.0, 10));
+        result.expect("headless replay");
+        let text = frame.text();
+        assert!(text.contains("Available"), "{text}");
+        assert!(text.contains("REPLAY"), "{text}");
+        assert!(text.contains("1/2 │ ×1.00 │ PAUSED"), "{text}");
+    }
+}
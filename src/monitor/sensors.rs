@@ -0,0 +1,549 @@
+//! Hardware sensors monitor - every hwmon chip's temperatures, fans, voltages,
+//! currents and power
+//!
+//! Readings sit next to the limits the driver reports and the lowest and
+//! highest values seen since the monitor started.
+
+use crate::colors::ColorState;
+use crate::help::{HelpEntry, HelpSpec};
+use crate::monitor::layout::{
+    header_color_scheme, muted_color_scheme, temp_gradient_color_scheme, text_color_scheme,
+};
+use crate::monitor::output::{self, Collector, OutputConfig, Snapshot};
+use crate::monitor::{MonitorAction, MonitorState};
+use crate::terminal::Terminal;
+use crate::unicode;
+use crossterm::event::KeyCode;
+use crossterm::style::Color;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Where the kernel lists hwmon chips
+pub const HWMON_ROOT: &str = "/sys/class/hwmon";
+
+/// Width of each value column
+const VALUE_WIDTH: usize = 11;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SensorKind {
+    Temp,
+    Fan,
+    Voltage,
+    Current,
+    Power,
+}
+
+impl SensorKind {
+    const ALL: [SensorKind; 5] = [
+        SensorKind::Temp,
+        SensorKind::Fan,
+        SensorKind::Voltage,
+        SensorKind::Current,
+        SensorKind::Power,
+    ];
+
+    /// Attribute prefix in sysfs, e.g. `in` for `in0_input`
+    fn prefix(self) -> &'static str {
+        match self {
+            SensorKind::Temp => "temp",
+            SensorKind::Fan => "fan",
+            SensorKind::Voltage => "in",
+            SensorKind::Current => "curr",
+            SensorKind::Power => "power",
+        }
+    }
+
+    /// Divisor from the sysfs unit (m°C, RPM, mV, mA, µW) to the shown one
+    fn scale(self) -> f64 {
+        match self {
+            SensorKind::Fan => 1.0,
+            SensorKind::Power => 1_000_000.0,
+            SensorKind::Temp | SensorKind::Voltage | SensorKind::Current => 1000.0,
+        }
+    }
+
+    fn format(self, value: f64) -> String {
+        match self {
+            SensorKind::Temp => format!("{value:.1}°C"),
+            SensorKind::Fan => format!("{value:.0} RPM"),
+            SensorKind::Voltage => format!("{value:.3} V"),
+            SensorKind::Current => format!("{value:.2} A"),
+            SensorKind::Power => format!("{value:.1} W"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Sensor {
+    /// Driver name of the chip, e.g. `coretemp`
+    pub chip: String,
+    /// The chip's hwmon directory, e.g. `hwmon2`, since chip names repeat
+    pub device: String,
+    /// The driver's label, or the attribute name (`temp1`) without one
+    pub label: String,
+    pub kind: SensorKind,
+    pub value: f64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub crit: Option<f64>,
+    /// Lowest and highest readings this session
+    pub lowest: f64,
+    pub highest: f64,
+    /// Attribute name, e.g. `temp1`
+    #[serde(skip)]
+    attribute: String,
+}
+
+impl Sensor {
+    /// Identifies the sensor across samples
+    fn key(&self) -> String {
+        format!("{}/{}", self.device, self.attribute)
+    }
+
+    /// Where `value` sits on this sensor's 0-100 colour scale: temperatures
+    /// from room temperature up to the critical (or maximum) limit, other
+    /// readings from zero up to their limit. `None` when there is no scale.
+    pub fn level(&self, value: f64) -> Option<f32> {
+        let limit = self.crit.or(self.max);
+        let percent = match (self.kind, limit) {
+            (SensorKind::Temp, Some(limit)) if limit > 20.0 => (value - 20.0) / (limit - 20.0),
+            (SensorKind::Temp, _) => (value - 20.0) / 80.0,
+            (_, Some(limit)) if limit > 0.0 => value / limit,
+            _ => return None,
+        };
+        Some((percent * 100.0).clamp(0.0, 100.0) as f32)
+    }
+}
+
+pub struct SensorMonitor {
+    root: PathBuf,
+    /// Every sensor, grouped by chip
+    sensors: Vec<Sensor>,
+    /// First line shown when the list is taller than the screen
+    scroll: usize,
+}
+
+impl SensorMonitor {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            sensors: Vec::new(),
+            scroll: 0,
+        }
+    }
+
+    pub fn update(&mut self) -> io::Result<()> {
+        let entries = fs::read_dir(&self.root).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("Cannot list hwmon chips in {}: {e}", self.root.display()),
+            )
+        })?;
+        let mut devices: Vec<(u32, String, PathBuf)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let device = entry.file_name().to_string_lossy().into_owned();
+                let index = device.strip_prefix("hwmon")?.parse().ok()?;
+                Some((index, device, entry.path()))
+            })
+            .collect();
+        devices.sort();
+
+        let previous: HashMap<String, (f64, f64)> = self
+            .sensors
+            .iter()
+            .map(|sensor| (sensor.key(), (sensor.lowest, sensor.highest)))
+            .collect();
+        let mut sensors = Vec::new();
+        for (_, device, dir) in devices {
+            read_chip(&dir, &device, &mut sensors);
+        }
+        for sensor in &mut sensors {
+            if let Some(&(lowest, highest)) = previous.get(&sensor.key()) {
+                sensor.lowest = lowest.min(sensor.value);
+                sensor.highest = highest.max(sensor.value);
+            }
+        }
+        self.sensors = sensors;
+        Ok(())
+    }
+
+    /// Start the session's lowest and highest readings over from now
+    pub fn clear_extremes(&mut self) {
+        for sensor in &mut self.sensors {
+            sensor.lowest = sensor.value;
+            sensor.highest = sensor.value;
+        }
+    }
+
+    pub fn scroll_by(&mut self, lines: isize) {
+        let last = self.sensors.len().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(lines).min(last);
+    }
+
+    pub fn render(&self, term: &mut Terminal, w: usize, h: usize, colors: &ColorState) {
+        if h < 4 || w < 50 {
+            return;
+        }
+
+        term.set_str(0, 0, "Sensors", Some(text_color_scheme(colors)), true);
+        let chips = self
+            .sensors
+            .iter()
+            .enumerate()
+            .filter(|(index, sensor)| {
+                *index == 0 || self.sensors[index - 1].device != sensor.device
+            })
+            .count();
+        let count = format!("{} sensors on {chips} chips", self.sensors.len());
+        term.set_str(
+            w.saturating_sub(count.len()) as i32,
+            0,
+            &count,
+            Some(muted_color_scheme(colors)),
+            false,
+        );
+
+        if self.sensors.is_empty() {
+            term.set_str(0, 2, "No hwmon sensors found", Some(Color::Yellow), false);
+            return;
+        }
+
+        // The session's extremes go first when the thresholds do not all fit
+        let columns = ((w - 20) / VALUE_WIDTH).min(6);
+        let headers = ["VALUE", "LOWEST", "HIGHEST", "MIN", "MAX", "CRIT"];
+        let label_w = w - columns * VALUE_WIDTH;
+        let mut header = format!("{:<label_w$}", "SENSOR");
+        for name in &headers[..columns] {
+            header.push_str(&format!("{name:>VALUE_WIDTH$}"));
+        }
+        term.set_str(0, 1, &header, Some(text_color_scheme(colors)), true);
+
+        // Each chip gets a header line above its sensors
+        let mut lines: Vec<Option<&Sensor>> = Vec::new();
+        for (index, sensor) in self.sensors.iter().enumerate() {
+            if index == 0 || self.sensors[index - 1].device != sensor.device {
+                lines.push(None);
+            }
+            lines.push(Some(sensor));
+        }
+        let height = h - 2;
+        let start = self.scroll.min(lines.len().saturating_sub(height));
+
+        for (y, (index, line)) in (2..).zip(lines.iter().enumerate().skip(start).take(height)) {
+            let Some(sensor) = line else {
+                // The chip of the next line
+                let Some(sensor) = lines[index + 1] else {
+                    continue;
+                };
+                term.set_str(0, y, &sensor.chip, Some(header_color_scheme(colors)), true);
+                term.set_str(
+                    unicode::str_width(&sensor.chip) as i32 + 1,
+                    y,
+                    &sensor.device,
+                    Some(muted_color_scheme(colors)),
+                    false,
+                );
+                continue;
+            };
+
+            let label = format!("  {}", sensor.label);
+            term.set_str(
+                0,
+                y,
+                unicode::truncate_to_width(&label, label_w.saturating_sub(1)),
+                Some(text_color_scheme(colors)),
+                false,
+            );
+            let values = [
+                Some(sensor.value),
+                Some(sensor.lowest),
+                Some(sensor.highest),
+                sensor.min,
+                sensor.max,
+                sensor.crit,
+            ];
+            for (column, value) in values.into_iter().take(columns).enumerate() {
+                let (text, color) = match value {
+                    Some(value) => (
+                        sensor.kind.format(value),
+                        match sensor.level(value) {
+                            Some(level) => temp_gradient_color_scheme(level, colors),
+                            None => text_color_scheme(colors),
+                        },
+                    ),
+                    None => ("—".to_string(), muted_color_scheme(colors)),
+                };
+                let x = label_w + column * VALUE_WIDTH;
+                let text = format!("{text:>VALUE_WIDTH$}");
+                term.set_str(x as i32, y, &text, Some(color), column == 0);
+            }
+        }
+    }
+}
+
+impl Collector for SensorMonitor {
+    fn sample(&mut self, _elapsed: f32) -> io::Result<()> {
+        self.update()
+    }
+
+    fn snapshot(&self) -> Snapshot<'_> {
+        Snapshot::Sensors {
+            sensors: &self.sensors,
+        }
+    }
+}
+
+/// Append the readable sensors of the chip in `dir`, by kind and number
+fn read_chip(dir: &Path, device: &str, sensors: &mut Vec<Sensor>) {
+    let chip = read_text(dir, "name").unwrap_or_else(|| device.to_string());
+    let names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    for kind in SensorKind::ALL {
+        let mut numbers: Vec<u32> = names
+            .iter()
+            .filter_map(|name| {
+                let rest = name.strip_prefix(kind.prefix())?;
+                let (number, _) = rest.split_once('_')?;
+                number.parse().ok()
+            })
+            .collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+
+        for number in numbers {
+            let attribute = format!("{}{number}", kind.prefix());
+            let read = |suffix: &str| {
+                read_text(dir, &format!("{attribute}_{suffix}"))?
+                    .parse::<f64>()
+                    .ok()
+                    .map(|raw| raw / kind.scale())
+            };
+            // Power meters may only report an average; missing or failing
+            // inputs (ENODATA from sleeping devices) are skipped
+            let value = match kind {
+                SensorKind::Power => read("input").or_else(|| read("average")),
+                _ => read("input"),
+            };
+            let Some(value) = value else {
+                continue;
+            };
+            sensors.push(Sensor {
+                chip: chip.clone(),
+                device: device.to_string(),
+                label: read_text(dir, &format!("{attribute}_label"))
+                    .unwrap_or_else(|| attribute.clone()),
+                kind,
+                value,
+                min: read("min"),
+                max: read("max").or_else(|| read("cap")),
+                crit: read("crit"),
+                lowest: value,
+                highest: value,
+                attribute,
+            });
+        }
+    }
+}
+
+fn read_text(dir: &Path, file: &str) -> Option<String> {
+    let text = fs::read_to_string(dir.join(file)).ok()?;
+    Some(text.trim().to_string()).filter(|text| !text.is_empty())
+}
+
+pub struct SensorsConfig {
+    pub time_step: f32,
+    /// Directory holding the `hwmonN` chips
+    pub root: PathBuf,
+    pub output: Option<OutputConfig>,
+}
+
+pub fn run(config: SensorsConfig) -> io::Result<()> {
+    let mut monitor = SensorMonitor::new(config.root);
+    if let Some(output) = config.output {
+        return output::stream(&mut monitor, output, config.time_step);
+    }
+
+    let mut term = Terminal::new(true)?;
+    let mut state = MonitorState::new(config.time_step, 0.5);
+    const HELP: HelpSpec = HelpSpec::monitor(
+        "SENSORS",
+        &[
+            HelpEntry::new("↑/↓ or j/k", "Scroll"),
+            HelpEntry::new("PgUp/PgDn", "Scroll a page"),
+            HelpEntry::new("c", "Clear session lowest/highest"),
+        ],
+    );
+
+    state.record_sample(monitor.update());
+
+    loop {
+        let mut action = MonitorAction::None;
+        if let Ok(Some((code, mods))) = term.check_key() {
+            let page = term.size().1.saturating_sub(2) as isize;
+            match code {
+                KeyCode::Up | KeyCode::Char('k') => monitor.scroll_by(-1),
+                KeyCode::Down | KeyCode::Char('j') => monitor.scroll_by(1),
+                KeyCode::PageUp => monitor.scroll_by(-page),
+                KeyCode::PageDown => monitor.scroll_by(page),
+                KeyCode::Char('c') => {
+                    monitor.clear_extremes();
+                    state.set_feedback("Session lowest/highest cleared");
+                }
+                _ => action = state.handle_key(code, mods),
+            }
+            if action == MonitorAction::Quit {
+                break;
+            }
+        }
+
+        if let Ok((new_w, new_h)) = term.query_size() {
+            let (cur_w, cur_h) = term.size();
+            if new_w != cur_w || new_h != cur_h {
+                term.resize(new_w, new_h);
+                term.clear_screen()?;
+            }
+        }
+
+        if state.should_sample(action) {
+            state.record_sample(monitor.update());
+        }
+
+        term.clear();
+
+        let (w, h) = term.size();
+        monitor.render(&mut term, w as usize, h as usize, &state.colors);
+        state.render_help(&mut term, w, h, &HELP);
+
+        term.present()?;
+        term.sleep(state.poll_delay());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SensorKind, SensorMonitor, SensorsConfig};
+    use crate::terminal::{run_headless, ScriptedInput};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    /// A hwmon class directory with a CPU chip and a board chip
+    fn chips() -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "termart-hwmon-{}-{}",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        write(
+            &root.join("hwmon10"),
+            &[
+                ("name", "nct6775"),
+                ("fan1_input", "1200"),
+                ("fan1_min", "300"),
+                ("in0_input", "1200"),
+                ("in0_label", "Vcore"),
+                ("curr1_input", "500"),
+                ("power1_average", "15000000"),
+                ("power1_cap", "60000000"),
+            ],
+        );
+        write(
+            &root.join("hwmon2"),
+            &[
+                ("name", "coretemp"),
+                ("temp1_input", "45000"),
+                ("temp1_label", "Package id 0"),
+                ("temp1_max", "80000"),
+                ("temp1_crit", "100000"),
+                // A label without a reading is not a sensor
+                ("temp2_label", "Core 0"),
+            ],
+        );
+        root
+    }
+
+    fn write(dir: &Path, files: &[(&str, &str)]) {
+        fs::create_dir_all(dir).expect("create chip");
+        for (file, content) in files {
+            fs::write(dir.join(file), format!("{content}\n")).expect("write chip file");
+        }
+    }
+
+    #[test]
+    fn chips_list_scaled_readings_with_limits_and_session_extremes() {
+        let root = chips();
+        let mut monitor = SensorMonitor::new(&root);
+        monitor.update().expect("read chips");
+
+        let summary: Vec<(&str, &str, SensorKind, f64)> = monitor
+            .sensors
+            .iter()
+            .map(|s| (s.chip.as_str(), s.label.as_str(), s.kind, s.value))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("coretemp", "Package id 0", SensorKind::Temp, 45.0),
+                ("nct6775", "fan1", SensorKind::Fan, 1200.0),
+                ("nct6775", "Vcore", SensorKind::Voltage, 1.2),
+                ("nct6775", "curr1", SensorKind::Current, 0.5),
+                ("nct6775", "power1", SensorKind::Power, 15.0),
+            ]
+        );
+        let package = &monitor.sensors[0];
+        assert_eq!((package.max, package.crit), (Some(80.0), Some(100.0)));
+        assert_eq!(package.level(60.0), Some(50.0));
+        assert_eq!(monitor.sensors[1].min, Some(300.0));
+        assert_eq!(monitor.sensors[1].level(1200.0), None);
+        assert_eq!(monitor.sensors[4].level(15.0), Some(25.0));
+
+        write(&root.join("hwmon2"), &[("temp1_input", "71500")]);
+        monitor.update().expect("read chips");
+        write(&root.join("hwmon2"), &[("temp1_input", "38000")]);
+        monitor.update().expect("read chips");
+        let package = &monitor.sensors[0];
+        assert_eq!(
+            (package.value, package.lowest, package.highest),
+            (38.0, 38.0, 71.5)
+        );
+
+        monitor.clear_extremes();
+        assert_eq!(monitor.sensors[0].highest, 38.0);
+        fs::remove_dir_all(&root).expect("test chips should be removed");
+        assert!(monitor.update().is_err());
+    }
+
+    #[test]
+    fn sensors_render_offscreen_grouped_by_chip() {
+        let root = chips();
+        let config = SensorsConfig {
+            time_step: 1.0,
+            root: root.clone(),
+            output: None,
+        };
+        let (result, frame) =
+            run_headless(100, 12, [ScriptedInput::Idle(1)], || super::run(config));
+        result.expect("headless sensors monitor");
+        let text = frame.text();
+        assert!(text.contains("5 sensors on 2 chips"), "{text}");
+        assert!(text.contains("coretemp hwmon2"), "{text}");
+        assert!(text.contains("Package id 0"), "{text}");
+        assert!(text.contains("80.0°C"), "{text}");
+        assert!(text.contains("1.200 V"), "{text}");
+        assert!(text.contains("60.0 W"), "{text}");
+        fs::remove_dir_all(&root).expect("test chips should be removed");
+    }
+}
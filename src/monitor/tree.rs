@@ -0,0 +1,64 @@
+//! Tree rows shared by the process and cgroup lists
+//!
+//! Both lists index their nodes into a flat vector and describe the hierarchy
+//! as per-node child lists; `rows` walks that into indented lines with
+//! collapsible subtrees.
+
+/// One line of a tree
+pub struct TreeRow {
+    /// Index into the list the tree was built from
+    pub index: usize,
+    /// Tree branches drawn before the name
+    pub prefix: String,
+    /// `Some(collapsed)` for nodes with children
+    pub expandable: Option<bool>,
+}
+
+/// Lines for `roots` and their descendants, depth first, siblings in the
+/// order `children` lists them; the subtrees of nodes `collapsed` accepts are
+/// left out
+pub fn rows(
+    roots: &[usize],
+    children: &[Vec<usize>],
+    collapsed: impl Fn(usize) -> bool,
+) -> Vec<TreeRow> {
+    let mut rows = Vec::with_capacity(children.len());
+    for &root in roots {
+        push_rows(root, children, &collapsed, &mut Vec::new(), &mut rows);
+    }
+    rows
+}
+
+/// Append `index` and, unless it is collapsed, its subtree. `last` holds
+/// whether each ancestor below the root was the last of its siblings.
+fn push_rows(
+    index: usize,
+    children: &[Vec<usize>],
+    collapsed: &impl Fn(usize) -> bool,
+    last: &mut Vec<bool>,
+    rows: &mut Vec<TreeRow>,
+) {
+    let mut prefix = String::new();
+    if let Some((own, ancestors)) = last.split_last() {
+        for &ancestor_last in ancestors {
+            prefix.push_str(if ancestor_last { "   " } else { "│  " });
+        }
+        prefix.push_str(if *own { "└─ " } else { "├─ " });
+    }
+
+    let is_collapsed = collapsed(index);
+    rows.push(TreeRow {
+        index,
+        prefix,
+        expandable: (!children[index].is_empty()).then_some(is_collapsed),
+    });
+
+    if is_collapsed {
+        return;
+    }
+    for (position, &child) in children[index].iter().enumerate() {
+        last.push(position + 1 == children[index].len());
+        push_rows(child, children, collapsed, last, rows);
+        last.pop();
+    }
+}
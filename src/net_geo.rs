@@ -2,12 +2,13 @@
 //!
 //! Parses /proc/net/tcp and /proc/net/udp to get active connections,
 //! then uses MaxMind GeoLite2 database to map remote IPs to coordinates.
+//! The parsed sockets, with their inodes, are also available on their own.
 
 use maxminddb::{geoip2, Reader};
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
-use std::net::{IpAddr, Ipv4Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
@@ -49,6 +50,23 @@ impl TcpState {
         }
     }
 
+    pub fn label(&self) -> &'static str {
+        match self {
+            TcpState::Established => "ESTABLISHED",
+            TcpState::SynSent => "SYN_SENT",
+            TcpState::SynRecv => "SYN_RECV",
+            TcpState::FinWait1 => "FIN_WAIT1",
+            TcpState::FinWait2 => "FIN_WAIT2",
+            TcpState::TimeWait => "TIME_WAIT",
+            TcpState::Close => "CLOSE",
+            TcpState::CloseWait => "CLOSE_WAIT",
+            TcpState::LastAck => "LAST_ACK",
+            TcpState::Listen => "LISTEN",
+            TcpState::Closing => "CLOSING",
+            TcpState::Unknown => "UNKNOWN",
+        }
+    }
+
     /// Priority for display (higher = more important)
     pub fn priority(&self) -> u8 {
         match self {
@@ -71,6 +89,70 @@ pub enum Protocol {
     Udp,
 }
 
+impl Protocol {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+        }
+    }
+}
+
+// ============================================================================
+// Sockets
+// ============================================================================
+
+/// One line of /proc/net/tcp, /proc/net/udp or their IPv6 counterparts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Socket {
+    pub protocol: Protocol,
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    pub state: TcpState,
+    pub inode: u64,
+}
+
+impl Socket {
+    /// Parse `sl local_address rem_address st ... uid timeout inode`
+    fn parse(line: &str, protocol: Protocol) -> Option<Self> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 10 {
+            return None;
+        }
+        Some(Self {
+            protocol,
+            local: parse_hex_addr(parts[1])?,
+            remote: parse_hex_addr(parts[2])?,
+            state: TcpState::from_hex(parts[3]),
+            inode: parts[9].parse().ok()?,
+        })
+    }
+}
+
+/// Every IPv4 and IPv6 TCP and UDP socket by inode, for matching the
+/// `socket:[inode]` links in /proc/<pid>/fd
+pub fn read_sockets() -> HashMap<u64, Socket> {
+    let mut sockets = HashMap::new();
+    for (path, protocol) in [
+        ("/proc/net/tcp", Protocol::Tcp),
+        ("/proc/net/udp", Protocol::Udp),
+        ("/proc/net/tcp6", Protocol::Tcp),
+        ("/proc/net/udp6", Protocol::Udp),
+    ] {
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        sockets.extend(
+            content
+                .lines()
+                .skip(1)
+                .filter_map(|line| Socket::parse(line, protocol))
+                .map(|socket| (socket.inode, socket)),
+        );
+    }
+    sockets
+}
+
 // ============================================================================
 // GeoConnection
 // ============================================================================
@@ -242,35 +324,28 @@ impl ConnectionTracker {
     ) -> io::Result<()> {
         let content = fs::read_to_string(path)?;
 
-        for line in content.lines().skip(1) {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() < 4 {
-                continue;
-            }
-
-            // Parse remote address (parts[2])
-            let remote = parts[2];
-            let Some((rem_ip_hex, rem_port_hex)) = remote.split_once(':') else {
+        for socket in content
+            .lines()
+            .skip(1)
+            .filter_map(|line| Socket::parse(line, protocol))
+        {
+            let IpAddr::V4(remote_ip) = socket.remote.ip() else {
                 continue;
             };
 
             // Skip if no remote connection
-            if rem_ip_hex == "00000000" {
+            if remote_ip.is_unspecified() {
                 continue;
             }
 
-            let Some(remote_ip) = parse_hex_ip(rem_ip_hex) else {
-                continue;
-            };
-
             // Skip localhost and private IPs
             if is_local_or_private(remote_ip) {
                 continue;
             }
 
-            // Parse state (TCP only)
+            // State is only meaningful for TCP
             let state = if protocol == Protocol::Tcp {
-                TcpState::from_hex(parts[3])
+                socket.state
             } else {
                 TcpState::Established // UDP is connectionless
             };
@@ -280,14 +355,8 @@ impl ConnectionTracker {
                 continue;
             }
 
-            let remote_port = u16::from_str_radix(rem_port_hex, 16).unwrap_or(0);
-
-            // Parse local port
-            let local = parts[1];
-            let local_port = local
-                .split_once(':')
-                .and_then(|(_, p)| u16::from_str_radix(p, 16).ok())
-                .unwrap_or(0);
+            let remote_port = socket.remote.port();
+            let local_port = socket.local.port();
 
             let key = (remote_ip, remote_port, protocol);
             seen.insert(key);
@@ -414,14 +483,32 @@ pub struct AggregatedLocation {
 // Helper Functions
 // ============================================================================
 
-/// Parse hex IP from /proc/net format (little-endian)
-fn parse_hex_ip(hex: &str) -> Option<Ipv4Addr> {
-    let bytes = u32::from_str_radix(hex, 16).ok()?;
-    Some(Ipv4Addr::new(
-        (bytes & 0xFF) as u8,
-        ((bytes >> 8) & 0xFF) as u8,
-        ((bytes >> 16) & 0xFF) as u8,
-        ((bytes >> 24) & 0xFF) as u8,
+/// Parse hex IP from /proc/net format: one little-endian 32-bit word for
+/// IPv4, four for IPv6
+fn parse_hex_ip(hex: &str) -> Option<IpAddr> {
+    let word = |i: usize| {
+        let bytes = u32::from_str_radix(hex.get(i * 8..(i + 1) * 8)?, 16).ok()?;
+        Some(bytes.to_le_bytes())
+    };
+    match hex.len() {
+        8 => Some(IpAddr::V4(Ipv4Addr::from(word(0)?))),
+        32 => {
+            let mut octets = [0; 16];
+            for i in 0..4 {
+                octets[i * 4..(i + 1) * 4].copy_from_slice(&word(i)?);
+            }
+            Some(IpAddr::V6(Ipv6Addr::from(octets)))
+        }
+        _ => None,
+    }
+}
+
+/// Parse a hex `ip:port` pair from /proc/net format
+fn parse_hex_addr(hex: &str) -> Option<SocketAddr> {
+    let (ip, port) = hex.split_once(':')?;
+    Some(SocketAddr::new(
+        parse_hex_ip(ip)?,
+        u16::from_str_radix(port, 16).ok()?,
     ))
 }
 
@@ -456,3 +543,29 @@ fn is_local_or_private(ip: Ipv4Addr) -> bool {
 
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Protocol, Socket, TcpState};
+
+    #[test]
+    fn socket_lines_parse_addresses_state_and_inode() {
+        let line = "   1: 0100007F:0277 2201A8C0:01BB 01 00000000:00000000 00:00000000 \
+                    00000000  1000        0 48213 1 0000000000000000 20 4 30 10 -1";
+        let socket = Socket::parse(line, Protocol::Tcp).expect("valid line");
+        assert_eq!(socket.local.to_string(), "127.0.0.1:631");
+        assert_eq!(socket.remote.to_string(), "192.168.1.34:443");
+        assert_eq!(socket.state, TcpState::Established);
+        assert_eq!(socket.inode, 48213);
+
+        let line = "   0: 00000000000000000000000001000000:0277 \
+                    0000000000000000FFFF00002201A8C0:01BB 0A 00000000:00000000 00:00000000 \
+                    00000000     0        0 20817 1 0000000000000000 100 0 0 10 0";
+        let socket = Socket::parse(line, Protocol::Tcp).expect("valid tcp6 line");
+        assert_eq!(socket.local.to_string(), "[::1]:631");
+        assert_eq!(socket.remote.to_string(), "[::ffff:192.168.1.34]:443");
+        assert_eq!(socket.state, TcpState::Listen);
+        assert_eq!(socket.inode, 20817);
+        assert!(Socket::parse("  sl  local_address rem_address", Protocol::Tcp).is_none());
+    }
+}
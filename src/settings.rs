@@ -1,4 +1,5 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -12,6 +13,14 @@ pub struct Settings {
     pub sunlight: SunlightSettings,
     #[serde(default)]
     pub tui: TuiSettings,
+    #[serde(default)]
+    pub colors: ColorSettings,
+    #[serde(default)]
+    pub dash: DashSettings,
+    #[serde(default)]
+    pub alerts: AlertSettings,
+    #[serde(default)]
+    pub ps: PsSettings,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -34,6 +43,55 @@ pub struct SunlightSettings {
     pub longitude: Option<f64>, // Longitude in degrees (-180 to 180)
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct ColorSettings {
+    #[serde(default, rename = "scheme")]
+    pub schemes: Vec<SchemeSettings>, // [[colors.scheme]] entries, selectable after the built-ins
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SchemeSettings {
+    pub name: String,
+    pub stops: Vec<String>,          // "#rrggbb" gradient stops, dimmest first
+    pub bold_threshold: Option<f32>, // Intensity (0.0-1.0) at and above which text is bold
+    pub background: Option<String>,  // "#rrggbb" fill for cells without a background
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DashSettings {
+    pub rows: Vec<Vec<String>>, // Panel names per row; repeating a name widens that panel
+    pub intervals: HashMap<String, f32>, // Sampling interval in seconds per panel name
+}
+
+impl Default for DashSettings {
+    fn default() -> Self {
+        Self {
+            rows: vec![
+                vec!["cpu".into()],
+                vec!["mem".into(), "disk".into()],
+                vec!["net".into(), "io".into()],
+            ],
+            intervals: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AlertSettings {
+    pub rules: Vec<String>, // e.g. "cpu.total > 90 for 30s", checked on every monitor sample
+    pub notify: bool,       // Also send a desktop notification through notify-send
+    pub command: Option<String>, // Shell command run per alert, with TERMART_ALERT_* variables set
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PsSettings {
+    pub columns: Vec<String>, // Process list columns in order, e.g. ["pid", "user", "cpu", "command"]
+    pub sort: Option<String>, // Column sorted by at startup
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct TuiSettings {
@@ -109,9 +167,45 @@ impl Settings {
     }
 
     pub fn config_path() -> PathBuf {
+        Self::config_dir().join("config.toml")
+    }
+
+    /// Directory holding one `<name>.toml` file per imported color scheme
+    pub fn schemes_dir() -> PathBuf {
+        Self::config_dir().join("schemes")
+    }
+
+    /// Load imported schemes, sorted by file name so numbering is stable
+    pub fn imported_schemes() -> Vec<SchemeSettings> {
+        let Ok(entries) = fs::read_dir(Self::schemes_dir()) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        paths.sort();
+
+        paths
+            .iter()
+            .filter_map(|path| {
+                let parsed = fs::read_to_string(path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|content| toml::from_str(&content).map_err(|e| e.to_string()));
+                match parsed {
+                    Ok(scheme) => Some(scheme),
+                    Err(e) => {
+                        eprintln!("Warning: Skipping scheme {}: {}", path.display(), e);
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn config_dir() -> PathBuf {
         dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join("termart")
-            .join("config.toml")
     }
 }
@@ -1,19 +1,191 @@
+use crate::unicode;
 use crossterm::{
     cursor::{Hide, MoveTo, Show},
     event::{poll, read, Event, KeyCode, KeyEventKind, KeyModifiers},
     queue,
     style::{
         force_color_output, Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor,
-        SetForegroundColor,
     },
     terminal::{
         disable_raw_mode, enable_raw_mode, size, Clear, ClearType, EnterAlternateScreen,
         LeaveAlternateScreen,
     },
 };
-use std::io::{self, stdout, BufWriter, Write};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{self, stdout, Write};
+use std::sync::OnceLock;
 use std::time::Duration;
 
+thread_local! {
+    /// Active offscreen session; terminals created while it is set never touch stdout
+    static HEADLESS: RefCell<Option<HeadlessSession>> = const { RefCell::new(None) };
+}
+
+/// Color depth forced from the command line, overriding environment detection
+static COLOR_DEPTH_OVERRIDE: OnceLock<ColorDepth> = OnceLock::new();
+
+/// xterm default RGB values for the 16 standard ANSI colors, in palette order
+const ANSI16_PALETTE: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::DarkRed, (205, 0, 0)),
+    (Color::DarkGreen, (0, 205, 0)),
+    (Color::DarkYellow, (205, 205, 0)),
+    (Color::DarkBlue, (0, 0, 238)),
+    (Color::DarkMagenta, (205, 0, 205)),
+    (Color::DarkCyan, (0, 205, 205)),
+    (Color::Grey, (229, 229, 229)),
+    (Color::DarkGrey, (127, 127, 127)),
+    (Color::Red, (255, 0, 0)),
+    (Color::Green, (0, 255, 0)),
+    (Color::Yellow, (255, 255, 0)),
+    (Color::Blue, (92, 92, 255)),
+    (Color::Magenta, (255, 0, 255)),
+    (Color::Cyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Channel levels of the 6x6x6 color cube in the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Number of colors the attached terminal can display
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorDepth {
+    /// 24-bit RGB
+    #[value(name = "truecolor")]
+    TrueColor,
+    /// xterm 256-color palette
+    #[value(name = "256")]
+    Ansi256,
+    /// Standard 16 ANSI colors
+    #[value(name = "16")]
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Force a color depth for every terminal created afterwards
+    pub fn set_override(depth: ColorDepth) {
+        let _ = COLOR_DEPTH_OVERRIDE.set(depth);
+    }
+
+    /// Resolve the color depth from the CLI override or the environment
+    pub fn detect() -> Self {
+        if let Some(depth) = COLOR_DEPTH_OVERRIDE.get() {
+            return *depth;
+        }
+        Self::from_env(
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    fn from_env(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        let colorterm = colorterm.unwrap_or_default().to_ascii_lowercase();
+        let term = term.unwrap_or_default().to_ascii_lowercase();
+
+        if colorterm == "truecolor"
+            || colorterm == "24bit"
+            || term.ends_with("-direct")
+            || term.contains("truecolor")
+            || term.contains("24bit")
+        {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    /// Map a color onto the closest color this depth can display
+    pub fn adapt(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::TrueColor, _) => color,
+            (ColorDepth::Ansi256, Color::Rgb { r, g, b }) => {
+                Color::AnsiValue(rgb_to_ansi256(r, g, b))
+            }
+            (ColorDepth::Ansi256, _) => color,
+            (ColorDepth::Ansi16, Color::Rgb { r, g, b }) => nearest_ansi16(r, g, b),
+            (ColorDepth::Ansi16, Color::AnsiValue(v)) if v < 16 => ANSI16_PALETTE[v as usize].0,
+            (ColorDepth::Ansi16, Color::AnsiValue(v)) => {
+                let (r, g, b) = ansi256_to_rgb(v);
+                nearest_ansi16(r, g, b)
+            }
+            (ColorDepth::Ansi16, _) => color,
+        }
+    }
+}
+
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let cube_index = |v: u8| -> usize {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, level)| (**level as i32 - v as i32).abs())
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (cube_index(r), cube_index(g), cube_index(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+
+    // The grey ramp (232-255) runs from 8 to 238 in steps of 10
+    let avg = (r as u32 + g as u32 + b as u32) / 3;
+    let grey_index = ((avg.saturating_sub(3)) / 10).min(23) as u8;
+    let grey_level = 8 + grey_index * 10;
+
+    if distance_sq((r, g, b), (grey_level, grey_level, grey_level)) < distance_sq((r, g, b), cube) {
+        232 + grey_index
+    } else {
+        16 + (36 * ri + 6 * gi + bi) as u8
+    }
+}
+
+fn ansi256_to_rgb(v: u8) -> (u8, u8, u8) {
+    match v {
+        0..=15 => ANSI16_PALETTE[v as usize].1,
+        16..=231 => {
+            let i = (v - 16) as usize;
+            (
+                CUBE_LEVELS[i / 36],
+                CUBE_LEVELS[(i / 6) % 6],
+                CUBE_LEVELS[i % 6],
+            )
+        }
+        _ => {
+            let level = 8 + (v - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+/// RGB value a color is displayed as, or `None` for the terminal default
+pub fn color_to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Reset => None,
+        Color::Rgb { r, g, b } => Some((r, g, b)),
+        Color::AnsiValue(v) => Some(ansi256_to_rgb(v)),
+        named => ANSI16_PALETTE
+            .iter()
+            .find(|(color, _)| *color == named)
+            .map(|(_, rgb)| *rgb),
+    }
+}
+
+fn nearest_ansi16(r: u8, g: u8, b: u8) -> Color {
+    ANSI16_PALETTE
+        .iter()
+        .min_by_key(|(_, rgb)| distance_sq((r, g, b), *rgb))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::White)
+}
+
+fn distance_sq(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let dr = a.0 as i32 - b.0 as i32;
+    let dg = a.1 as i32 - b.1 as i32;
+    let db = a.2 as i32 - b.2 as i32;
+    (dr * dr + dg * dg + db * db) as u32
+}
+
 fn normalize_key(code: KeyCode, mods: KeyModifiers) -> KeyCode {
     if !mods.contains(KeyModifiers::SHIFT) {
         return code;
@@ -52,6 +224,168 @@ fn enable_visual_colors() {
     force_color_output(true);
 }
 
+/// One step of scripted input for a headless terminal
+#[cfg_attr(not(test), allow(dead_code))]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptedInput {
+    Key(KeyCode, KeyModifiers),
+    /// Report no input for this many polls
+    Idle(usize),
+    /// Change the reported terminal size
+    Resize(u16, u16),
+}
+
+/// The last frame a headless terminal presented
+#[derive(Clone, Debug, Default)]
+pub struct Frame {
+    pub cells: Vec<Vec<Cell>>,
+    pub presented: usize,
+    /// Background drawn behind cells without their own
+    pub default_bg: Option<Color>,
+    /// Time the program had slept for when the frame was presented
+    pub elapsed: Duration,
+}
+
+impl Frame {
+    /// Characters of the frame, one line per row with trailing spaces trimmed
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn text(&self) -> String {
+        self.cells
+            .iter()
+            .map(|row| {
+                let mut line = String::new();
+                for cell in row.iter().filter(|cell| !cell.is_continuation()) {
+                    line.push(cell.ch);
+                    line.extend(cell.combining.iter().map(|c| &**c));
+                }
+                line.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+struct HeadlessSession {
+    width: u16,
+    height: u16,
+    input: VecDeque<ScriptedInput>,
+    frame: Frame,
+    /// Sleeps are skipped offscreen; this adds them up instead
+    clock: Duration,
+    capture: Option<Capture>,
+}
+
+/// Frames kept by `capture_headless`
+struct Capture {
+    limit: usize,
+    frames: Vec<Frame>,
+    /// Key polls since the last frame; a program that stops drawing (a
+    /// finished bonsai waiting for a key) is done after `MAX_IDLE_POLLS`
+    idle_polls: usize,
+}
+
+/// Key polls without a new frame before a capture gives up waiting
+const MAX_IDLE_POLLS: usize = 1000;
+
+impl HeadlessSession {
+    fn new(width: u16, height: u16, input: VecDeque<ScriptedInput>) -> Self {
+        Self {
+            width,
+            height,
+            input,
+            frame: Frame::default(),
+            clock: Duration::ZERO,
+            capture: None,
+        }
+    }
+
+    /// Pop the next key. Once the script runs out, enough frames have been
+    /// captured or the program stopped drawing, every poll reports Esc so run
+    /// loops terminate.
+    fn next_key(&mut self) -> Option<(KeyCode, KeyModifiers)> {
+        if let Some(capture) = &mut self.capture {
+            if capture.frames.len() >= capture.limit || capture.idle_polls >= MAX_IDLE_POLLS {
+                return Some((KeyCode::Esc, KeyModifiers::NONE));
+            }
+            capture.idle_polls += 1;
+        }
+        loop {
+            match self.input.front_mut() {
+                None => return Some((KeyCode::Esc, KeyModifiers::NONE)),
+                Some(ScriptedInput::Idle(0)) => {
+                    self.input.pop_front();
+                }
+                Some(ScriptedInput::Idle(remaining)) => {
+                    *remaining -= 1;
+                    return None;
+                }
+                Some(ScriptedInput::Resize(width, height)) => {
+                    (self.width, self.height) = (*width, *height);
+                    self.input.pop_front();
+                    return None;
+                }
+                Some(ScriptedInput::Key(code, mods)) => {
+                    let key = (*code, *mods);
+                    self.input.pop_front();
+                    return Some(key);
+                }
+            }
+        }
+    }
+}
+
+fn with_headless<T>(f: impl FnOnce(&mut HeadlessSession) -> T) -> Option<T> {
+    HEADLESS.with(|session| session.borrow_mut().as_mut().map(f))
+}
+
+/// Run `f` with every `Terminal` it creates rendering offscreen at a fixed
+/// size, reading keys from `input`. Returns the result and the last frame.
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn run_headless<T>(
+    width: u16,
+    height: u16,
+    input: impl IntoIterator<Item = ScriptedInput>,
+    f: impl FnOnce() -> T,
+) -> (T, Frame) {
+    let (result, session) = run_session(
+        HeadlessSession::new(width, height, input.into_iter().collect()),
+        f,
+    );
+    (
+        result,
+        session.map(|session| session.frame).unwrap_or_default(),
+    )
+}
+
+/// Run `f` offscreen with no input, keeping the first `limit` frames it
+/// presents. Once they are captured, or when no new frame comes for a while,
+/// every key poll reports Esc.
+pub fn capture_headless<T>(
+    width: u16,
+    height: u16,
+    limit: usize,
+    f: impl FnOnce() -> T,
+) -> (T, Vec<Frame>) {
+    let mut session = HeadlessSession::new(width, height, [ScriptedInput::Idle(usize::MAX)].into());
+    session.capture = Some(Capture {
+        limit,
+        frames: Vec::new(),
+        idle_polls: 0,
+    });
+    let (result, session) = run_session(session, f);
+    let frames = session
+        .and_then(|session| session.capture)
+        .map(|capture| capture.frames)
+        .unwrap_or_default();
+    (result, frames)
+}
+
+fn run_session<T>(session: HeadlessSession, f: impl FnOnce() -> T) -> (T, Option<HeadlessSession>) {
+    HEADLESS.with(|slot| *slot.borrow_mut() = Some(session));
+    let result = f();
+    (result, HEADLESS.with(|slot| slot.borrow_mut().take()))
+}
+
 /// Terminal abstraction for rendering
 pub struct Terminal {
     width: u16,
@@ -59,15 +393,99 @@ pub struct Terminal {
     front_buffer: Vec<Vec<Cell>>,
     back_buffer: Vec<Vec<Cell>>,
     alternate_screen: bool,
+    color_depth: ColorDepth,
+    default_bg: Option<Color>,
+    headless: bool,
+}
+
+/// Text attributes of a cell, as a bitset
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Attrs(u8);
+
+impl Attrs {
+    pub const NONE: Attrs = Attrs(0);
+    pub const BOLD: Attrs = Attrs(1);
+    pub const DIM: Attrs = Attrs(1 << 1);
+    pub const ITALIC: Attrs = Attrs(1 << 2);
+    pub const UNDERLINE: Attrs = Attrs(1 << 3);
+    pub const REVERSE: Attrs = Attrs(1 << 4);
+
+    /// Each attribute with the SGR attribute that turns it on
+    const SGR: [(Attrs, Attribute); 5] = [
+        (Attrs::BOLD, Attribute::Bold),
+        (Attrs::DIM, Attribute::Dim),
+        (Attrs::ITALIC, Attribute::Italic),
+        (Attrs::UNDERLINE, Attribute::Underlined),
+        (Attrs::REVERSE, Attribute::Reverse),
+    ];
+
+    /// `BOLD` or nothing, for the `bold: bool` drawing calls
+    pub const fn bold(bold: bool) -> Self {
+        if bold {
+            Attrs::BOLD
+        } else {
+            Attrs::NONE
+        }
+    }
+
+    pub const fn contains(self, other: Attrs) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// `self` with each attribute in `other` flipped
+    pub const fn toggled(self, other: Attrs) -> Self {
+        Attrs(self.0 ^ other.0)
+    }
+
+    /// Attributes set in `self` but not in `other`
+    const fn without(self, other: Attrs) -> Self {
+        Attrs(self.0 & !other.0)
+    }
+
+    /// Emit the SGR codes to switch from `from` to `self`. Turning an
+    /// attribute off needs a full reset, which also clears the colors, so
+    /// this returns whether colors have to be set again.
+    fn write_transition(self, from: Attrs, out: &mut impl Write) -> io::Result<bool> {
+        let reset = !from.without(self).is_empty();
+        let added = if reset {
+            queue!(out, SetAttribute(Attribute::Reset))?;
+            self
+        } else {
+            self.without(from)
+        };
+        for (attr, sgr) in Attrs::SGR {
+            if added.contains(attr) {
+                queue!(out, SetAttribute(sgr))?;
+            }
+        }
+        Ok(reset)
+    }
+}
+
+impl std::ops::BitOr for Attrs {
+    type Output = Attrs;
+
+    fn bitor(self, rhs: Attrs) -> Attrs {
+        Attrs(self.0 | rhs.0)
+    }
 }
 
 /// A single cell in the terminal buffer
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Cell {
     pub ch: char,
     pub fg: Option<Color>,
     pub bg: Option<Color>,
-    pub bold: bool,
+    pub attrs: Attrs,
+    /// Zero-width characters (combining marks, ZWJ sequences) drawn with `ch`
+    pub combining: Option<Box<str>>,
+    /// Columns the glyph covers: 2 for wide glyphs, 0 for the cell to the
+    /// right of a wide glyph, which is drawn by it
+    pub width: u8,
 }
 
 impl Default for Cell {
@@ -76,14 +494,45 @@ impl Default for Cell {
             ch: ' ',
             fg: None,
             bg: None,
-            bold: false,
+            attrs: Attrs::NONE,
+            combining: None,
+            width: 1,
         }
     }
 }
 
+impl Cell {
+    fn new(ch: char, fg: Option<Color>, bg: Option<Color>, attrs: Attrs) -> Self {
+        Self {
+            ch,
+            fg,
+            bg,
+            attrs,
+            ..Self::default()
+        }
+    }
+
+    /// Whether this cell is covered by the wide glyph to its left
+    pub fn is_continuation(&self) -> bool {
+        self.width == 0
+    }
+
+    fn write_glyph(&self, out: &mut impl Write) -> io::Result<()> {
+        queue!(out, Print(self.ch))?;
+        if let Some(combining) = &self.combining {
+            queue!(out, Print(combining))?;
+        }
+        Ok(())
+    }
+}
+
 impl Terminal {
     /// Initialize the terminal for drawing
     pub fn new(alternate_screen: bool) -> io::Result<Self> {
+        if let Some((width, height)) = with_headless(|session| (session.width, session.height)) {
+            return Ok(Self::offscreen(width, height));
+        }
+
         enable_visual_colors();
 
         let (width, height) = size()?;
@@ -92,8 +541,12 @@ impl Terminal {
             enable_raw_mode()?;
             let mut stdout = stdout();
             if let Err(error) = (|| -> io::Result<()> {
-                queue!(stdout, EnterAlternateScreen, Hide)?;
-                stdout.flush()
+                let mut out = Vec::new();
+                queue!(out, EnterAlternateScreen, Hide)?;
+                stdout.write_all(&out)?;
+                stdout.flush()?;
+                crate::asciicast::output(width, height, &out);
+                Ok(())
             })() {
                 let _ = queue!(stdout, Show, LeaveAlternateScreen);
                 let _ = stdout.flush();
@@ -111,9 +564,85 @@ impl Terminal {
             front_buffer,
             back_buffer,
             alternate_screen,
+            color_depth: ColorDepth::detect(),
+            default_bg: None,
+            headless: false,
         })
     }
 
+    /// Terminal that only renders into its buffers, never touching stdout
+    pub fn offscreen(width: u16, height: u16) -> Self {
+        let cells = vec![vec![Cell::default(); width as usize]; height as usize];
+        Self {
+            width,
+            height,
+            front_buffer: cells.clone(),
+            back_buffer: cells,
+            alternate_screen: false,
+            color_depth: ColorDepth::TrueColor,
+            default_bg: None,
+            headless: true,
+        }
+    }
+
+    /// Whether this terminal renders offscreen
+    pub fn is_headless(&self) -> bool {
+        self.headless
+    }
+
+    /// Write pre-encoded output straight to the terminal, bypassing the
+    /// buffers. Everything the terminal emits goes through here so session
+    /// recordings see it too.
+    pub fn write_raw(&self, bytes: &[u8]) -> io::Result<()> {
+        if self.headless {
+            return Ok(());
+        }
+        let mut stdout = stdout().lock();
+        stdout.write_all(bytes)?;
+        stdout.flush()?;
+        crate::asciicast::output(self.width, self.height, bytes);
+        Ok(())
+    }
+
+    /// Current size of the attached terminal, which may differ from the
+    /// buffer size until `resize` is called
+    pub fn query_size(&self) -> io::Result<(u16, u16)> {
+        if self.headless {
+            Ok(with_headless(|session| (session.width, session.height))
+                .unwrap_or((self.width, self.height)))
+        } else {
+            size()
+        }
+    }
+
+    /// Read-only view of the back buffer, indexed `[y][x]`
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn back_buffer(&self) -> &[Vec<Cell>] {
+        &self.back_buffer
+    }
+
+    /// Copy the back buffer to the front buffer and, when headless, hand it
+    /// to the session as the latest frame
+    fn sync_front_buffer(&mut self) {
+        self.front_buffer.clone_from(&self.back_buffer);
+        if self.headless {
+            let cells = self.back_buffer.clone();
+            let default_bg = self.default_bg;
+            with_headless(|session| {
+                session.frame.cells = cells;
+                session.frame.presented += 1;
+                session.frame.default_bg = default_bg;
+                session.frame.elapsed = session.clock;
+                if let Some(capture) = &mut session.capture {
+                    if capture.frames.len() < capture.limit {
+                        capture.frames.push(session.frame.clone());
+                    }
+                    capture.idle_polls = 0;
+                }
+            });
+        }
+    }
+
     /// Get terminal dimensions
     pub fn size(&self) -> (u16, u16) {
         (self.width, self.height)
@@ -124,11 +653,29 @@ impl Terminal {
         if width != self.width || height != self.height {
             self.width = width;
             self.height = height;
+            if !self.headless {
+                crate::asciicast::resize(width, height);
+            }
             self.front_buffer = vec![vec![Cell::default(); width as usize]; height as usize];
             self.back_buffer = vec![vec![Cell::default(); width as usize]; height as usize];
         }
     }
 
+    /// Set the background drawn behind cells that have none of their own.
+    /// Changing it forces the next `present` to repaint every cell.
+    pub fn set_default_bg(&mut self, bg: Option<Color>) {
+        if bg != self.default_bg {
+            self.default_bg = bg;
+            for row in &mut self.front_buffer {
+                for cell in row {
+                    // A control character never reaches the back buffer, so
+                    // every cell compares as changed
+                    cell.ch = '\0';
+                }
+            }
+        }
+    }
+
     /// Clear the back buffer
     pub fn clear(&mut self) {
         for row in &mut self.back_buffer {
@@ -140,9 +687,9 @@ impl Terminal {
 
     /// Clear the actual terminal and both buffers
     pub fn clear_screen(&mut self) -> io::Result<()> {
-        let mut stdout = stdout();
-        queue!(stdout, Clear(ClearType::All))?;
-        stdout.flush()?;
+        let mut out = Vec::new();
+        queue!(out, Clear(ClearType::All))?;
+        self.write_raw(&out)?;
         // Reset both buffers to force full redraw
         for row in &mut self.front_buffer {
             for cell in row {
@@ -159,14 +706,7 @@ impl Terminal {
 
     /// Set a character in the back buffer
     pub fn set(&mut self, x: i32, y: i32, ch: char, fg: Option<Color>, bold: bool) {
-        if x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32 {
-            self.back_buffer[y as usize][x as usize] = Cell {
-                ch: printable_cell_char(ch),
-                fg,
-                bg: None,
-                bold,
-            };
-        }
+        self.set_with_bg(x, y, ch, fg, None, bold);
     }
 
     /// Set a character with both foreground and background color
@@ -179,29 +719,152 @@ impl Terminal {
         bg: Option<Color>,
         bold: bool,
     ) {
-        if x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32 {
-            self.back_buffer[y as usize][x as usize] = Cell {
-                ch: printable_cell_char(ch),
-                fg,
-                bg,
-                bold,
-            };
+        self.set_with_attrs(x, y, ch, fg, bg, Attrs::bold(bold));
+    }
+
+    /// Set a character with colors and any combination of text attributes
+    pub fn set_with_attrs(
+        &mut self,
+        x: i32,
+        y: i32,
+        ch: char,
+        fg: Option<Color>,
+        bg: Option<Color>,
+        attrs: Attrs,
+    ) {
+        let ch = printable_cell_char(ch);
+        let mut buf = [0; 4];
+        self.put_grapheme(x, y, ch.encode_utf8(&mut buf), fg, bg, attrs);
+    }
+
+    /// Flip `attrs` on `width` already-drawn cells from (x, y), e.g. to flash
+    /// a row in reverse video
+    pub fn toggle_attrs(&mut self, x: i32, y: i32, width: usize, attrs: Attrs) {
+        let Some(row) = usize::try_from(y)
+            .ok()
+            .and_then(|y| self.back_buffer.get_mut(y))
+        else {
+            return;
+        };
+        let start = x.max(0) as usize;
+        let end = (x + width as i32).max(0) as usize;
+        for cell in row.iter_mut().take(end).skip(start) {
+            cell.attrs = cell.attrs.toggled(attrs);
         }
     }
 
-    /// Set a string starting at position in the back buffer
+    /// Set a string starting at position in the back buffer, advancing by
+    /// each character's display width
     pub fn set_str(&mut self, x: i32, y: i32, s: &str, fg: Option<Color>, bold: bool) {
-        for (i, ch) in s.chars().enumerate() {
-            self.set(x + i as i32, y, ch, fg, bold);
+        self.set_str_with_attrs(x, y, s, fg, None, Attrs::bold(bold));
+    }
+
+    /// Set a string with colors and any combination of text attributes
+    pub fn set_str_with_attrs(
+        &mut self,
+        x: i32,
+        y: i32,
+        s: &str,
+        fg: Option<Color>,
+        bg: Option<Color>,
+        attrs: Attrs,
+    ) {
+        let mut x = x;
+        for grapheme in unicode::graphemes(s) {
+            x += self.put_grapheme(x, y, grapheme, fg, bg, attrs) as i32;
         }
     }
 
+    /// Write one grapheme cluster at (x, y) and return the columns it took.
+    /// Wide glyphs also claim the next cell, and whatever wide glyph they
+    /// partly cover is blanked so the buffer never holds half a glyph.
+    fn put_grapheme(
+        &mut self,
+        x: i32,
+        y: i32,
+        grapheme: &str,
+        fg: Option<Color>,
+        bg: Option<Color>,
+        attrs: Attrs,
+    ) -> usize {
+        let mut chars = grapheme.chars();
+        let Some(first) = chars.next() else {
+            return 0;
+        };
+        let first = printable_cell_char(first);
+        let width = unicode::grapheme_width(grapheme);
+        if width == 0 {
+            // A stray combining mark decorates the glyph to its left
+            self.attach_combining(x - 1, y, grapheme);
+            return 0;
+        }
+        if x < 0 || x >= self.width as i32 || y < 0 || y >= self.height as i32 {
+            return width;
+        }
+
+        let (x, y) = (x as usize, y as usize);
+        let fits = width == 1 || x + 1 < self.width as usize;
+        self.clear_overlap(x, y);
+        let mut cell = Cell::new(if fits { first } else { ' ' }, fg, bg, attrs);
+        if fits {
+            let rest: String = chars.map(printable_cell_char).collect();
+            cell.combining = (!rest.is_empty()).then(|| rest.into_boxed_str());
+        }
+        if width == 2 && fits {
+            self.clear_overlap(x + 1, y);
+            cell.width = 2;
+            self.back_buffer[y][x + 1] = Cell {
+                width: 0,
+                ..Cell::new(' ', fg, bg, attrs)
+            };
+        }
+        self.back_buffer[y][x] = cell;
+        width
+    }
+
+    /// Blank the other half of any wide glyph covering (x, y)
+    fn clear_overlap(&mut self, x: usize, y: usize) {
+        let row = &mut self.back_buffer[y];
+        let blank = |cell: &Cell| Cell::new(' ', cell.fg, cell.bg, cell.attrs);
+        if row[x].is_continuation() && x > 0 {
+            row[x - 1] = blank(&row[x - 1]);
+        } else if row[x].width == 2 && x + 1 < row.len() {
+            row[x + 1] = blank(&row[x + 1]);
+        }
+    }
+
+    fn attach_combining(&mut self, x: i32, y: i32, combining: &str) {
+        if y < 0 || y >= self.height as i32 || x < 0 || x >= self.width as i32 {
+            return;
+        }
+        let row = &mut self.back_buffer[y as usize];
+        let mut x = x as usize;
+        if row[x].is_continuation() && x > 0 {
+            x -= 1;
+        }
+        let mut joined = row[x]
+            .combining
+            .take()
+            .map(String::from)
+            .unwrap_or_default();
+        joined.push_str(combining);
+        row[x].combining = Some(joined.into_boxed_str());
+    }
+
     /// Render only changed cells (differential update) with single flush
     pub fn present(&mut self) -> io::Result<()> {
-        let mut stdout = BufWriter::with_capacity(32 * 1024, stdout());
+        self.set_default_bg(crate::colors::active_background());
+        if self.headless {
+            self.sync_front_buffer();
+            return Ok(());
+        }
+
+        let mut out = Vec::with_capacity(32 * 1024);
+        let depth = self.color_depth;
+        let default_bg = self.default_bg;
         let mut last_fg: Option<Color> = None;
         let mut last_bg: Option<Color> = None;
-        let mut last_bold = false;
+        let mut last_attrs = Attrs::NONE;
         let mut has_bg = false;
 
         for y in 0..self.height as usize {
@@ -209,51 +872,57 @@ impl Terminal {
                 let back = &self.back_buffer[y][x];
                 let front = &self.front_buffer[y][x];
 
+                // Continuation cells are drawn by the wide glyph to their left
+                if back.is_continuation() {
+                    self.front_buffer[y][x] = back.clone();
+                    continue;
+                }
+
                 // Skip unchanged cells
-                if back == front {
+                let wide_changed = back.width == 2
+                    && self.back_buffer[y].get(x + 1) != self.front_buffer[y].get(x + 1);
+                if back == front && !wide_changed {
                     continue;
                 }
 
                 // Move cursor
-                queue!(stdout, MoveTo(x as u16, y as u16))?;
+                queue!(out, MoveTo(x as u16, y as u16))?;
 
-                // Handle bold attribute changes
-                if back.bold != last_bold {
-                    if back.bold {
-                        queue!(stdout, SetAttribute(Attribute::Bold))?;
-                    } else {
-                        queue!(stdout, SetAttribute(Attribute::Reset))?;
+                // Handle attribute changes
+                if back.attrs != last_attrs {
+                    if back.attrs.write_transition(last_attrs, &mut out)? {
                         last_fg = None; // Reset clears colors too
                         last_bg = None;
                     }
-                    last_bold = back.bold;
+                    last_attrs = back.attrs;
                 }
 
                 // Handle foreground color changes
                 if back.fg != last_fg {
                     if let Some(color) = back.fg {
-                        queue!(stdout, SetForegroundColor(color))?;
+                        write_ansi_fg(&mut out, depth.adapt(color))?;
                     } else {
-                        queue!(stdout, ResetColor)?;
+                        queue!(out, ResetColor)?;
                         last_bg = None; // ResetColor clears both
                     }
                     last_fg = back.fg;
                 }
 
                 // Handle background color changes
-                if back.bg != last_bg {
-                    if let Some(color) = back.bg {
-                        queue!(stdout, SetBackgroundColor(color))?;
+                let bg = back.bg.or(default_bg);
+                if bg != last_bg {
+                    if let Some(color) = bg {
+                        write_ansi_bg(&mut out, depth.adapt(color))?;
                         has_bg = true;
                     } else if has_bg {
                         // Only reset bg when transitioning from bg to no-bg
-                        queue!(stdout, SetBackgroundColor(Color::Reset))?;
+                        queue!(out, SetBackgroundColor(Color::Reset))?;
                         has_bg = false;
                     }
-                    last_bg = back.bg;
+                    last_bg = bg;
                 }
 
-                queue!(stdout, Print(back.ch))?;
+                back.write_glyph(&mut out)?;
 
                 // Update front buffer
                 self.front_buffer[y][x] = back.clone();
@@ -261,82 +930,85 @@ impl Terminal {
         }
 
         // Reset attributes at end of frame
-        if last_bold || last_fg.is_some() || has_bg {
-            queue!(stdout, SetAttribute(Attribute::Reset), ResetColor)?;
+        if !last_attrs.is_empty() || last_fg.is_some() || has_bg {
+            queue!(out, SetAttribute(Attribute::Reset), ResetColor)?;
         }
 
-        stdout.flush()?;
+        self.write_raw(&out)?;
         Ok(())
     }
 
     /// Render the entire back buffer to screen (full redraw, single flush)
     pub fn render(&mut self) -> io::Result<()> {
-        let mut stdout = BufWriter::with_capacity(32 * 1024, stdout());
+        self.set_default_bg(crate::colors::active_background());
+        if self.headless {
+            self.sync_front_buffer();
+            return Ok(());
+        }
+
+        let mut out = Vec::with_capacity(32 * 1024);
+        let depth = self.color_depth;
+        let default_bg = self.default_bg;
         let mut last_fg: Option<Color> = None;
         let mut last_bg: Option<Color> = None;
-        let mut last_bold = false;
+        let mut last_attrs = Attrs::NONE;
         let mut has_bg = false;
 
-        queue!(stdout, MoveTo(0, 0))?;
+        queue!(out, MoveTo(0, 0))?;
 
         for (y, row) in self.back_buffer.iter().enumerate() {
-            queue!(stdout, MoveTo(0, y as u16))?;
+            queue!(out, MoveTo(0, y as u16))?;
 
-            for cell in row {
-                // Handle bold
-                if cell.bold != last_bold {
-                    if cell.bold {
-                        queue!(stdout, SetAttribute(Attribute::Bold))?;
-                    } else {
-                        queue!(stdout, SetAttribute(Attribute::Reset))?;
+            for cell in row.iter().filter(|cell| !cell.is_continuation()) {
+                // Handle attributes
+                if cell.attrs != last_attrs {
+                    if cell.attrs.write_transition(last_attrs, &mut out)? {
                         last_fg = None;
                         last_bg = None;
                     }
-                    last_bold = cell.bold;
+                    last_attrs = cell.attrs;
                 }
 
                 // Handle foreground color
                 if cell.fg != last_fg {
                     if let Some(color) = cell.fg {
-                        queue!(stdout, SetForegroundColor(color))?;
+                        write_ansi_fg(&mut out, depth.adapt(color))?;
                     } else {
-                        queue!(stdout, ResetColor)?;
+                        queue!(out, ResetColor)?;
                         last_bg = None;
                     }
                     last_fg = cell.fg;
                 }
 
                 // Handle background color
-                if cell.bg != last_bg {
-                    if let Some(color) = cell.bg {
-                        queue!(stdout, SetBackgroundColor(color))?;
+                let bg = cell.bg.or(default_bg);
+                if bg != last_bg {
+                    if let Some(color) = bg {
+                        write_ansi_bg(&mut out, depth.adapt(color))?;
                         has_bg = true;
                     } else if has_bg {
-                        queue!(stdout, SetBackgroundColor(Color::Reset))?;
+                        queue!(out, SetBackgroundColor(Color::Reset))?;
                         has_bg = false;
                     }
-                    last_bg = cell.bg;
+                    last_bg = bg;
                 }
 
-                queue!(stdout, Print(cell.ch))?;
+                cell.write_glyph(&mut out)?;
             }
         }
 
-        queue!(stdout, SetAttribute(Attribute::Reset), ResetColor)?;
-        stdout.flush()?;
-
-        // Sync front buffer
-        for y in 0..self.height as usize {
-            for x in 0..self.width as usize {
-                self.front_buffer[y][x] = self.back_buffer[y][x].clone();
-            }
-        }
+        queue!(out, SetAttribute(Attribute::Reset), ResetColor)?;
+        self.write_raw(&out)?;
 
+        self.sync_front_buffer();
         Ok(())
     }
 
     /// Check for keypress (non-blocking), returns (code, modifiers)
     pub fn check_key(&self) -> io::Result<Option<(KeyCode, crossterm::event::KeyModifiers)>> {
+        if self.headless {
+            return Ok(with_headless(HeadlessSession::next_key).flatten());
+        }
         if poll(Duration::from_millis(0))? {
             if let Event::Key(key_event) = read()? {
                 if is_key_action(key_event.kind) {
@@ -350,6 +1022,11 @@ impl Terminal {
 
     /// Wait for a keypress with timeout
     pub fn wait_key(&self, timeout_ms: u64) -> io::Result<Option<KeyCode>> {
+        if self.headless {
+            return Ok(with_headless(HeadlessSession::next_key)
+                .flatten()
+                .map(|(code, _)| code));
+        }
         if poll(Duration::from_millis(timeout_ms))? {
             if let Event::Key(key_event) = read()? {
                 if is_key_action(key_event.kind) {
@@ -363,36 +1040,45 @@ impl Terminal {
 
     /// Sleep for specified duration
     pub fn sleep(&self, seconds: f32) {
+        if self.headless {
+            with_headless(|session| session.clock += Duration::from_secs_f32(seconds.max(0.0)));
+            return;
+        }
         std::thread::sleep(Duration::from_secs_f32(seconds));
     }
 
     /// Print buffer to stdout with ANSI colors (for print mode)
-    pub fn print_to_stdout(&self) {
-        let mut out = BufWriter::new(stdout());
+    pub fn print_to_stdout(&mut self) {
+        if self.headless {
+            self.sync_front_buffer();
+            return;
+        }
+
+        let mut out = Vec::new();
+        let depth = self.color_depth;
         for row in &self.back_buffer {
-            for cell in row {
-                if cell.ch == ' ' && cell.bg.is_none() {
+            for cell in row.iter().filter(|cell| !cell.is_continuation()) {
+                if cell.ch == ' ' && cell.bg.is_none() && cell.attrs.is_empty() {
                     let _ = write!(out, " ");
                     continue;
                 }
 
-                if cell.bold {
-                    let _ = write!(out, "\x1b[1m");
-                }
+                let _ = cell.attrs.write_transition(Attrs::NONE, &mut out);
 
                 if let Some(color) = cell.fg {
-                    let _ = write_ansi_fg(&mut out, color);
+                    let _ = write_ansi_fg(&mut out, depth.adapt(color));
                 }
 
                 if let Some(color) = cell.bg {
-                    let _ = write_ansi_bg(&mut out, color);
+                    let _ = write_ansi_bg(&mut out, depth.adapt(color));
                 }
 
-                let _ = write!(out, "{}\x1b[0m", cell.ch);
+                let _ = cell.write_glyph(&mut out);
+                let _ = write!(out, "\x1b[0m");
             }
             let _ = writeln!(out);
         }
-        let _ = out.flush();
+        let _ = self.write_raw(&out);
     }
 }
 
@@ -424,7 +1110,7 @@ fn write_ansi_fg(out: &mut impl Write, color: Color) -> io::Result<()> {
         Color::Magenta => write!(out, "\x1b[95m"),
         Color::Cyan => write!(out, "\x1b[96m"),
         Color::White => write!(out, "\x1b[97m"),
-        _ => Ok(()),
+        Color::Reset => write!(out, "\x1b[39m"),
     }
 }
 
@@ -448,24 +1134,55 @@ fn write_ansi_bg(out: &mut impl Write, color: Color) -> io::Result<()> {
         Color::Magenta => write!(out, "\x1b[105m"),
         Color::Cyan => write!(out, "\x1b[106m"),
         Color::White => write!(out, "\x1b[107m"),
-        _ => Ok(()),
+        Color::Reset => write!(out, "\x1b[49m"),
     }
 }
 
 impl Drop for Terminal {
     fn drop(&mut self) {
         if self.alternate_screen {
-            let mut stdout = stdout();
-            let _ = queue!(stdout, Show, LeaveAlternateScreen);
-            let _ = stdout.flush();
+            let mut out = Vec::new();
+            let _ = queue!(out, Show, LeaveAlternateScreen);
+            let _ = self.write_raw(&out);
             let _ = disable_raw_mode();
         }
     }
 }
 
+/// Compare `actual` with the golden file `tests/snapshots/<name>.txt`.
+/// Set `UPDATE_SNAPSHOTS=1` to rewrite the golden file instead.
+#[cfg(test)]
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.txt"));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(path.parent().expect("snapshot dir")).expect("create snapshot dir");
+        std::fs::write(&path, format!("{actual}\n")).expect("write snapshot");
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "missing snapshot {} ({e}); run with UPDATE_SNAPSHOTS=1",
+            path.display()
+        )
+    });
+    // Golden files end with a single newline, so trailing blank rows compare
+    // by their absence
+    assert_eq!(
+        expected.trim_end_matches('\n'),
+        actual.trim_end_matches('\n'),
+        "frame differs from {}",
+        path.display()
+    );
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{enable_visual_colors, is_key_action, normalize_key, Cell, Terminal};
+    use super::{
+        enable_visual_colors, is_key_action, normalize_key, rgb_to_ansi256, run_headless,
+        write_ansi_bg, write_ansi_fg, Attrs, Cell, ColorDepth, ScriptedInput, Terminal,
+    };
     use crossterm::{
         event::{KeyCode, KeyEventKind, KeyModifiers},
         style::{force_color_output, Color, SetForegroundColor},
@@ -519,6 +1236,72 @@ mod tests {
         assert_eq!(SetForegroundColor(Color::Red).to_string(), "\u{1b}[38;5;9m");
     }
 
+    #[test]
+    fn wide_glyphs_take_two_cells_and_overwrites_never_leave_half_a_glyph() {
+        let mut terminal = Terminal::offscreen(8, 1);
+        terminal.set_str(0, 0, "木e\u{301}🇯🇵x", None, false);
+
+        let row = &terminal.back_buffer[0];
+        assert_eq!((row[0].ch, row[0].width), ('木', 2));
+        assert!(row[1].is_continuation());
+        assert_eq!(row[2].ch, 'e');
+        assert_eq!(row[2].combining.as_deref(), Some("\u{301}"));
+        assert_eq!(row[3].combining.as_deref(), Some("\u{1f1f5}"));
+        assert!(row[4].is_continuation());
+        assert_eq!(row[5].ch, 'x');
+
+        // Writing over either half of a wide glyph blanks the other half
+        terminal.set(1, 0, 'a', None, false);
+        terminal.set(4, 0, 'b', None, false);
+        let text: String = terminal.back_buffer[0].iter().map(|cell| cell.ch).collect();
+        assert_eq!(text, " ae bx  ");
+        assert!(terminal.back_buffer[0].iter().all(|cell| cell.width == 1));
+
+        // A wide glyph that does not fit in the last column becomes a space
+        terminal.set(7, 0, '木', None, false);
+        assert_eq!(
+            (
+                terminal.back_buffer[0][7].ch,
+                terminal.back_buffer[0][7].width
+            ),
+            (' ', 1)
+        );
+    }
+
+    #[test]
+    fn attribute_changes_only_reset_when_an_attribute_turns_off() {
+        let transition = |from: Attrs, to: Attrs| {
+            let mut out = Vec::new();
+            let reset = to.write_transition(from, &mut out).expect("write sgr");
+            (String::from_utf8(out).expect("utf8 sgr"), reset)
+        };
+
+        assert_eq!(
+            transition(Attrs::BOLD, Attrs::BOLD | Attrs::UNDERLINE),
+            ("\x1b[4m".to_string(), false)
+        );
+        assert_eq!(
+            transition(Attrs::BOLD | Attrs::REVERSE, Attrs::REVERSE | Attrs::ITALIC),
+            ("\x1b[0m\x1b[3m\x1b[7m".to_string(), true)
+        );
+        assert_eq!(
+            transition(Attrs::DIM, Attrs::NONE),
+            ("\x1b[0m".to_string(), true)
+        );
+
+        let mut terminal = Terminal::offscreen(4, 1);
+        terminal.set_str_with_attrs(0, 0, "ab", None, None, Attrs::REVERSE);
+        terminal.set(1, 0, 'c', None, true);
+        let attrs: Vec<Attrs> = terminal.back_buffer[0]
+            .iter()
+            .map(|cell| cell.attrs)
+            .collect();
+        assert_eq!(
+            attrs,
+            [Attrs::REVERSE, Attrs::BOLD, Attrs::NONE, Attrs::NONE]
+        );
+    }
+
     #[test]
     fn terminal_cells_replace_control_characters() {
         let cells = vec![vec![Cell::default(); 3]];
@@ -528,6 +1311,9 @@ mod tests {
             front_buffer: cells.clone(),
             back_buffer: cells,
             alternate_screen: false,
+            color_depth: ColorDepth::TrueColor,
+            default_bg: None,
+            headless: false,
         };
 
         terminal.set_str(0, 0, "\u{1b}\nA", None, false);
@@ -536,6 +1322,103 @@ mod tests {
         assert_eq!(terminal.back_buffer[0][1].ch, '�');
         assert_eq!(terminal.back_buffer[0][2].ch, 'A');
     }
+
+    #[test]
+    fn headless_terminals_script_input_and_capture_frames() {
+        let script = [
+            ScriptedInput::Idle(1),
+            ScriptedInput::Key(KeyCode::Char('x'), KeyModifiers::NONE),
+            ScriptedInput::Resize(4, 2),
+        ];
+        let (keys, frame) = run_headless(6, 3, script, || {
+            let mut term = Terminal::new(true).expect("headless terminal");
+            assert!(term.is_headless());
+            assert_eq!(term.size(), (6, 3));
+
+            let mut keys = Vec::new();
+            for _ in 0..4 {
+                keys.push(
+                    term.check_key()
+                        .expect("scripted key")
+                        .map(|(code, _)| code),
+                );
+            }
+            let (w, h) = term.query_size().expect("headless size");
+            term.resize(w, h);
+            term.set_str(0, 1, "ok", None, false);
+            assert_eq!(term.back_buffer()[1][1].ch, 'k');
+            term.present().expect("offscreen present");
+            keys
+        });
+
+        assert_eq!(
+            keys,
+            [None, Some(KeyCode::Char('x')), None, Some(KeyCode::Esc)]
+        );
+        assert_eq!(frame.presented, 1);
+        assert_eq!(frame.text(), "\nok");
+    }
+
+    #[test]
+    fn color_depth_is_detected_from_colorterm_and_term() {
+        assert_eq!(
+            ColorDepth::from_env(Some("truecolor"), Some("xterm-256color")),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::from_env(None, Some("xterm-direct")),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::from_env(None, Some("screen-256color")),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(
+            ColorDepth::from_env(None, Some("linux")),
+            ColorDepth::Ansi16
+        );
+        assert_eq!(ColorDepth::from_env(None, None), ColorDepth::Ansi16);
+    }
+
+    #[test]
+    fn rgb_colors_are_downsampled_to_the_available_palette() {
+        let orange = Color::Rgb {
+            r: 255,
+            g: 135,
+            b: 0,
+        };
+        assert_eq!(ColorDepth::TrueColor.adapt(orange), orange);
+        assert_eq!(ColorDepth::Ansi256.adapt(orange), Color::AnsiValue(208));
+        assert_eq!(
+            ColorDepth::Ansi16.adapt(Color::Rgb { r: 0, g: 0, b: 0 }),
+            Color::Black
+        );
+        assert_eq!(
+            ColorDepth::Ansi16.adapt(Color::Rgb {
+                r: 250,
+                g: 10,
+                b: 5
+            }),
+            Color::Red
+        );
+        assert_eq!(ColorDepth::Ansi16.adapt(Color::AnsiValue(10)), Color::Green);
+        assert_eq!(ColorDepth::Ansi16.adapt(Color::Cyan), Color::Cyan);
+    }
+
+    #[test]
+    fn greys_map_onto_the_256_color_grey_ramp() {
+        assert_eq!(rgb_to_ansi256(128, 128, 128), 244);
+        assert_eq!(rgb_to_ansi256(0, 0, 0), 16);
+        assert_eq!(rgb_to_ansi256(255, 255, 255), 231);
+    }
+
+    #[test]
+    fn reset_colors_restore_the_terminal_defaults() {
+        let mut out = Vec::new();
+        write_ansi_fg(&mut out, Color::Reset).unwrap();
+        write_ansi_bg(&mut out, Color::Reset).unwrap();
+        assert_eq!(out, b"\x1b[39m\x1b[49m");
+    }
 }
 
 /// Predefined colors for bonsai (using standard terminal colors)
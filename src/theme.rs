@@ -0,0 +1,372 @@
+//! Terminal theme import
+//!
+//! Converts base16 YAML, Alacritty TOML and iTerm2 `.itermcolors` palettes
+//! into termart color schemes saved under the config directory.
+
+use crate::colors::{parse_hex_color, CustomScheme};
+use crate::settings::{SchemeSettings, Settings};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Supported theme file formats
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ThemeFormat {
+    Base16,
+    Alacritty,
+    Iterm,
+}
+
+impl ThemeFormat {
+    /// Guess the format from the file extension, falling back to content sniffing
+    fn detect(path: &Path, content: &str) -> Option<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml" | "yml") => return Some(ThemeFormat::Base16),
+            Some("toml") => return Some(ThemeFormat::Alacritty),
+            Some("itermcolors" | "plist") => return Some(ThemeFormat::Iterm),
+            _ => {}
+        }
+        if content.contains("<plist") {
+            Some(ThemeFormat::Iterm)
+        } else if content.contains("base0D") {
+            Some(ThemeFormat::Base16)
+        } else if content.contains("[colors") {
+            Some(ThemeFormat::Alacritty)
+        } else {
+            None
+        }
+    }
+}
+
+/// The palette entries a termart scheme is built from
+#[derive(Debug, PartialEq)]
+struct ThemePalette {
+    name: Option<String>,
+    background: (u8, u8, u8),
+    foreground: (u8, u8, u8),
+    bright_black: (u8, u8, u8),
+    blue: (u8, u8, u8),
+    cyan: (u8, u8, u8),
+}
+
+impl ThemePalette {
+    /// Ramp from the theme's muted color through its accents to the foreground,
+    /// drawn over the theme background
+    fn into_scheme(self, name: String) -> SchemeSettings {
+        let hex = |(r, g, b): (u8, u8, u8)| format!("#{r:02x}{g:02x}{b:02x}");
+        SchemeSettings {
+            name,
+            stops: vec![
+                hex(self.bright_black),
+                hex(self.blue),
+                hex(self.cyan),
+                hex(self.foreground),
+            ],
+            bold_threshold: None,
+            background: Some(hex(self.background)),
+        }
+    }
+}
+
+/// Import a theme file and save it as a scheme. Returns the saved path. An
+/// existing scheme of the same name is only replaced when `force` is set.
+pub fn import(
+    path: &Path,
+    name: Option<String>,
+    format: Option<ThemeFormat>,
+    force: bool,
+) -> io::Result<PathBuf> {
+    let content = fs::read_to_string(path)?;
+    let format = format
+        .or_else(|| ThemeFormat::detect(path, &content))
+        .ok_or_else(|| invalid("unrecognized theme format; pass --format"))?;
+
+    let palette = match format {
+        ThemeFormat::Base16 => parse_base16(&content),
+        ThemeFormat::Alacritty => parse_alacritty(&content),
+        ThemeFormat::Iterm => parse_iterm(&content),
+    }
+    .map_err(invalid)?;
+
+    let file_stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("imported")
+        .to_string();
+    let name = name.or_else(|| palette.name.clone()).unwrap_or(file_stem);
+    let scheme = palette.into_scheme(name);
+    // Validate with the same rules the scheme loader applies
+    CustomScheme::from_settings(&scheme).map_err(invalid)?;
+
+    let dir = Settings::schemes_dir();
+    fs::create_dir_all(&dir)?;
+    let target = dir.join(format!("{}.toml", slug(&scheme.name)));
+    let serialized = toml::to_string(&scheme).map_err(|e| invalid(e.to_string()))?;
+    save(&target, &serialized, force)?;
+    Ok(target)
+}
+
+/// Write `content` to `target`, refusing to replace an existing file unless
+/// `force` is set
+fn save(target: &Path, content: &str, force: bool) -> io::Result<()> {
+    if force {
+        return fs::write(target, content);
+    }
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(target)
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::AlreadyExists => io::Error::new(
+                e.kind(),
+                format!(
+                    "{} already exists; pass --force to replace it",
+                    target.display()
+                ),
+            ),
+            _ => e,
+        })?;
+    file.write_all(content.as_bytes())
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn slug(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "imported".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Parse `#rrggbb`, `rrggbb` or Alacritty's `0xrrggbb`
+fn parse_color(value: &str) -> Option<(u8, u8, u8)> {
+    let value = value.trim();
+    let value = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .unwrap_or(value);
+    parse_hex_color(value)
+}
+
+/// base16 schemes are flat `key: "value"` YAML, optionally nested under
+/// `palette:` in the newer tinted-theming layout
+fn parse_base16(content: &str) -> Result<ThemePalette, String> {
+    let mut name = None;
+    let mut colors = std::collections::HashMap::new();
+
+    for line in content.lines() {
+        let line = line.split(" #").next().unwrap_or_default().trim();
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+        match key.trim() {
+            "scheme" | "name" if !value.is_empty() => name = Some(value.to_string()),
+            key if key.starts_with("base0") && key.len() == 6 => {
+                if let Some(color) = parse_color(value) {
+                    colors.insert(key.to_ascii_lowercase(), color);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let get = |key: &str| {
+        colors
+            .get(key)
+            .copied()
+            .ok_or_else(|| format!("base16 theme is missing {key}"))
+    };
+    Ok(ThemePalette {
+        name,
+        background: get("base00")?,
+        foreground: get("base05")?,
+        bright_black: get("base03")?,
+        blue: get("base0d")?,
+        cyan: get("base0c")?,
+    })
+}
+
+fn parse_alacritty(content: &str) -> Result<ThemePalette, String> {
+    let value: toml::Value = toml::from_str(content).map_err(|e| e.to_string())?;
+    let colors = value
+        .get("colors")
+        .ok_or("Alacritty theme has no [colors] section")?;
+    let get = |section: &str, key: &str| {
+        colors
+            .get(section)
+            .and_then(|table| table.get(key))
+            .and_then(|color| color.as_str())
+            .and_then(parse_color)
+            .ok_or_else(|| format!("Alacritty theme is missing colors.{section}.{key}"))
+    };
+
+    Ok(ThemePalette {
+        name: None,
+        background: get("primary", "background")?,
+        foreground: get("primary", "foreground")?,
+        bright_black: get("bright", "black")?,
+        blue: get("normal", "blue")?,
+        cyan: get("normal", "cyan")?,
+    })
+}
+
+/// `.itermcolors` files are XML plists mapping names such as `Ansi 4 Color`
+/// to dicts of floating-point `Red/Green/Blue Component` values
+fn parse_iterm(content: &str) -> Result<ThemePalette, String> {
+    let get = |key: &str| {
+        iterm_color(content, key).ok_or_else(|| format!("iTerm theme is missing {key}"))
+    };
+    Ok(ThemePalette {
+        name: None,
+        background: get("Background Color")?,
+        foreground: get("Foreground Color")?,
+        bright_black: get("Ansi 8 Color")?,
+        blue: get("Ansi 4 Color")?,
+        cyan: get("Ansi 6 Color")?,
+    })
+}
+
+fn iterm_color(content: &str, key: &str) -> Option<(u8, u8, u8)> {
+    let start = content.find(&format!("<key>{key}</key>"))?;
+    let rest = &content[start..];
+    let dict_start = rest.find("<dict>")?;
+    let dict_end = rest[dict_start..].find("</dict>")? + dict_start;
+    let dict = &rest[dict_start..dict_end];
+
+    let component = |name: &str| -> Option<u8> {
+        let after_key = &dict[dict.find(&format!("<key>{name} Component</key>"))?..];
+        let open = after_key.find("<real>")? + "<real>".len();
+        let close = after_key[open..].find("</real>")? + open;
+        let value: f32 = after_key[open..close].trim().parse().ok()?;
+        Some((value.clamp(0.0, 1.0) * 255.0).round() as u8)
+    };
+    Some((component("Red")?, component("Green")?, component("Blue")?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_alacritty, parse_base16, parse_iterm, save, slug, ThemeFormat, ThemePalette,
+    };
+    use std::path::Path;
+
+    #[test]
+    fn base16_yaml_maps_accents_onto_a_scheme() {
+        let yaml = r#"
+scheme: "Tomorrow Night"
+author: "Chris Kempson"
+base00: "1d1f21" # background
+base03: "969896"
+base05: "c5c8c6"
+base0C: "8abeb7"
+base0D: "81a2be"
+"#;
+        let palette = parse_base16(yaml).expect("valid base16");
+        assert_eq!(
+            palette,
+            ThemePalette {
+                name: Some("Tomorrow Night".to_string()),
+                background: (0x1d, 0x1f, 0x21),
+                foreground: (0xc5, 0xc8, 0xc6),
+                bright_black: (0x96, 0x98, 0x96),
+                blue: (0x81, 0xa2, 0xbe),
+                cyan: (0x8a, 0xbe, 0xb7),
+            }
+        );
+
+        let scheme = palette.into_scheme("Night".to_string());
+        assert_eq!(scheme.stops, ["#969896", "#81a2be", "#8abeb7", "#c5c8c6"]);
+        assert_eq!(scheme.background.as_deref(), Some("#1d1f21"));
+    }
+
+    #[test]
+    fn alacritty_toml_accepts_hex_and_0x_colors() {
+        let toml = r##"
+[colors.primary]
+background = "0x282c34"
+foreground = "#abb2bf"
+
+[colors.normal]
+blue = "#61afef"
+cyan = "#56b6c2"
+
+[colors.bright]
+black = "#5c6370"
+"##;
+        let palette = parse_alacritty(toml).expect("valid alacritty theme");
+        assert_eq!(palette.background, (0x28, 0x2c, 0x34));
+        assert_eq!(palette.bright_black, (0x5c, 0x63, 0x70));
+        assert!(parse_alacritty("[colors.primary]\nbackground = \"#000000\"").is_err());
+    }
+
+    #[test]
+    fn itermcolors_plist_components_are_scaled_to_bytes() {
+        let color = |key: &str, r: f32, g: f32, b: f32| {
+            format!(
+                "<key>{key}</key>\n<dict>\n<key>Blue Component</key>\n<real>{b}</real>\n\
+                 <key>Green Component</key>\n<real>{g}</real>\n\
+                 <key>Red Component</key>\n<real>{r}</real>\n</dict>\n"
+            )
+        };
+        let plist = format!(
+            "<plist version=\"1.0\"><dict>{}{}{}{}{}</dict></plist>",
+            color("Ansi 4 Color", 0.0, 0.0, 1.0),
+            color("Ansi 6 Color", 0.0, 1.0, 1.0),
+            color("Ansi 8 Color", 0.5, 0.5, 0.5),
+            color("Background Color", 0.0, 0.0, 0.0),
+            color("Foreground Color", 1.0, 1.0, 1.0),
+        );
+        let palette = parse_iterm(&plist).expect("valid itermcolors");
+        assert_eq!(palette.blue, (0, 0, 255));
+        assert_eq!(palette.bright_black, (128, 128, 128));
+        assert_eq!(palette.foreground, (255, 255, 255));
+    }
+
+    #[test]
+    fn formats_are_detected_and_names_slugged() {
+        assert_eq!(
+            ThemeFormat::detect(Path::new("a.itermcolors"), ""),
+            Some(ThemeFormat::Iterm)
+        );
+        assert_eq!(
+            ThemeFormat::detect(Path::new("theme"), "base0D: \"000000\""),
+            Some(ThemeFormat::Base16)
+        );
+        assert_eq!(ThemeFormat::detect(Path::new("theme"), "nothing"), None);
+        assert_eq!(slug("Tomorrow Night!"), "tomorrow-night");
+        assert_eq!(slug("***"), "imported");
+    }
+
+    #[test]
+    fn existing_schemes_are_only_replaced_when_forced() {
+        let path = std::env::temp_dir().join(format!(
+            "termart-theme-{}-{}.toml",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        save(&path, "first", false).expect("new scheme should be saved");
+        let error = save(&path, "second", false).expect_err("existing scheme kept");
+        assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+        assert!(error.to_string().contains("--force"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first");
+
+        save(&path, "second", true).expect("forced save should replace");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -16,7 +16,7 @@
 //! - Space: Pause
 //! - q/Esc: Quit
 
-use super::{scheme_color, VizState};
+use super::{scheme_ramp, VizState};
 use crate::config::FractalConfig;
 use crate::terminal::Terminal;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
@@ -291,13 +291,16 @@ impl BarDecayState {
         self.visual_height = self.visual_height.max(hit_height as f32);
     }
 
-    /// Get the tier for a specific level (-1 if collapsed)
-    fn tier_at(&self, level: usize) -> i8 {
-        if level < self.max_levels {
-            self.level_tiers[level]
-        } else {
-            -1
+    /// Get the continuous color intensity for a level (0.0 = collapsed or
+    /// about to collapse, 1.0 = fresh hit), blending each tier's remaining
+    /// decay time so colors fade smoothly instead of stepping per tier
+    fn intensity_at(&self, level: usize) -> f32 {
+        if level >= self.max_levels || self.level_tiers[level] < 0 {
+            return 0.0;
         }
+        let tier = self.level_tiers[level];
+        let remaining = (self.level_timers[level] / decay_time_for_tier(tier)).clamp(0.0, 1.0);
+        (tier as f32 + remaining) / NUM_TIERS as f32
     }
 
     /// Get the smooth visual height for rendering
@@ -419,17 +422,18 @@ fn process_channel_spectrum(
     }
 }
 
-/// Get color for a bar cell based on color scheme, position, and decay tier
-/// tier: 0-3 where 3 is brightest (newest hit), 0 is dimmest (about to collapse)
-fn get_bar_color(color_scheme: u8, x_ratio: f32, tier: i8, is_peak: bool) -> (Color, bool) {
+/// Get color for a bar cell based on color scheme, position, and decay intensity
+/// intensity: 0.0-1.0 where 1.0 is brightest (newest hit), 0.0 is dimmest (about to collapse)
+fn get_bar_color(color_scheme: u8, x_ratio: f32, intensity: f32, is_peak: bool) -> (Color, bool) {
     if color_scheme == RAINBOW_SCHEME {
-        // Rainbow mode: color based on X position, bold for high tiers
-        (rainbow_color(x_ratio), tier >= 2)
+        // Rainbow mode: color based on X position, bold for the upper half of the decay
+        (rainbow_color(x_ratio), intensity >= 0.5)
     } else {
-        // Standard schemes: color intensity based on decay tier
-        // tier 3 = brightest, tier 0 = dimmest
-        let intensity = tier.max(0) as u8;
-        scheme_color(color_scheme, intensity.min(3), is_peak)
+        // Standard schemes: sample the scheme ramp at the decay intensity
+        (
+            scheme_ramp(color_scheme, intensity),
+            intensity >= 0.75 || (is_peak && intensity >= 0.5),
+        )
     }
 }
 
@@ -622,7 +626,7 @@ enum BarDirection {
 /// Render a single bar with sub-cell resolution using partial block characters.
 ///
 /// Draws both full blocks and a partial block at the tip for smooth animation.
-/// Color is determined by the decay tier at each level.
+/// Color is determined by the decay intensity at each level.
 fn render_channel_bar(
     term: &mut Terminal,
     decay_state: &BarDecayState,
@@ -643,12 +647,11 @@ fn render_channel_bar(
 
     // Full blocks
     for y in 0..full_blocks.min(max_height) {
-        let tier = decay_state.tier_at(y);
-        let render_tier = if tier < 0 { 0 } else { tier };
+        let intensity = decay_state.intensity_at(y);
 
         let y_ratio = y as f32 / half_height;
         let is_peak = y_ratio > PEAK_THRESHOLD;
-        let (color, bold) = get_bar_color(color_scheme, x_ratio, render_tier, is_peak);
+        let (color, bold) = get_bar_color(color_scheme, x_ratio, intensity, is_peak);
 
         let screen_y = match direction {
             BarDirection::Up => center_y - 1 - y as i32,
@@ -669,11 +672,10 @@ fn render_channel_bar(
 
     // Partial block at tip
     if frac > 0.0 && full_blocks < max_height {
-        let tier = decay_state.tier_at(full_blocks);
-        let render_tier = if tier < 0 { 0 } else { tier };
+        let intensity = decay_state.intensity_at(full_blocks);
         let char_idx = (frac * (bar_chars.len() - 1) as f32).round() as usize;
         let char_idx = char_idx.min(bar_chars.len() - 1);
-        let (color, bold) = get_bar_color(color_scheme, x_ratio, render_tier, false);
+        let (color, bold) = get_bar_color(color_scheme, x_ratio, intensity, false);
 
         let screen_y = match direction {
             BarDirection::Up => center_y - 1 - full_blocks as i32,
@@ -900,7 +902,7 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
     let bar_chars_upper = ['▔', '▀', '█'];
 
     loop {
-        let (width, height) = crossterm::terminal::size().unwrap_or(term.size());
+        let (width, height) = term.query_size().unwrap_or(term.size());
 
         // Calculate frame delta time for decay updates
         let now = std::time::Instant::now();
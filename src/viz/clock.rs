@@ -6,7 +6,6 @@ use crate::terminal::Terminal;
 use chrono::{Datelike, Local, Timelike};
 use crossterm::event::KeyCode;
 use crossterm::style::Color;
-use crossterm::terminal::size;
 use std::env;
 use std::fs;
 use std::io;
@@ -440,7 +439,7 @@ pub fn run(mut config: ClockConfig) -> io::Result<()> {
         }
 
         // Handle resize
-        if let Ok((new_w, new_h)) = size() {
+        if let Ok((new_w, new_h)) = term.query_size() {
             if new_w != w || new_h != h {
                 w = new_w;
                 h = new_h;
@@ -8,7 +8,6 @@ use crate::viz::usage::{
 use crate::viz::VizState;
 use crossterm::event::KeyCode;
 use crossterm::style::Color;
-use crossterm::terminal::size;
 use serde::Deserialize;
 use std::io;
 use std::path::PathBuf;
@@ -309,7 +308,7 @@ pub fn run(config: CodexTokenConfig) -> io::Result<()> {
             }
         }
 
-        if let Ok((new_width, new_height)) = size() {
+        if let Ok((new_width, new_height)) = term.query_size() {
             if new_width != width || new_height != height {
                 width = new_width;
                 height = new_height;
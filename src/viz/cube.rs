@@ -1,6 +1,7 @@
 //! 3D rotating cube effect using braille characters
 
 use super::{scheme_color, VizState};
+use crate::canvas::{Canvas, CanvasMode};
 use crate::config::FractalConfig;
 use crate::terminal::Terminal;
 use std::io;
@@ -52,22 +53,18 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
     let mut prev_w = init_w;
     let mut prev_h = init_h;
 
-    let mut braille_w = init_w as usize * 2;
-    let mut braille_h = init_h as usize * 4;
-    let mut braille_dots: Vec<Vec<bool>> = vec![vec![false; braille_w]; braille_h];
+    let mut canvas = Canvas::new(CanvasMode::Braille, init_w, init_h);
     let mut projected: Vec<(f32, f32)> = vec![(0.0, 0.0); 8];
 
     loop {
-        let (width, height) = crossterm::terminal::size().unwrap_or(term.size());
+        let (width, height) = term.query_size().unwrap_or(term.size());
 
         if width != prev_w || height != prev_h {
             term.resize(width, height);
             term.clear_screen()?;
             prev_w = width;
             prev_h = height;
-            braille_w = width as usize * 2;
-            braille_h = height as usize * 4;
-            braille_dots = vec![vec![false; braille_w]; braille_h];
+            canvas.resize(width, height);
         }
 
         let w = width as f32;
@@ -87,9 +84,7 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
             continue;
         }
 
-        for row in &mut braille_dots {
-            row.fill(false);
-        }
+        canvas.clear();
 
         let rx = time * ROTATION_X_SPEED;
         let ry = time * ROTATION_Y_SPEED;
@@ -118,85 +113,17 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
             let (x0, y0) = projected[v1];
             let (x1, y1) = projected[v2];
 
-            let bx0 = (x0 * 2.0) as i32;
-            let by0 = (y0 * 4.0) as i32;
-            let bx1 = (x1 * 2.0) as i32;
-            let by1 = (y1 * 4.0) as i32;
-
-            let dx = (bx1 - bx0).abs();
-            let dy = -(by1 - by0).abs();
-            let sx = if bx0 < bx1 { 1 } else { -1 };
-            let sy = if by0 < by1 { 1 } else { -1 };
-            let mut err = dx + dy;
-            let mut x = bx0;
-            let mut y = by0;
-
-            loop {
-                if x >= 0 && x < braille_w as i32 && y >= 0 && y < braille_h as i32 {
-                    braille_dots[y as usize][x as usize] = true;
-                }
-
-                if x == bx1 && y == by1 {
-                    break;
-                }
-
-                let e2 = 2 * err;
-                if e2 >= dy {
-                    if x == bx1 {
-                        break;
-                    }
-                    err += dy;
-                    x += sx;
-                }
-                if e2 <= dx {
-                    if y == by1 {
-                        break;
-                    }
-                    err += dx;
-                    y += sy;
-                }
-            }
+            canvas.line(
+                (x0 * 2.0) as i32,
+                (y0 * 4.0) as i32,
+                (x1 * 2.0) as i32,
+                (y1 * 4.0) as i32,
+                1,
+            );
         }
 
         term.clear();
-        for cy in 0..height as usize {
-            let by = cy * 4;
-            for cx in 0..width as usize {
-                let bx = cx * 2;
-
-                let mut dots: u8 = 0;
-                if braille_dots[by][bx] {
-                    dots |= 0x01;
-                }
-                if braille_dots[by + 1][bx] {
-                    dots |= 0x02;
-                }
-                if braille_dots[by + 2][bx] {
-                    dots |= 0x04;
-                }
-                if braille_dots[by][bx + 1] {
-                    dots |= 0x08;
-                }
-                if braille_dots[by + 1][bx + 1] {
-                    dots |= 0x10;
-                }
-                if braille_dots[by + 2][bx + 1] {
-                    dots |= 0x20;
-                }
-                if braille_dots[by + 3][bx] {
-                    dots |= 0x40;
-                }
-                if braille_dots[by + 3][bx + 1] {
-                    dots |= 0x80;
-                }
-
-                if dots > 0 {
-                    let ch = char::from_u32(0x2800 + dots as u32).unwrap_or(' ');
-                    let (color, bold) = scheme_color(state.color_scheme(), 2, true);
-                    term.set(cx as i32, cy as i32, ch, Some(color), bold);
-                }
-            }
-        }
+        canvas.flush(term, |_| scheme_color(state.color_scheme(), 2, true));
 
         state.render_help(term, width, height);
         term.present()?;
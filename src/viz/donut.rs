@@ -56,7 +56,7 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
         .collect();
 
     loop {
-        let (width, height) = crossterm::terminal::size().unwrap_or(term.size());
+        let (width, height) = term.query_size().unwrap_or(term.size());
 
         if width != prev_w || height != prev_h {
             term.resize(width, height);
@@ -1232,7 +1232,7 @@ pub fn run(config: DygmaConfig) -> io::Result<()> {
     let mut prev_layer_mask: u32 = 1; // Track previous layer to detect changes
 
     loop {
-        let (width, height) = crossterm::terminal::size().unwrap_or(term.size());
+        let (width, height) = term.query_size().unwrap_or(term.size());
 
         if width != prev_w || height != prev_h {
             term.resize(width, height);
@@ -1,6 +1,6 @@
 //! Fire effect visualization (doom-style)
 
-use super::{scheme_color, VizState};
+use super::{scheme_steps, VizState};
 use crate::config::FractalConfig;
 use crate::terminal::Terminal;
 use rand::prelude::*;
@@ -11,8 +11,7 @@ const FIRE_GENERATION_PROBABILITY: f64 = 0.8;
 const HEAT_RANGE_MIN: u8 = 200;
 const HEAT_RANGE_MAX: u8 = 255;
 const DECAY_RANGE_MAX: u16 = 15;
-const INTENSITY_DIVISOR: u8 = 64;
-const INTENSITY_MAX: u8 = 3;
+const HEAT_LEVELS: usize = HEAT_RANGE_MAX as usize + 1;
 const HOT_THRESHOLD: u8 = 200;
 
 /// Run the fire effect visualization
@@ -30,7 +29,7 @@ pub fn run(term: &mut Terminal, config: &FractalConfig, rng: &mut StdRng) -> io:
     let fire_chars = [' ', '.', ':', ';', '*', 'o', 'O', '#', '@', '%'];
 
     loop {
-        let (new_w, new_h) = crossterm::terminal::size().unwrap_or(term.size());
+        let (new_w, new_h) = term.query_size().unwrap_or(term.size());
         if new_w as usize != w || new_h as usize != h {
             w = new_w as usize;
             h = new_h as usize;
@@ -86,15 +85,14 @@ pub fn run(term: &mut Terminal, config: &FractalConfig, rng: &mut StdRng) -> io:
             }
         }
 
-        // Draw to back buffer
+        // Draw to back buffer, one palette entry per heat level
+        let palette = scheme_steps(state.color_scheme(), HEAT_LEVELS);
         for (y, row) in fire.iter().enumerate() {
             for (x, &heat) in row.iter().enumerate() {
                 let char_idx = (heat as usize * (fire_chars.len() - 1)) / 255;
                 let ch = fire_chars[char_idx.min(fire_chars.len() - 1)];
-                let intensity = (heat / INTENSITY_DIVISOR).min(INTENSITY_MAX);
-                let (color, bold) =
-                    scheme_color(state.color_scheme(), intensity, heat > HOT_THRESHOLD);
-                term.set(x as i32, y as i32, ch, Some(color), bold);
+                let color = palette[heat as usize];
+                term.set(x as i32, y as i32, ch, Some(color), heat > HOT_THRESHOLD);
             }
         }
 
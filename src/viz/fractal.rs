@@ -13,16 +13,12 @@
 //! - Q/Esc: Quit
 
 use super::{scheme_color, VizState};
+use crate::canvas::{Canvas, CanvasMode};
 use crate::config::FractalConfig;
 use crate::terminal::Terminal;
 use crossterm::event::KeyCode;
 use std::io;
 
-// Braille constants (2x4 dot grid per character)
-const BRAILLE_BASE: u32 = 0x2800;
-const DOTS_X: usize = 2;
-const DOTS_Y: usize = 4;
-
 // Iteration limits
 const MAX_ITER: u32 = 80;
 
@@ -210,42 +206,6 @@ fn julia_iter(mut zx: f64, mut zy: f64, cx: f64, cy: f64) -> u32 {
     iter
 }
 
-/// Encode 2x4 dot pattern to braille character
-fn encode_braille(dots: &[[bool; DOTS_X]; DOTS_Y]) -> char {
-    // Braille dot positions:
-    // 0 3
-    // 1 4
-    // 2 5
-    // 6 7
-    let mut code: u32 = 0;
-    if dots[0][0] {
-        code |= 1 << 0;
-    }
-    if dots[1][0] {
-        code |= 1 << 1;
-    }
-    if dots[2][0] {
-        code |= 1 << 2;
-    }
-    if dots[0][1] {
-        code |= 1 << 3;
-    }
-    if dots[1][1] {
-        code |= 1 << 4;
-    }
-    if dots[2][1] {
-        code |= 1 << 5;
-    }
-    if dots[3][0] {
-        code |= 1 << 6;
-    }
-    if dots[3][1] {
-        code |= 1 << 7;
-    }
-
-    char::from_u32(BRAILLE_BASE + code).unwrap_or(' ')
-}
-
 /// Help text
 const HELP: crate::help::HelpSpec = crate::help::HelpSpec::pausable(
     "JULIA FRACTAL",
@@ -268,11 +228,11 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
     let mut prev_w = init_w;
     let mut prev_h = init_h;
 
-    // Iteration buffer (stores iteration count per braille dot)
-    let mut iter_grid: Vec<Vec<u32>> = Vec::new();
+    // Stores iteration count + 1 per braille dot; points inside the set stay off
+    let mut canvas = Canvas::new(CanvasMode::Braille, init_w, init_h);
 
     loop {
-        let (width, height) = crossterm::terminal::size().unwrap_or(term.size());
+        let (width, height) = term.query_size().unwrap_or(term.size());
 
         if width != prev_w || height != prev_h {
             term.resize(width, height);
@@ -281,12 +241,8 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
             prev_h = height;
         }
 
-        // Initialize/resize grid
-        let grid_w = width as usize * DOTS_X;
-        let grid_h = height as usize * DOTS_Y;
-        if iter_grid.len() != grid_h || iter_grid.first().map_or(0, |r| r.len()) != grid_w {
-            iter_grid = vec![vec![0; grid_w]; grid_h];
-        }
+        canvas.resize(width, height);
+        let (grid_w, grid_h) = canvas.size();
 
         if let Some((code, mods)) = term.check_key()? {
             match code {
@@ -335,45 +291,22 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
                 let x = (gx as f64 / grid_w as f64 - 0.5) * scale * aspect + fractal.pan_x;
                 let y = (gy as f64 / grid_h as f64 - 0.5) * scale + fractal.pan_y;
 
-                iter_grid[gy][gx] = julia_iter(x, y, fractal.cx, fractal.cy);
+                let iter = julia_iter(x, y, fractal.cx, fractal.cy);
+                // Point is "inside" if it reached max iterations
+                let value = if iter < MAX_ITER { iter as u8 + 1 } else { 0 };
+                canvas.set(gx as i32, gy as i32, value);
             }
         }
 
         // Render to terminal
         term.clear();
 
-        for cy in 0..height as usize {
-            for cx in 0..width as usize {
-                let mut dots = [[false; DOTS_X]; DOTS_Y];
-                let mut max_iter = 0u32;
-
-                // Sample 2x4 braille dots
-                for dy in 0..DOTS_Y {
-                    for dx in 0..DOTS_X {
-                        let gx = cx * DOTS_X + dx;
-                        let gy = cy * DOTS_Y + dy;
-                        if gx < grid_w && gy < grid_h {
-                            let iter = iter_grid[gy][gx];
-                            // Point is "inside" if it reached max iterations
-                            dots[dy][dx] = iter < MAX_ITER;
-                            max_iter = max_iter.max(iter);
-                        }
-                    }
-                }
-
-                let ch = encode_braille(&dots);
-
-                // Color based on iteration count
-                let intensity = if max_iter >= MAX_ITER {
-                    0 // Inside the set
-                } else {
-                    ((max_iter as f32 / MAX_ITER as f32) * 3.0).min(3.0) as u8
-                };
-
-                let (color, bold) = scheme_color(state.color_scheme(), intensity, intensity >= 2);
-                term.set(cx as i32, cy as i32, ch, Some(color), bold);
-            }
-        }
+        canvas.flush(term, |max| {
+            // Color based on iteration count
+            let max_iter = max as u32 - 1;
+            let intensity = ((max_iter as f32 / MAX_ITER as f32) * 3.0).min(3.0) as u8;
+            scheme_color(state.color_scheme(), intensity, intensity >= 2)
+        });
 
         // Show path name (with auto-cycle indicator)
         let auto_indicator = if fractal.auto_cycle { " ⟳" } else { "" };
@@ -1,6 +1,7 @@
 //! Rotating 3D globe with network activity (eDEX-UI style)
 
 use super::{scheme_color, VizState};
+use crate::canvas::{Canvas, CanvasMode};
 use crate::config::FractalConfig;
 use crate::net_geo::ConnectionTracker;
 use crate::terminal::Terminal;
@@ -446,9 +447,7 @@ pub fn run(
     let mut prev_w = init_w;
     let mut prev_h = init_h;
 
-    let mut braille_w = init_w as usize * 2;
-    let mut braille_h = init_h as usize * 4;
-    let mut braille_dots: Vec<Vec<u8>> = vec![vec![0; braille_w]; braille_h];
+    let mut canvas = Canvas::new(CanvasMode::Braille, init_w, init_h);
 
     struct Blip {
         lat: f32,
@@ -488,16 +487,14 @@ pub fn run(
     };
 
     loop {
-        let (width, height) = crossterm::terminal::size().unwrap_or(term.size());
+        let (width, height) = term.query_size().unwrap_or(term.size());
 
         if width != prev_w || height != prev_h {
             term.resize(width, height);
             term.clear_screen()?;
             prev_w = width;
             prev_h = height;
-            braille_w = width as usize * 2;
-            braille_h = height as usize * 4;
-            braille_dots = vec![vec![0; braille_w]; braille_h];
+            canvas.resize(width, height);
         }
 
         let w = width as f32;
@@ -538,11 +535,7 @@ pub fn run(
             continue;
         }
 
-        for row in &mut braille_dots {
-            for cell in row {
-                *cell = 0;
-            }
-        }
+        canvas.clear();
 
         // Calculate solar position for day/night
         let now = std::time::SystemTime::now()
@@ -605,13 +598,8 @@ pub fn run(
                     continue;
                 }
                 if let Some((bx, by, _)) = lat_lon_to_screen(lat, lon) {
-                    if bx >= 0
-                        && bx < braille_w as i32
-                        && by >= 0
-                        && by < braille_h as i32
-                        && braille_dots[by as usize][bx as usize] == 0
-                    {
-                        braille_dots[by as usize][bx as usize] = 1;
+                    if canvas.get(bx, by) == 0 {
+                        canvas.set(bx, by, 1);
                     }
                 }
             }
@@ -626,13 +614,8 @@ pub fn run(
             for lat_deg in -90..=90 {
                 let lat = (lat_deg as f32).to_radians();
                 if let Some((bx, by, _)) = lat_lon_to_screen(lat, lon) {
-                    if bx >= 0
-                        && bx < braille_w as i32
-                        && by >= 0
-                        && by < braille_h as i32
-                        && braille_dots[by as usize][bx as usize] == 0
-                    {
-                        braille_dots[by as usize][bx as usize] = 1;
+                    if canvas.get(bx, by) == 0 {
+                        canvas.set(bx, by, 1);
                     }
                 }
             }
@@ -650,11 +633,9 @@ pub fn run(
                     let lon = lon1 + (lon2 - lon1) * frac;
 
                     if let Some((bx, by, _)) = lat_lon_to_screen(lat, lon) {
-                        if bx >= 0 && bx < braille_w as i32 && by >= 0 && by < braille_h as i32 {
-                            let dl = daylight_level(lon);
-                            let intensity = if dl > 0.7 { 2 } else { 1 };
-                            braille_dots[by as usize][bx as usize] = intensity;
-                        }
+                        let dl = daylight_level(lon);
+                        let intensity = if dl > 0.7 { 2 } else { 1 };
+                        canvas.set(bx, by, intensity);
                     }
                 }
             }
@@ -791,14 +772,7 @@ pub fn run(
 
                 if let Some((bx, by, _)) = lat_lon_to_screen(blip.lat, blip.lon) {
                     for dy in -size..=size {
-                        for dx in -size..=size {
-                            let px = bx + dx;
-                            let py = by + dy;
-                            if px >= 0 && px < braille_w as i32 && py >= 0 && py < braille_h as i32
-                            {
-                                braille_dots[py as usize][px as usize] = 3;
-                            }
-                        }
+                        canvas.line(bx - size, by + dy, bx + size, by + dy, 3);
                     }
                 }
                 new_blips.push(blip);
@@ -823,9 +797,7 @@ pub fn run(
                     let lat_adj = lat + arc_height;
 
                     if let Some((bx, by, _)) = lat_lon_to_screen(lat_adj, lon) {
-                        if bx >= 0 && bx < braille_w as i32 && by >= 0 && by < braille_h as i32 {
-                            braille_dots[by as usize][bx as usize] = 3;
-                        }
+                        canvas.set(bx, by, 3);
                     }
                 }
                 new_arcs.push(arc);
@@ -839,73 +811,27 @@ pub fn run(
             let pulse_size = ((user_pulse.sin() + 1.0) * 2.0 + 2.0) as i32;
 
             if let Some((bx, by, _)) = lat_lon_to_screen(user_lat, user_lon) {
+                // Filled diamond
                 for dy in -pulse_size..=pulse_size {
-                    for dx in -pulse_size..=pulse_size {
-                        if dx.abs() + dy.abs() <= pulse_size {
-                            let px = bx + dx;
-                            let py = by + dy;
-                            if px >= 0 && px < braille_w as i32 && py >= 0 && py < braille_h as i32
-                            {
-                                braille_dots[py as usize][px as usize] = 4;
-                            }
-                        }
-                    }
+                    let half = pulse_size - dy.abs();
+                    canvas.line(bx - half, by + dy, bx + half, by + dy, 4);
                 }
             }
         }
 
-        // Render braille to terminal
         term.clear();
-        for cy in 0..height as usize {
-            let by = cy * 4;
-            if by + 3 >= braille_h {
-                continue;
-            }
-            for cx in 0..width as usize {
-                let bx = cx * 2;
-                if bx + 1 >= braille_w {
-                    continue;
-                }
-
-                let mut dots: u8 = 0;
-                let mut max_intensity: u8 = 0;
-
-                let positions = [
-                    (by, bx),
-                    (by + 1, bx),
-                    (by + 2, bx),
-                    (by, bx + 1),
-                    (by + 1, bx + 1),
-                    (by + 2, bx + 1),
-                    (by + 3, bx),
-                    (by + 3, bx + 1),
-                ];
-                let dot_bits = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80];
-
-                for (i, &(py, px)) in positions.iter().enumerate() {
-                    let val = braille_dots[py][px];
-                    if val > 0 {
-                        dots |= dot_bits[i];
-                        max_intensity = max_intensity.max(val);
-                    }
-                }
-
-                if dots > 0 {
-                    let ch = char::from_u32(0x2800 + dots as u32).unwrap_or(' ');
-                    let (color, bold) = if max_intensity == 4 {
-                        (Color::Yellow, true)
-                    } else {
-                        let intensity = match max_intensity {
-                            1 => 0,
-                            2 => 2,
-                            _ => 3,
-                        };
-                        scheme_color(state.color_scheme(), intensity, max_intensity >= 3)
-                    };
-                    term.set(cx as i32, cy as i32, ch, Some(color), bold);
-                }
+        canvas.flush(term, |max_intensity| {
+            if max_intensity == 4 {
+                (Color::Yellow, true)
+            } else {
+                let intensity = match max_intensity {
+                    1 => 0,
+                    2 => 2,
+                    _ => 3,
+                };
+                scheme_color(state.color_scheme(), intensity, max_intensity >= 3)
             }
-        }
+        });
 
         state.render_help(term, width, height);
         term.present()?;
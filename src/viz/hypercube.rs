@@ -11,6 +11,7 @@
 //! - Q/Esc: Quit
 
 use super::{scheme_color, VizState};
+use crate::canvas::{Canvas, CanvasMode};
 use crate::config::FractalConfig;
 use crate::terminal::Terminal;
 use crossterm::event::KeyCode;
@@ -65,13 +66,6 @@ mod constants {
     /// Aspect ratio correction for terminal characters
     pub const ASPECT_CORRECTION: f32 = 0.5;
 
-    /// Base Unicode code point for braille patterns (U+2800)
-    pub const BRAILLE_BASE: u32 = 0x2800;
-    /// Braille dots per character horizontally
-    pub const BRAILLE_WIDTH: usize = 2;
-    /// Braille dots per character vertically
-    pub const BRAILLE_HEIGHT: usize = 4;
-
     /// Sleep time when paused
     pub const PAUSE_SLEEP: f32 = 0.1;
     /// Time step normalization factor
@@ -189,95 +183,6 @@ fn project_to_2d(coords: &[f32]) -> (f32, f32) {
     }
 }
 
-/// Encode an 8-dot braille pattern from a 2x4 grid of boolean dots.
-///
-/// Braille patterns use Unicode range U+2800-U+28FF where each bit
-/// represents a dot position:
-/// ```text
-/// [0] [3]    bits: 0x01 0x08
-/// [1] [4]          0x02 0x10
-/// [2] [5]          0x04 0x20
-/// [6] [7]          0x40 0x80
-/// ```
-fn encode_braille(
-    dots: &[Vec<bool>],
-    base_y: usize,
-    base_x: usize,
-    max_y: usize,
-    max_x: usize,
-) -> u8 {
-    // Lookup table: [row][col] -> bit pattern
-    // Row 0-2 are sequential bits, row 3 jumps to bits 6,7
-    const BITS: [[u8; 2]; 4] = [
-        [0x01, 0x08], // row 0: bits 0, 3
-        [0x02, 0x10], // row 1: bits 1, 4
-        [0x04, 0x20], // row 2: bits 2, 5
-        [0x40, 0x80], // row 3: bits 6, 7
-    ];
-
-    let mut pattern: u8 = 0;
-    for (row, bits) in BITS.iter().enumerate() {
-        let y = base_y + row;
-        if y < max_y {
-            if base_x < max_x && dots[y][base_x] {
-                pattern |= bits[0];
-            }
-            if base_x + 1 < max_x && dots[y][base_x + 1] {
-                pattern |= bits[1];
-            }
-        }
-    }
-    pattern
-}
-
-/// Draw a line using Bresenham's algorithm into a dot buffer.
-///
-/// Sets all dots along the line from (x0, y0) to (x1, y1) to true,
-/// with bounds checking against the buffer dimensions.
-fn draw_line(
-    dots: &mut [Vec<bool>],
-    x0: i32,
-    y0: i32,
-    x1: i32,
-    y1: i32,
-    width: usize,
-    height: usize,
-) {
-    let dx = (x1 - x0).abs();
-    let dy = -(y1 - y0).abs();
-    let sx = if x0 < x1 { 1 } else { -1 };
-    let sy = if y0 < y1 { 1 } else { -1 };
-    let mut err = dx + dy;
-    let mut x = x0;
-    let mut y = y0;
-
-    loop {
-        if x >= 0 && x < width as i32 && y >= 0 && y < height as i32 {
-            dots[y as usize][x as usize] = true;
-        }
-
-        if x == x1 && y == y1 {
-            break;
-        }
-
-        let e2 = 2 * err;
-        if e2 >= dy {
-            if x == x1 {
-                break;
-            }
-            err += dy;
-            x += sx;
-        }
-        if e2 <= dx {
-            if y == y1 {
-                break;
-            }
-            err += dx;
-            y += sy;
-        }
-    }
-}
-
 /// Help text for hypercube visualization
 const HELP: crate::help::HelpSpec = crate::help::HelpSpec::animated(
     "HYPERCUBE",
@@ -305,9 +210,8 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
     let mut prev_w = init_w;
     let mut prev_h = init_h;
 
-    let mut braille_w = init_w as usize * BRAILLE_WIDTH;
-    let mut braille_h = init_h as usize * BRAILLE_HEIGHT;
-    let mut braille_dots: Vec<Vec<bool>> = vec![vec![false; braille_w]; braille_h];
+    let mut canvas = Canvas::new(CanvasMode::Braille, init_w, init_h);
+    let (dots_x, dots_y) = CanvasMode::Braille.dots();
 
     // Cache geometry - only regenerate when dimensions change
     let mut cached_dimensions = dimensions;
@@ -318,16 +222,14 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
     let mut coord_buffer: Vec<f32> = vec![0.0; MAX_DIMENSIONS];
 
     loop {
-        let (width, height) = crossterm::terminal::size().unwrap_or(term.size());
+        let (width, height) = term.query_size().unwrap_or(term.size());
 
         if width != prev_w || height != prev_h {
             term.resize(width, height);
             term.clear_screen()?;
             prev_w = width;
             prev_h = height;
-            braille_w = width as usize * BRAILLE_WIDTH;
-            braille_h = height as usize * BRAILLE_HEIGHT;
-            braille_dots = vec![vec![false; braille_w]; braille_h];
+            canvas.resize(width, height);
         }
 
         let w = width as f32;
@@ -369,10 +271,7 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
             continue;
         }
 
-        // Clear braille buffer
-        for row in &mut braille_dots {
-            row.fill(false);
-        }
+        canvas.clear();
 
         // Regenerate geometry only when dimensions change
         if dimensions != cached_dimensions {
@@ -447,31 +346,16 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
             let (x0, y0) = projected[v1];
             let (x1, y1) = projected[v2];
 
-            let bx0 = (x0 * BRAILLE_WIDTH as f32) as i32;
-            let by0 = (y0 * BRAILLE_HEIGHT as f32) as i32;
-            let bx1 = (x1 * BRAILLE_WIDTH as f32) as i32;
-            let by1 = (y1 * BRAILLE_HEIGHT as f32) as i32;
+            let bx0 = (x0 * dots_x as f32) as i32;
+            let by0 = (y0 * dots_y as f32) as i32;
+            let bx1 = (x1 * dots_x as f32) as i32;
+            let by1 = (y1 * dots_y as f32) as i32;
 
-            draw_line(&mut braille_dots, bx0, by0, bx1, by1, braille_w, braille_h);
+            canvas.line(bx0, by0, bx1, by1, 1);
         }
 
         term.clear();
-
-        // Render braille characters
-        for cy in 0..height as usize {
-            let by = cy * BRAILLE_HEIGHT;
-            for cx in 0..width as usize {
-                let bx = cx * BRAILLE_WIDTH;
-
-                let dots = encode_braille(&braille_dots, by, bx, braille_h, braille_w);
-
-                if dots > 0 {
-                    let ch = char::from_u32(BRAILLE_BASE + dots as u32).unwrap_or(' ');
-                    let (color, bold) = scheme_color(state.color_scheme(), 2, true);
-                    term.set(cx as i32, cy as i32, ch, Some(color), bold);
-                }
-            }
-        }
+        canvas.flush(term, |_| scheme_color(state.color_scheme(), 2, true));
 
         // Show dimension and zoom indicator
         let dim_text = format!("{}D", dimensions);
@@ -40,7 +40,7 @@ pub fn run(
     loop {
         // Check for terminal resize - uses crossterm directly to get fresh size
         // rather than term.size() which returns the cached internal dimensions
-        let (new_w, new_h) = crossterm::terminal::size().unwrap_or((w as u16, h as u16));
+        let (new_w, new_h) = term.query_size().unwrap_or((w as u16, h as u16));
         if new_w as usize != w || new_h as usize != h {
             w = new_w as usize;
             h = new_h as usize;
@@ -11,17 +11,13 @@
 //! - Q/Esc: Quit
 
 use super::{scheme_color, VizState};
+use crate::canvas::{Canvas, CanvasMode};
 use crate::config::FractalConfig;
 use crate::terminal::Terminal;
 use crossterm::event::KeyCode;
 use crossterm::style::Color;
 use std::io;
 
-// Braille constants (2x4 dot grid per character)
-const BRAILLE_BASE: u32 = 0x2800;
-const DOTS_X: usize = 2;
-const DOTS_Y: usize = 4;
-
 // Trail persistence
 const TRAIL_LENGTH: usize = 256;
 const DECAY_RATE: f32 = 0.97;
@@ -97,42 +93,6 @@ impl LissajousCurve {
     }
 }
 
-/// Encode 2x4 dot pattern to braille character
-fn encode_braille(dots: &[[bool; DOTS_X]; DOTS_Y]) -> char {
-    // Braille dot positions:
-    // 0 3
-    // 1 4
-    // 2 5
-    // 6 7
-    let mut code: u32 = 0;
-    if dots[0][0] {
-        code |= 1 << 0;
-    }
-    if dots[1][0] {
-        code |= 1 << 1;
-    }
-    if dots[2][0] {
-        code |= 1 << 2;
-    }
-    if dots[0][1] {
-        code |= 1 << 3;
-    }
-    if dots[1][1] {
-        code |= 1 << 4;
-    }
-    if dots[2][1] {
-        code |= 1 << 5;
-    }
-    if dots[3][0] {
-        code |= 1 << 6;
-    }
-    if dots[3][1] {
-        code |= 1 << 7;
-    }
-
-    char::from_u32(BRAILLE_BASE + code).unwrap_or(' ')
-}
-
 /// Help text for Lissajous visualization
 const HELP: crate::help::HelpSpec = crate::help::HelpSpec::animated(
     "LISSAJOUS",
@@ -153,9 +113,10 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
 
     // Intensity grid for braille rendering (stores max intensity per dot)
     let mut intensity_grid: Vec<Vec<f32>> = Vec::new();
+    let mut canvas = Canvas::new(CanvasMode::Braille, init_w, init_h);
 
     loop {
-        let (width, height) = crossterm::terminal::size().unwrap_or(term.size());
+        let (width, height) = term.query_size().unwrap_or(term.size());
 
         if width != prev_w || height != prev_h {
             term.resize(width, height);
@@ -163,15 +124,14 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
             prev_w = width;
             prev_h = height;
             // Resize intensity grid
-            let grid_w = width as usize * DOTS_X;
-            let grid_h = height as usize * DOTS_Y;
+            canvas.resize(width, height);
+            let (grid_w, grid_h) = canvas.size();
             intensity_grid = vec![vec![0.0; grid_w]; grid_h];
         }
 
         // Initialize grid if needed
         if intensity_grid.is_empty() {
-            let grid_w = width as usize * DOTS_X;
-            let grid_h = height as usize * DOTS_Y;
+            let (grid_w, grid_h) = canvas.size();
             intensity_grid = vec![vec![0.0; grid_w]; grid_h];
         }
 
@@ -209,8 +169,7 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
         }
 
         // Plot trail points onto intensity grid
-        let grid_w = width as usize * DOTS_X;
-        let grid_h = height as usize * DOTS_Y;
+        let (grid_w, grid_h) = canvas.size();
         let cx = grid_w as f32 / 2.0;
         let cy = grid_h as f32 / 2.0;
         let scale = (cx.min(cy) * 0.85).max(1.0);
@@ -225,39 +184,23 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
             }
         }
 
-        // Render braille characters
-        term.clear();
-
-        for char_y in 0..height as usize {
-            for char_x in 0..width as usize {
-                let mut dots = [[false; DOTS_X]; DOTS_Y];
-                let mut max_intensity: f32 = 0.0;
-
-                for dy in 0..DOTS_Y {
-                    for dx in 0..DOTS_X {
-                        let gx = char_x * DOTS_X + dx;
-                        let gy = char_y * DOTS_Y + dy;
-
-                        if gy < grid_h && gx < grid_w {
-                            let intensity = intensity_grid[gy][gx];
-                            if intensity > 0.1 {
-                                dots[dy][dx] = true;
-                                max_intensity = max_intensity.max(intensity);
-                            }
-                        }
-                    }
-                }
-
-                if max_intensity > 0.1 {
-                    let ch = encode_braille(&dots);
-                    let level = ((max_intensity * 4.0) as u8).min(3);
-                    let (color, bold) =
-                        scheme_color(state.color_scheme(), level, max_intensity > 0.7);
-                    term.set(char_x as i32, char_y as i32, ch, Some(color), bold);
+        // Render braille characters, storing intensity as 0-255 per dot
+        canvas.clear();
+        for (gy, row) in intensity_grid.iter().enumerate() {
+            for (gx, &intensity) in row.iter().enumerate() {
+                if intensity > 0.1 {
+                    canvas.set(gx as i32, gy as i32, (intensity * 255.0) as u8);
                 }
             }
         }
 
+        term.clear();
+        canvas.flush(term, |max| {
+            let max_intensity = max as f32 / 255.0;
+            let level = ((max_intensity * 4.0) as u8).min(3);
+            scheme_color(state.color_scheme(), level, max_intensity > 0.7)
+        });
+
         // Draw harmonic ratio indicator
         let (_, _, name) = HARMONICS[harmonic_idx];
         let label = format!("Lissajous {} [H:cycle ?:help]", name);
@@ -34,8 +34,8 @@ use crate::help::{render_help_spec, HelpSpec};
 use crate::terminal::Terminal;
 use crossterm::event::{KeyCode, KeyModifiers};
 
-// Re-export scheme_color from colors module for viz users
-pub use crate::colors::scheme_color;
+// Re-export scheme sampling from colors module for viz users
+pub use crate::colors::{scheme_color, scheme_ramp, scheme_steps};
 
 /// Default color scheme for all visualizations (7 = mono/white)
 const DEFAULT_COLOR_SCHEME: u8 = 7;
@@ -137,9 +137,36 @@ impl VizState {
 #[cfg(test)]
 mod tests {
     use super::VizState;
+    use crate::config::{FractalConfig, FractalKind};
     use crate::help::HelpSpec;
+    use crate::terminal::{assert_snapshot, run_headless, ScriptedInput};
     use crossterm::event::{KeyCode, KeyModifiers};
 
+    fn seeded_frame(kind: FractalKind, frames: usize) -> String {
+        let config = FractalConfig {
+            kind,
+            time_step: 0.03,
+            seed: Some(7),
+            debug: false,
+        };
+        let (result, frame) = run_headless(48, 16, [ScriptedInput::Idle(frames)], || {
+            crate::fractal::run(config)
+        });
+        result.expect("headless visualization");
+        assert_eq!(frame.presented, frames);
+        frame.text()
+    }
+
+    #[test]
+    fn seeded_plasma_matches_golden_frame() {
+        assert_snapshot("plasma_seed_7", &seeded_frame(FractalKind::Plasma, 3));
+    }
+
+    #[test]
+    fn seeded_fire_matches_golden_frame() {
+        assert_snapshot("fire_seed_7", &seeded_frame(FractalKind::Fire, 5));
+    }
+
     #[test]
     fn handle_key_toggles_help() {
         let mut state = VizState::new(0.03, HelpSpec::animated("TEST", &[]));
@@ -1,6 +1,6 @@
 //! Plasma effect visualization (animated sine waves)
 
-use super::{scheme_color, VizState};
+use super::{scheme_ramp, VizState};
 use crate::config::FractalConfig;
 use crate::terminal::Terminal;
 use rand::prelude::*;
@@ -44,7 +44,7 @@ pub fn run(term: &mut Terminal, config: &FractalConfig, rng: &mut StdRng) -> io:
     };
 
     loop {
-        let (width, height) = crossterm::terminal::size().unwrap_or(term.size());
+        let (width, height) = term.query_size().unwrap_or(term.size());
 
         if width != prev_w || height != prev_h {
             term.resize(width, height);
@@ -100,10 +100,9 @@ pub fn run(term: &mut Terminal, config: &FractalConfig, rng: &mut StdRng) -> io:
                 let char_idx = (normalized * (chars.len() - 1) as f64) as usize;
                 let ch = chars[char_idx.min(chars.len() - 1)];
 
-                let intensity = (normalized * 3.0) as u8;
-                let (color, bold) = scheme_color(state.color_scheme(), intensity, normalized > 0.7);
+                let color = scheme_ramp(state.color_scheme(), normalized as f32);
 
-                term.set(x as i32, y as i32, ch, Some(color), bold);
+                term.set(x as i32, y as i32, ch, Some(color), normalized > 0.7);
             }
         }
 
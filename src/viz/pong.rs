@@ -12,7 +12,6 @@ use crate::help::render_help_spec;
 use crate::terminal::Terminal;
 use crossterm::event::KeyCode;
 use crossterm::style::Color;
-use crossterm::terminal::size;
 use rand::Rng;
 use std::io;
 
@@ -193,7 +192,7 @@ pub fn run(time_step: f32) -> io::Result<()> {
         }
 
         // Handle resize
-        if let Ok((nw, nh)) = size() {
+        if let Ok((nw, nh)) = term.query_size() {
             if nw != w || nh != h {
                 w = nw;
                 h = nh;
@@ -41,7 +41,7 @@ pub fn run(term: &mut Terminal, config: &FractalConfig, rng: &mut StdRng) -> io:
     let mut screen: Vec<Vec<char>> = vec![vec![' '; w]; h];
 
     loop {
-        let (new_w, new_h) = crossterm::terminal::size().unwrap_or((w as u16, h as u16));
+        let (new_w, new_h) = term.query_size().unwrap_or((w as u16, h as u16));
         if new_w as usize != w || new_h as usize != h {
             w = new_w as usize;
             h = new_h as usize;
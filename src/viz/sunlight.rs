@@ -14,7 +14,6 @@ use crate::terminal::Terminal;
 use chrono::{Local, NaiveDate, Timelike};
 use crossterm::event::KeyCode;
 use crossterm::style::Color;
-use crossterm::terminal::size;
 use std::io;
 use std::process::Command;
 use sunrise_sunset_calculator::SunriseSunsetParameters;
@@ -477,7 +476,7 @@ pub fn run(config: SunlightConfig) -> io::Result<()> {
         }
 
         // Handle resize
-        if let Ok((new_w, new_h)) = size() {
+        if let Ok((new_w, new_h)) = term.query_size() {
             if new_w != w || new_h != h {
                 w = new_w;
                 h = new_h;
@@ -10,7 +10,6 @@ use crate::viz::usage::{draw_usage_bar, elapsed_percent, format_duration, text_c
 use crate::viz::VizState;
 use crossterm::event::KeyCode;
 use crossterm::style::Color;
-use crossterm::terminal::size;
 use serde::{Deserialize, Serialize};
 use std::fs::OpenOptions;
 use std::io::{self, BufRead, BufReader, Write as IoWrite};
@@ -713,7 +712,7 @@ pub fn run(config: TokenEaterConfig) -> io::Result<()> {
             }
         }
 
-        if let Ok((new_w, new_h)) = size() {
+        if let Ok((new_w, new_h)) = term.query_size() {
             if new_w != w || new_h != h {
                 w = new_w;
                 h = new_h;
@@ -39,7 +39,7 @@ pub fn run(term: &mut Terminal, config: &FractalConfig) -> io::Result<()> {
     let (mut prev_w, mut prev_h) = term.size();
 
     loop {
-        if let Ok((w, h)) = crossterm::terminal::size() {
+        if let Ok((w, h)) = term.query_size() {
             if w != prev_w || h != prev_h {
                 term.resize(w, h);
                 term.clear_screen()?;
@@ -3,7 +3,6 @@ use crate::help::{render_help_spec, HelpEntry, HelpSpec};
 use crate::terminal::Terminal;
 use crossterm::event::KeyCode;
 use crossterm::style::Color;
-use crossterm::terminal::size;
 use rand::Rng;
 use serde::Deserialize;
 use std::io;
@@ -1019,7 +1018,7 @@ pub fn run(config: WeatherConfig) -> io::Result<()> {
         }
 
         // Handle resize
-        if let Ok((new_w, new_h)) = size() {
+        if let Ok((new_w, new_h)) = term.query_size() {
             let (cur_w, cur_h) = term.size();
             if new_w != cur_w || new_h != cur_h {
                 term.resize(new_w, new_h);
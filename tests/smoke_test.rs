@@ -32,6 +32,11 @@ const SUBCOMMANDS: &[&str] = &[
     "gpu",
     "ps",
     "docker",
+    "cgroups",
+    "sensors",
+    "dash",
+    "export",
+    "replay",
     "weather",
     "pomodoro",
     "fah",
@@ -39,6 +44,7 @@ const SUBCOMMANDS: &[&str] = &[
     "tui-control",
     "claude-tokens",
     "codex-tokens",
+    "theme",
 ];
 
 fn termart() -> Command {
@@ -144,6 +150,9 @@ fn invalid_timing_and_pomodoro_values_are_rejected_without_panicking() {
         &["pomodoro", "--count=4294967295"],
         &["pomodoro", "--work=0"],
         &["pomodoro", "--work=71582789"],
+        &["disk", "--history=60"],
+        &["gpu", "--history=60"],
+        &["ps", "--record-samples=x"],
     ];
 
     for args in cases {